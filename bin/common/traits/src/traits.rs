@@ -1,24 +1,48 @@
+use codec::{Decode, Encode};
 pub use sp_std::vec::Vec;
+use sp_weights::Weight;
+
+/// The key states are written under, e.g. the metadata id of a result.
+pub type StateKey = u64;
 
 pub enum SharedStateError {
 	InputTooLong,
 }
 
 /// The trait that allows us to read some state from some storage
-/// and write some state to it.
+/// and write some state to it, by key.
 /// Should always use the `I` generic to refer to the correct storage.
 pub trait SharedState<I: 'static> {
-	fn read() -> Vec<u8>;
-	fn write(state: Vec<u8>) -> Result<(), SharedStateError>;
+	/// The latest state written under `key`, if any.
+	fn read(key: StateKey) -> Option<Vec<u8>>;
+	/// Write `state` as the latest state under `key`.
+	fn write(key: StateKey, state: Vec<u8>) -> Result<(), SharedStateError>;
+	/// The most writing a state of `len` bytes may consume.
+	fn write_weight(len: u32) -> Weight;
+
+	/// The latest state written under `key`, decoded as a `V`. Returns `None` if there is no
+	/// such state or it is not a `V`.
+	fn read_typed<V: Decode>(key: StateKey) -> Option<V> {
+		Self::read(key).and_then(|state| V::decode(&mut &state[..]).ok())
+	}
+
+	/// Write the encoded `state` as the latest state under `key`.
+	fn write_typed<V: Encode>(key: StateKey, state: &V) -> Result<(), SharedStateError> {
+		Self::write(key, state.encode())
+	}
 }
 
 // Blanket implementation for x-chain pallets on solo chain side.
 impl<I: 'static> SharedState<I> for () {
-	fn read() -> Vec<u8> {
-		Vec::new()
+	fn read(_key: StateKey) -> Option<Vec<u8>> {
+		None
 	}
 
-	fn write(_state: Vec<u8>) -> Result<(), SharedStateError> {
+	fn write(_key: StateKey, _state: Vec<u8>) -> Result<(), SharedStateError> {
 		Ok(())
 	}
+
+	fn write_weight(_len: u32) -> Weight {
+		Weight::zero()
+	}
 }
@@ -0,0 +1,78 @@
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_core::H256;
+
+/// The version of [`CrossChainResult`] produced by this code. Receivers reject other versions,
+/// so it has to be bumped whenever the layout of the type changes.
+pub const CROSS_CHAIN_RESULT_VERSION: u8 = 2;
+
+/// How the round that produced a [`CrossChainResult`] went.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct RoundInfo {
+	/// The block of the sending chain in which the round was settled.
+	pub settled_at: u64,
+	/// The number of participants that committed to an answer.
+	pub participants: u32,
+	/// The number of participants whose answer agreed with the result.
+	pub agreeing: u32,
+}
+
+/// The result of a round of the logic provider, as sent to the bridged chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct CrossChainResult<Hash = H256> {
+	/// The metadata the round was about.
+	pub metadata_id: u64,
+	/// The result the round settled on.
+	pub result_hash: Hash,
+	pub round_info: RoundInfo,
+	/// Increases with every result sent by the same source, so that receivers can reject
+	/// replayed or reordered results. Set by the sender.
+	pub nonce: u64,
+	/// The layout version of this result, see [`CROSS_CHAIN_RESULT_VERSION`].
+	pub version: u8,
+}
+
+impl<Hash> CrossChainResult<Hash> {
+	/// A result in the current version.
+	pub fn new(metadata_id: u64, result_hash: Hash, round_info: RoundInfo) -> Self {
+		Self { metadata_id, result_hash, round_info, nonce: 0, version: CROSS_CHAIN_RESULT_VERSION }
+	}
+
+	/// Whether this result can be understood by this code.
+	pub fn is_supported(&self) -> bool {
+		self.version == CROSS_CHAIN_RESULT_VERSION
+	}
+}
+
+/// Where the result of a round stands on its way to the bridged chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum DeliveryStatus {
+	/// Sending the result failed, it is sent again once the retry delay is over.
+	Pending,
+	/// The result was handed to the bridge as the message with the given hash. It is sent again
+	/// if the delivery is not confirmed within the delivery timeout.
+	Sent([u8; 32]),
+	/// The bridge confirmed that the message carrying the result was delivered.
+	Delivered,
+	/// Sending the result failed, or its delivery was not confirmed, `MaxDeliveryAttempts`
+	/// times.
+	Failed,
+}
+
+/// An entry of the ledger of results the logic provider sent over the bridge. Kept here so that
+/// the bridged chain can decode the entries proven from the state of the sending chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct OutboundResult<Hash = H256> {
+	/// The result the round settled on, as sent to the bridged chain.
+	pub payload: CrossChainResult<Hash>,
+	pub status: DeliveryStatus,
+	/// How many times sending the result was attempted.
+	pub attempts: u32,
+}
+
+impl<Hash> OutboundResult<Hash> {
+	/// Whether the payload was handed to the bridge, i.e. its nonce was used up.
+	pub fn was_sent(&self) -> bool {
+		matches!(self.status, DeliveryStatus::Sent(_) | DeliveryStatus::Delivered)
+	}
+}
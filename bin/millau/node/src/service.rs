@@ -284,6 +284,7 @@ pub fn new_full(
 		use sc_consensus_grandpa::FinalityProofProvider as GrandpaFinalityProofProvider;
 
 		use mmr_rpc::{Mmr, MmrApiServer};
+		use pallet_logic_provider_rpc::{LogicProvider, LogicProviderApiServer};
 		use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 		use sc_consensus_beefy_rpc::{Beefy, BeefyApiServer};
 		use sc_consensus_grandpa_rpc::{Grandpa, GrandpaApiServer};
@@ -309,6 +310,7 @@ pub fn new_full(
 			io.merge(System::new(client.clone(), pool.clone(), DenyUnsafe::No).into_rpc())
 				.map_err(map_err)?;
 			io.merge(TransactionPayment::new(client.clone()).into_rpc()).map_err(map_err)?;
+			io.merge(LogicProvider::new(client.clone()).into_rpc()).map_err(map_err)?;
 			io.merge(
 				Grandpa::new(
 					subscription_executor.clone(),
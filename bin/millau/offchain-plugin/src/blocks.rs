@@ -0,0 +1,90 @@
+use futures::{future, Stream, StreamExt};
+use sc_client_api::BlockchainEvents;
+use sp_api::BlockT;
+use sp_runtime::{traits::Header, SaturatedConversion};
+
+/// A block the plugin acts upon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockEvent<Hash> {
+	/// The number of the block.
+	pub number: u64,
+	/// The hash of the block, to query the runtime at.
+	pub hash: Hash,
+}
+
+impl<Hash> BlockEvent<Hash> {
+	/// The earliest block a transaction submitted on top of this block can be included in.
+	pub fn next_inclusion(&self) -> u64 {
+		self.number.saturating_add(1)
+	}
+
+	/// Whether a transaction submitted on top of this block can still be included in the window
+	/// spanning the blocks `start` to `end`, both included. A window of a single block is only
+	/// reachable from the block right before it.
+	pub fn can_land_within(&self, (start, end): (u64, u64)) -> bool {
+		let inclusion = self.next_inclusion();
+		inclusion >= start && inclusion <= end
+	}
+
+	/// Whether a transaction submitted on top of this block would be included before the window
+	/// starting at block `start` opens.
+	pub fn lands_before(&self, start: u64) -> bool {
+		self.next_inclusion() < start
+	}
+}
+
+/// The new best blocks imported by the client. Commitments and reveals are sent on top of them, so
+/// that they are included as soon as possible.
+pub fn new_best_blocks<B, C>(client: &C) -> impl Stream<Item = BlockEvent<B::Hash>>
+where
+	B: BlockT,
+	C: BlockchainEvents<B>,
+{
+	client.import_notification_stream().filter_map(|notification| {
+		future::ready(notification.is_new_best.then(|| BlockEvent {
+			number: (*notification.header.number()).saturated_into::<u64>(),
+			hash: notification.hash,
+		}))
+	})
+}
+
+/// The blocks finalized by the client.
+pub fn finalized_blocks<B, C>(client: &C) -> impl Stream<Item = BlockEvent<B::Hash>>
+where
+	B: BlockT,
+	C: BlockchainEvents<B>,
+{
+	client.finality_notification_stream().map(|notification| BlockEvent {
+		number: (*notification.header.number()).saturated_into::<u64>(),
+		hash: notification.hash,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::BlockEvent;
+
+	fn at(number: u64) -> BlockEvent<()> {
+		BlockEvent { number, hash: () }
+	}
+
+	#[test]
+	fn one_block_windows_are_reached_from_the_block_before() {
+		assert!(!at(9).can_land_within((11, 11)));
+		assert!(at(10).can_land_within((11, 11)));
+		assert!(!at(11).can_land_within((11, 11)));
+	}
+
+	#[test]
+	fn windows_are_reached_until_their_last_block() {
+		assert!(at(10).can_land_within((11, 16)));
+		assert!(at(15).can_land_within((11, 16)));
+		assert!(!at(16).can_land_within((11, 16)));
+	}
+
+	#[test]
+	fn transactions_land_before_windows_opening_later() {
+		assert!(at(9).lands_before(11));
+		assert!(!at(10).lands_before(11));
+	}
+}
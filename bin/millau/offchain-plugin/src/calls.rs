@@ -1,5 +1,6 @@
 use codec::Encode;
-use primitives::shared::{MapToCall, Pair};
+use crate::PluginError;
+use primitives::shared::{Hash, MapToCall, Pair};
 use runtime_api::ConstructExtrinsicApi;
 use sc_client_api::HeaderBackend;
 use sp_api::{BlockT, ProvideRuntimeApi};
@@ -12,12 +13,12 @@ use std::sync::Arc;
 /// This function is used to send a variant of `MapToCall`
 /// to the runtime. Upon submission, it will be processed by the
 /// respective runtime api impl in `runtime` and dispatched to
-/// the respective pallet.
+/// the respective pallet. Returns the hash of the submitted extrinsic.
 pub fn submit_call<B, C: 'static>(
 	client: Arc<C>,
 	pair: Arc<Pair>,
 	mapped_call: MapToCall,
-) -> Result<(), ()>
+) -> Result<Hash, PluginError>
 where
 	B: BlockT,
 	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
@@ -34,6 +35,6 @@ where
 			payload,
 			signature,
 			pair.public(),
-		)
-		.map_err(|_| ())?
+		)?
+		.map_err(PluginError::from)
 }
@@ -6,15 +6,12 @@ use super::{
 };
 
 use core::time::Duration;
-use primitives::{
-	shared::{Public, PUBLIC_KEY_TYPE_ID},
-	PROCESSING_INTERVAL,
-};
+use primitives::shared::{Public, PUBLIC_KEY_TYPE_ID};
 use sc_client_db::offchain::LocalStorage;
 use sc_keystore::LocalKeystore;
 use serde_json::{Map, Value};
 
-use futures::StreamExt;
+use futures::{Stream, StreamExt};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 pub const CONFIG_ACCOUNT_ID: &str = "config_account_id";
@@ -72,16 +69,15 @@ pub async fn set_offchain_data(
 
 /// Runs config update task.
 ///
-/// It updates the existing config, wrapped by a `Mutex`, every [`PROCESSING_INTERVAL`] seconds.
-/// Other services that use the config will also be able to use the updated config.
+/// It updates the existing config, wrapped by a `Mutex`, on every item of `blocks`, e.g. every
+/// finalized block. Other services that use the config will also be able to use the updated
+/// config.
 pub async fn schedule_config_update(
 	offchain_storage: &Arc<Mutex<LocalStorage>>,
 	config: Arc<Mutex<Map<String, Value>>>,
+	blocks: impl Stream,
 ) {
-	// NOTE: set the interval to the same interval as in the client module
-	// This will ensure consistent value updates
-	let interval = tokio::time::interval(PROCESSING_INTERVAL);
-	tokio_stream::wrappers::IntervalStream::new(interval)
+	blocks
 		.for_each(|_| {
 			let config = config.clone();
 			async move {
@@ -96,20 +92,38 @@ pub async fn schedule_config_update(
 #[cfg(test)]
 mod tests {
 	use crate::config::{
-		config_provider::{get_config, CONFIG_ACCOUNT_ID},
+		config_provider::{get_config, schedule_config_update, CONFIG_ACCOUNT_ID},
 		offchain_config::OFFCHAIN_KEY,
 		test_utils::{
 			create_keystore, create_local_storage, KEYS_LIST, PUBLIC_KEY, TEST_KEY, TEST_KEY_STR,
 			TEST_VALUE,
 		},
 	};
+	use futures::stream;
 	use primitives::shared::PUBLIC_KEY_TYPE_ID;
+	use serde_json::Map;
 	use sp_core::{
 		crypto::Ss58Codec,
 		offchain::{OffchainStorage, STORAGE_PREFIX},
 		sr25519::Public,
 	};
 	use std::sync::Arc;
+	use tokio::sync::Mutex;
+
+	#[tokio::test]
+	async fn test_config_is_refreshed_on_blocks() {
+		let storage = create_local_storage();
+		storage.lock().await.set(STORAGE_PREFIX, OFFCHAIN_KEY, KEYS_LIST);
+		let config = Arc::new(Mutex::new(Map::new()));
+
+		// Nothing is refreshed without blocks
+		schedule_config_update(&storage, config.clone(), stream::empty::<()>()).await;
+		assert!(!config.lock().await.contains_key(TEST_KEY_STR));
+
+		storage.lock().await.set(STORAGE_PREFIX, TEST_KEY, TEST_VALUE);
+		schedule_config_update(&storage, config.clone(), stream::iter([()])).await;
+		assert!(config.lock().await.contains_key(TEST_KEY_STR));
+	}
 
 	#[tokio::test]
 	async fn test_get_config_success() {
@@ -2,7 +2,7 @@ pub mod config_provider;
 pub mod key_mgmt;
 pub mod offchain_config;
 #[cfg(test)]
-mod test_utils;
+pub(crate) mod test_utils;
 
 use crate::{config::config_provider::CONFIG_ACCOUNT_ID, PluginError};
 use primitives::shared::{Pair, Public};
@@ -0,0 +1,268 @@
+//! Canonical JSON, so that equivalent documents hash the same.
+//!
+//! On top of dropping insignificant whitespace and sorting the keys of every object (bytewise), the
+//! canonical form normalizes:
+//! - numbers: integral values, e.g. `6`, `6.0` or `60e-1`, are written as integers, other values
+//!   as the shortest representation of the closest `f64`;
+//! - timestamps: strings holding a timestamp in one of the [`TIMESTAMP_FORMATS`] or in RFC 3339
+//!   are converted to UTC and written in RFC 3339, e.g. `2020-05-26T15:36:18Z`.
+
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use serde_json::{Number, Value};
+
+/// Timestamp formats without a time zone, taken as UTC. Matches `mock/metadatas.json`.
+pub const TIMESTAMP_FORMATS: &[&str] = &["%d-%m-%Y %H:%M:%S", "%Y-%m-%d %H:%M:%S"];
+
+/// Serialize `value` as canonical JSON.
+pub fn canonical_json(value: &Value) -> Vec<u8> {
+	let mut out = Vec::new();
+	write_canonical_json(value, &mut out);
+	out
+}
+
+/// Parse `text` as a timestamp in one of the [`TIMESTAMP_FORMATS`] or in RFC 3339.
+pub fn parse_timestamp(text: &str) -> Option<DateTime<Utc>> {
+	if let Ok(timestamp) = DateTime::parse_from_rfc3339(text) {
+		return Some(timestamp.with_timezone(&Utc))
+	}
+	TIMESTAMP_FORMATS.iter().find_map(|format| {
+		NaiveDateTime::parse_from_str(text, format)
+			.ok()
+			.map(|timestamp| DateTime::from_naive_utc_and_offset(timestamp, Utc))
+	})
+}
+
+fn write_canonical_json(value: &Value, out: &mut Vec<u8>) {
+	match value {
+		Value::Array(items) => {
+			out.push(b'[');
+			for (index, item) in items.iter().enumerate() {
+				if index > 0 {
+					out.push(b',');
+				}
+				write_canonical_json(item, out);
+			}
+			out.push(b']');
+		},
+		Value::Object(object) => {
+			let mut entries = object.iter().collect::<Vec<_>>();
+			entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+			out.push(b'{');
+			for (index, (key, item)) in entries.into_iter().enumerate() {
+				if index > 0 {
+					out.push(b',');
+				}
+				write_string(key, out);
+				out.push(b':');
+				write_canonical_json(item, out);
+			}
+			out.push(b'}');
+		},
+		Value::Number(number) => write_number(number, out),
+		Value::String(text) => match parse_timestamp(text) {
+			Some(timestamp) =>
+				write_string(&timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true), out),
+			None => write_string(text, out),
+		},
+		literal => out.extend_from_slice(literal.to_string().as_bytes()),
+	}
+}
+
+fn write_string(text: &str, out: &mut Vec<u8>) {
+	out.extend_from_slice(Value::from(text).to_string().as_bytes());
+}
+
+fn write_number(number: &Number, out: &mut Vec<u8>) {
+	if number.is_u64() || number.is_i64() {
+		out.extend_from_slice(number.to_string().as_bytes());
+		return
+	}
+	// Every integral `f64` within the range of `i64` and `u64` converts exactly
+	let integral = number.as_f64().filter(|float| {
+		float.fract() == 0.0 && *float >= i64::MIN as f64 && *float < u64::MAX as f64
+	});
+	match integral {
+		Some(float) => out.extend_from_slice((float as i128).to_string().as_bytes()),
+		None => out.extend_from_slice(number.to_string().as_bytes()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::prelude::*;
+	use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+	use sp_io::hashing::blake2_256;
+	use std::collections::BTreeMap;
+
+	fn canonical(text: &str) -> String {
+		String::from_utf8(canonical_json(&serde_json::from_str(text).unwrap())).unwrap()
+	}
+
+	#[test]
+	fn keys_are_sorted_and_whitespace_dropped() {
+		assert_eq!(
+			canonical("{ \"b\": [1, {\"d\": null, \"c\": \"x y\"}],\n \"a\": true }"),
+			r#"{"a":true,"b":[1,{"c":"x y","d":null}]}"#
+		);
+	}
+
+	#[test]
+	fn numbers_are_normalized() {
+		assert_eq!(
+			canonical("[6, 6.0, 60e-1, 0.6E1, -0.0, 1e19]"),
+			"[6,6,6,6,0,10000000000000000000]"
+		);
+		assert_eq!(canonical("[1.50, 15e-1, 0.1, 1e300]"), "[1.5,1.5,0.1,1e300]");
+	}
+
+	#[test]
+	fn timestamps_are_normalized() {
+		assert_eq!(
+			canonical(
+				r#"["26-05-2020 15:36:18", "2020-05-26 15:36:18", "2020-05-26T17:36:18+02:00",
+				"2020-05-26T15:36:18.000Z", "2020-05-26T15:36:18.5Z"]"#
+			),
+			format!("[{}\"2020-05-26T15:36:18.500Z\"]", "\"2020-05-26T15:36:18Z\",".repeat(4))
+		);
+		// Strings that only look like timestamps are left alone
+		assert_eq!(
+			canonical(r#"["26-05-2020", "32-05-2020 15:36:18"]"#),
+			r#"["26-05-2020","32-05-2020 15:36:18"]"#
+		);
+	}
+
+	#[test]
+	fn mock_metadatas_are_canonical_after_normalization() {
+		let mock = std::fs::read_to_string("mock/metadatas.json").unwrap();
+		let canonical_mock = canonical(&mock);
+		assert_eq!(canonical(&canonical_mock), canonical_mock);
+		assert!(canonical_mock.contains(r#""submissionTimestamp":"2020-05-26T15:36:18Z""#));
+	}
+
+	/// A JSON document, from which equivalent texts are rendered.
+	#[derive(Clone, Debug)]
+	enum Document {
+		Null,
+		Bool(bool),
+		Integer(i64),
+		/// `mantissa * 10^exponent`
+		Decimal(i32, i8),
+		Text(String),
+		/// Seconds since the Unix epoch.
+		Timestamp(i64),
+		Array(Vec<Document>),
+		Object(BTreeMap<String, Document>),
+	}
+
+	fn document() -> impl Strategy<Value = Document> {
+		let leaf = prop_oneof![
+			Just(Document::Null),
+			any::<bool>().prop_map(Document::Bool),
+			// Small enough for every rendering to parse exactly
+			(-(1i64 << 46)..(1i64 << 46)).prop_map(Document::Integer),
+			(any::<i32>(), -6i8..=6).prop_map(|(mantissa, exponent)| {
+				Document::Decimal(mantissa, exponent)
+			}),
+			"[a-zA-Z _\"\\\\é]{0,8}".prop_map(Document::Text),
+			(0i64..4_000_000_000).prop_map(Document::Timestamp),
+		];
+		leaf.prop_recursive(4, 64, 8, |inner| {
+			prop_oneof![
+				prop::collection::vec(inner.clone(), 0..8).prop_map(Document::Array),
+				prop::collection::btree_map("[a-zA-Z]{1,6}", inner, 0..8)
+					.prop_map(Document::Object),
+			]
+		})
+	}
+
+	/// Render `document` as JSON text, picking the whitespace, the order of the keys and the
+	/// representation of numbers, strings and timestamps at random.
+	fn render(document: &Document, rng: &mut StdRng) -> String {
+		let space = |rng: &mut StdRng| [" ", "", "\n\t", "  "].choose(rng).unwrap().to_string();
+		match document {
+			Document::Null => "null".into(),
+			Document::Bool(value) => value.to_string(),
+			Document::Integer(value) => match rng.gen_range(0..4) {
+				0 => value.to_string(),
+				1 => format!("{}.0", value),
+				2 => format!("{}0e-1", value),
+				_ => format!("{}.00E+0", value),
+			},
+			Document::Decimal(mantissa, exponent) => match rng.gen_range(0..3) {
+				0 => format!("{}e{}", mantissa, exponent),
+				1 => format!("{}0e{}", mantissa, exponent - 1),
+				_ => format!("{}.0E{}", mantissa, exponent),
+			},
+			Document::Text(text) => {
+				let mut rendered = String::from("\"");
+				for character in text.chars() {
+					match character {
+						'"' => rendered.push_str("\\\""),
+						'\\' => rendered.push_str("\\\\"),
+						_ if rng.gen_bool(0.2) =>
+							rendered.push_str(&format!("\\u{:04x}", character as u32)),
+						_ => rendered.push(character),
+					}
+				}
+				rendered.push('"');
+				rendered
+			},
+			Document::Timestamp(seconds) => {
+				let timestamp = DateTime::from_timestamp(*seconds, 0).unwrap();
+				match rng.gen_range(0..4) {
+					0 => format!("\"{}\"", timestamp.format("%d-%m-%Y %H:%M:%S")),
+					1 => format!("\"{}\"", timestamp.format("%Y-%m-%d %H:%M:%S")),
+					2 => format!("\"{}\"", timestamp.format("%Y-%m-%dT%H:%M:%SZ")),
+					_ => format!(
+						"\"{}\"",
+						timestamp
+							.with_timezone(&chrono::FixedOffset::east_opt(5400).unwrap())
+							.format("%Y-%m-%dT%H:%M:%S%:z")
+					),
+				}
+			},
+			Document::Array(items) => {
+				let items =
+					items.iter().map(|item| render(item, rng) + &space(rng)).collect::<Vec<_>>();
+				format!("[{}{}]", space(rng), items.join(","))
+			},
+			Document::Object(entries) => {
+				let mut entries = entries.iter().collect::<Vec<_>>();
+				entries.shuffle(rng);
+				let entries = entries
+					.into_iter()
+					.map(|(key, item)| {
+						format!("\"{}\"{}:{}{}", key, space(rng), space(rng), render(item, rng))
+					})
+					.collect::<Vec<_>>();
+				format!("{{{}{}}}", space(rng), entries.join(","))
+			},
+		}
+	}
+
+	proptest! {
+		#[test]
+		fn equivalent_documents_hash_the_same(
+			document in document(),
+			first_seed in any::<u64>(),
+			second_seed in any::<u64>(),
+		) {
+			let first = render(&document, &mut StdRng::seed_from_u64(first_seed));
+			let second = render(&document, &mut StdRng::seed_from_u64(second_seed));
+			let first_hash = blake2_256(&canonical_json(&serde_json::from_str(&first).unwrap()));
+			let second_hash = blake2_256(&canonical_json(&serde_json::from_str(&second).unwrap()));
+			prop_assert_eq!(first_hash, second_hash, "{} and {}", first, second);
+		}
+
+		#[test]
+		fn canonical_json_is_a_fixed_point(document in document(), seed in any::<u64>()) {
+			let rendered = render(&document, &mut StdRng::seed_from_u64(seed));
+			let canonical = canonical_json(&serde_json::from_str(&rendered).unwrap());
+			prop_assert_eq!(
+				canonical_json(&serde_json::from_slice(&canonical).unwrap()),
+				canonical
+			);
+		}
+	}
+}
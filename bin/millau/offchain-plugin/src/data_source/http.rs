@@ -0,0 +1,108 @@
+use super::{canonical_json, parse_json, DataSource, DataSourceError};
+use async_trait::async_trait;
+use hyper::{Client, Uri};
+use std::time::Duration;
+
+/// How long a request, including reading the response, may take.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A JSON document served over plain HTTP, turned into canonical JSON.
+pub struct HttpSource {
+	uri: Uri,
+	validate_schema: bool,
+}
+
+impl HttpSource {
+	pub fn new(url: &str) -> Result<Self, DataSourceError> {
+		let uri = url.parse::<Uri>().map_err(|_| DataSourceError::InvalidUrl(url.to_string()))?;
+		if uri.scheme_str() != Some("http") {
+			return Err(DataSourceError::InvalidUrl(url.to_string()))
+		}
+		Ok(Self { uri, validate_schema: false })
+	}
+
+	/// Whether to check the served document against the metadata schema.
+	pub fn validate_schema(mut self, validate_schema: bool) -> Self {
+		self.validate_schema = validate_schema;
+		self
+	}
+
+	async fn request(&self) -> Result<Vec<u8>, DataSourceError> {
+		let response = Client::new()
+			.get(self.uri.clone())
+			.await
+			.map_err(|err| DataSourceError::Http(err.to_string()))?;
+		if !response.status().is_success() {
+			return Err(DataSourceError::HttpStatus(response.status().as_u16()))
+		}
+		let body = hyper::body::to_bytes(response.into_body())
+			.await
+			.map_err(|err| DataSourceError::Http(err.to_string()))?;
+		Ok(canonical_json(&parse_json(&body, self.validate_schema)?))
+	}
+}
+
+#[async_trait]
+impl DataSource for HttpSource {
+	async fn fetch(&self) -> Result<Vec<u8>, DataSourceError> {
+		tokio::time::timeout(REQUEST_TIMEOUT, self.request())
+			.await
+			.map_err(|_| DataSourceError::Http("request timed out".to_string()))?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tokio::{
+		io::{AsyncReadExt, AsyncWriteExt},
+		net::TcpListener,
+	};
+
+	/// Answer the first request made to the returned URL with `status` and `body`.
+	async fn serve_once(status: &'static str, body: &'static str) -> String {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let address = listener.local_addr().unwrap();
+		tokio::spawn(async move {
+			let (mut socket, _) = listener.accept().await.unwrap();
+			let mut request = [0u8; 1024];
+			let _ = socket.read(&mut request).await;
+			let response = format!(
+				"HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+				status,
+				body.len(),
+				body
+			);
+			socket.write_all(response.as_bytes()).await.unwrap();
+		});
+		format!("http://{}/metadatas", address)
+	}
+
+	#[tokio::test]
+	async fn served_json_is_canonicalized() {
+		let url = serve_once("200 OK", "{ \"nmi\": \"kj2kh3b452k3h4\", \"limit\": 6 }").await;
+		let fetched = HttpSource::new(&url).unwrap().fetch().await.unwrap();
+		assert_eq!(fetched, br#"{"limit":6,"nmi":"kj2kh3b452k3h4"}"#.to_vec());
+	}
+
+	#[tokio::test]
+	async fn failed_requests_are_reported() {
+		let url = serve_once("404 Not Found", "").await;
+		let fetched = HttpSource::new(&url).unwrap().fetch().await;
+		assert!(matches!(fetched, Err(DataSourceError::HttpStatus(404))));
+
+		let url = serve_once("200 OK", "not json").await;
+		let fetched = HttpSource::new(&url).unwrap().fetch().await;
+		assert!(matches!(fetched, Err(DataSourceError::InvalidJson(_))));
+	}
+
+	#[test]
+	fn only_http_urls_are_accepted() {
+		assert!(HttpSource::new("http://localhost:8000/metadatas").is_ok());
+		assert!(matches!(
+			HttpSource::new("https://localhost:8000/metadatas"),
+			Err(DataSourceError::InvalidUrl(_))
+		));
+		assert!(matches!(HttpSource::new("metadatas"), Err(DataSourceError::InvalidUrl(_))));
+	}
+}
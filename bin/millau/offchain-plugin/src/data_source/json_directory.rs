@@ -0,0 +1,72 @@
+use super::{canonical_json, parse_json, DataSource, DataSourceError};
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+
+/// Every file with a `json` extension directly within a local directory. They are combined into
+/// one JSON object keyed by file name, in canonical JSON, so neither the order in which the
+/// directory lists them nor their formatting matter.
+pub struct JsonDirectorySource {
+	path: PathBuf,
+	validate_schema: bool,
+}
+
+impl JsonDirectorySource {
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into(), validate_schema: false }
+	}
+
+	/// Whether to check every file against the metadata schema.
+	pub fn validate_schema(mut self, validate_schema: bool) -> Self {
+		self.validate_schema = validate_schema;
+		self
+	}
+}
+
+#[async_trait]
+impl DataSource for JsonDirectorySource {
+	async fn fetch(&self) -> Result<Vec<u8>, DataSourceError> {
+		let mut files = Map::new();
+		let mut entries = tokio::fs::read_dir(&self.path).await?;
+		while let Some(entry) = entries.next_entry().await? {
+			let path = entry.path();
+			if !entry.file_type().await?.is_file() ||
+				path.extension().and_then(|extension| extension.to_str()) != Some("json")
+			{
+				continue
+			}
+			let contents = tokio::fs::read(&path).await?;
+			let name = entry.file_name().to_string_lossy().into_owned();
+			files.insert(name, parse_json(&contents, self.validate_schema)?);
+		}
+
+		if files.is_empty() {
+			return Err(DataSourceError::NoData)
+		}
+		Ok(canonical_json(&Value::Object(files)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn json_files_are_combined_by_name() {
+		let fetched = JsonDirectorySource::new("mock/metadatas").fetch().await.unwrap();
+		let expected = serde_json::json!({
+			"first.json": { "nmi": "kj2kh3b452k3h4", "activePowerExportLimit": 6 },
+			"second.json": { "nmi": "l3k4j5h6g7f8d9", "activePowerExportLimit": 8 },
+		});
+		assert_eq!(fetched, canonical_json(&expected));
+	}
+
+	#[tokio::test]
+	async fn directories_without_json_files_are_rejected() {
+		let fetched = JsonDirectorySource::new("src/config").fetch().await;
+		assert!(matches!(fetched, Err(DataSourceError::NoData)));
+
+		let fetched = JsonDirectorySource::new("notHere").fetch().await;
+		assert!(matches!(fetched, Err(DataSourceError::Io(_))));
+	}
+}
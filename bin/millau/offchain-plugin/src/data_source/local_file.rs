@@ -0,0 +1,66 @@
+use super::{canonical_json, parse_json, DataSource, DataSourceError};
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+/// The contents of a single local file. Files with a `json` extension, or any file once the schema
+/// is validated, are turned into canonical JSON; other files are taken as they are.
+pub struct LocalFileSource {
+	path: PathBuf,
+	validate_schema: bool,
+}
+
+impl LocalFileSource {
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into(), validate_schema: false }
+	}
+
+	/// Whether to check the file against the metadata schema.
+	pub fn validate_schema(mut self, validate_schema: bool) -> Self {
+		self.validate_schema = validate_schema;
+		self
+	}
+}
+
+#[async_trait]
+impl DataSource for LocalFileSource {
+	async fn fetch(&self) -> Result<Vec<u8>, DataSourceError> {
+		let contents = tokio::fs::read(&self.path).await?;
+		let extension = self.path.extension().and_then(|extension| extension.to_str());
+		let is_json = extension == Some("json");
+		if is_json || self.validate_schema {
+			Ok(canonical_json(&parse_json(&contents, self.validate_schema)?))
+		} else {
+			Ok(contents)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn json_files_are_canonicalized() {
+		let path = std::env::temp_dir().join("offchain-plugin-local-file-source.json");
+		std::fs::write(&path, "{\n  \"b\": 2,\n  \"a\": 1\n}\n").unwrap();
+
+		let fetched = LocalFileSource::new(&path).fetch().await.unwrap();
+		assert_eq!(fetched, br#"{"a":1,"b":2}"#.to_vec());
+		std::fs::remove_file(path).unwrap();
+	}
+
+	#[tokio::test]
+	async fn other_files_are_taken_as_they_are() {
+		let fetched = LocalFileSource::new("README.md").fetch().await.unwrap();
+		assert_eq!(fetched, std::fs::read("README.md").unwrap());
+	}
+
+	#[tokio::test]
+	async fn unreadable_files_are_reported() {
+		let fetched = LocalFileSource::new("notHere.json").fetch().await;
+		assert!(matches!(fetched, Err(DataSourceError::Io(_))));
+
+		let fetched = LocalFileSource::new("mock/invalid.json").fetch().await;
+		assert!(matches!(fetched, Err(DataSourceError::InvalidJson(_))));
+	}
+}
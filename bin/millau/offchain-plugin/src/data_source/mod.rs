@@ -0,0 +1,163 @@
+//! Sources of the offchain data the plugin commits to.
+//!
+//! Every source normalizes what it fetches, e.g. into canonical JSON, so that plugins reading the
+//! same data commit to the same hash however it happens to be formatted. JSON sources can also
+//! check the documents they fetch against the metadata [`schema`].
+
+mod canonical;
+mod http;
+mod json_directory;
+mod local_file;
+pub mod schema;
+
+pub use canonical::{canonical_json, parse_timestamp, TIMESTAMP_FORMATS};
+pub use http::HttpSource;
+pub use json_directory::JsonDirectorySource;
+pub use local_file::LocalFileSource;
+
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use sp_core::H256;
+use sp_io::hashing::blake2_256;
+
+/// Config key selecting the data source: `local_file` (the default), `json_directory` or `http`.
+pub const DATA_SOURCE_KEY: &str = "data_source";
+/// Config key of the file read by the `local_file` source.
+pub const LOCAL_FILE_PATH_KEY: &str = "local_file_path";
+/// Config key of the directory read by the `json_directory` source.
+pub const JSON_DIRECTORY_PATH_KEY: &str = "json_directory_path";
+/// Config key of the URL queried by the `http` source.
+pub const HTTP_URL_KEY: &str = "http_url";
+/// Config key enabling the [`schema`] validation of JSON documents when set to `true`.
+pub const VALIDATE_SCHEMA_KEY: &str = "validate_schema";
+
+#[derive(Debug)]
+pub enum DataSourceError {
+	/// A config key the selected source needs is not set, or is not a string.
+	MissingConfig(&'static str),
+	/// The configured `data_source` is not one of the built-in sources.
+	UnknownSource(String),
+	/// Reading a file or a directory failed.
+	Io(std::io::Error),
+	/// The data is not valid JSON.
+	InvalidJson(serde_json::Error),
+	/// The document does not match the metadata schema, and why.
+	InvalidSchema(String),
+	/// The source holds no data to commit to.
+	NoData,
+	/// The configured URL could not be parsed.
+	InvalidUrl(String),
+	/// The HTTP request failed or timed out.
+	Http(String),
+	/// The HTTP endpoint answered with a status other than success.
+	HttpStatus(u16),
+}
+
+impl From<std::io::Error> for DataSourceError {
+	fn from(err: std::io::Error) -> Self {
+		DataSourceError::Io(err)
+	}
+}
+
+impl From<serde_json::Error> for DataSourceError {
+	fn from(err: serde_json::Error) -> Self {
+		DataSourceError::InvalidJson(err)
+	}
+}
+
+/// Where the plugin gets the offchain data it commits to.
+#[async_trait]
+pub trait DataSource: Send + Sync {
+	/// Fetch the data, normalized.
+	async fn fetch(&self) -> Result<Vec<u8>, DataSourceError>;
+
+	/// The hash of the normalized data. In this case we know that the `Hash` type configured in
+	/// the runtime is H256. This will need to be updated should the configured hash type in the
+	/// Runtime change.
+	async fn hash(&self) -> Result<H256, DataSourceError> {
+		Ok(H256(blake2_256(&self.fetch().await?)))
+	}
+}
+
+/// Build the source selected by the `data_source` key of the operator's `config`.
+pub fn from_config(config: &Map<String, Value>) -> Result<Box<dyn DataSource>, DataSourceError> {
+	let kind = match config.get(DATA_SOURCE_KEY) {
+		Some(kind) => kind.as_str().ok_or(DataSourceError::MissingConfig(DATA_SOURCE_KEY))?,
+		None => "local_file",
+	};
+	let validate_schema = config.get(VALIDATE_SCHEMA_KEY).and_then(Value::as_str) == Some("true");
+	match kind {
+		"local_file" => Ok(Box::new(
+			LocalFileSource::new(config_value(config, LOCAL_FILE_PATH_KEY)?)
+				.validate_schema(validate_schema),
+		)),
+		"json_directory" => Ok(Box::new(
+			JsonDirectorySource::new(config_value(config, JSON_DIRECTORY_PATH_KEY)?)
+				.validate_schema(validate_schema),
+		)),
+		"http" => Ok(Box::new(
+			HttpSource::new(config_value(config, HTTP_URL_KEY)?)?.validate_schema(validate_schema),
+		)),
+		other => Err(DataSourceError::UnknownSource(other.to_string())),
+	}
+}
+
+fn config_value<'a>(
+	config: &'a Map<String, Value>,
+	key: &'static str,
+) -> Result<&'a str, DataSourceError> {
+	config.get(key).and_then(Value::as_str).ok_or(DataSourceError::MissingConfig(key))
+}
+
+/// Parse a JSON `document`, checking it against the metadata [`schema`] if `validate_schema`.
+fn parse_json(document: &[u8], validate_schema: bool) -> Result<Value, DataSourceError> {
+	let document = serde_json::from_slice(document)?;
+	if validate_schema {
+		schema::validate(&document)?;
+	}
+	Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn sources_are_selected_through_the_config() {
+		let config = |entries: Value| entries.as_object().unwrap().clone();
+
+		assert!(matches!(
+			from_config(&config(json!({}))),
+			Err(DataSourceError::MissingConfig(LOCAL_FILE_PATH_KEY))
+		));
+		assert!(from_config(&config(json!({ "local_file_path": "mock/metadatas.json" }))).is_ok());
+		assert!(matches!(
+			from_config(&config(json!({ "data_source": "json_directory" }))),
+			Err(DataSourceError::MissingConfig(JSON_DIRECTORY_PATH_KEY))
+		));
+		assert!(matches!(
+			from_config(&config(json!({ "data_source": "http", "http_url": "not a url" }))),
+			Err(DataSourceError::InvalidUrl(_))
+		));
+		assert!(matches!(
+			from_config(&config(json!({ "data_source": "ftp" }))),
+			Err(DataSourceError::UnknownSource(kind)) if kind == "ftp"
+		));
+	}
+
+	#[tokio::test]
+	async fn schema_validation_is_enabled_through_the_config() {
+		let config = |validate_schema: &str| {
+			let mut config = json!({ "local_file_path": "mock/metadatas/first.json" });
+			config[VALIDATE_SCHEMA_KEY] = validate_schema.into();
+			config.as_object().unwrap().clone()
+		};
+
+		assert!(from_config(&config("false")).unwrap().hash().await.is_ok());
+		assert!(matches!(
+			from_config(&config("true")).unwrap().hash().await,
+			Err(DataSourceError::InvalidSchema(_))
+		));
+	}
+}
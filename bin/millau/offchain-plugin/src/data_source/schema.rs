@@ -0,0 +1,139 @@
+//! The layout of metadata documents, as in `mock/metadatas.json`. Checked before hashing when the
+//! operator enables `validate_schema`, so that malformed data is never committed to.
+
+use super::{canonical::parse_timestamp, DataSourceError};
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+/// Check that `document` is a metadata document:
+/// - a `participantId` string, a `submissionTimestamp` and a non-empty `metadatas` array;
+/// - every metadata has an `nmi` string and a non-empty `intervals` array;
+/// - every interval has the `activePowerExportLimit` and `activePowerImportLimit` numbers, and a
+///   `diStarttime` no later than its `diEndtime`.
+///
+/// Timestamps may be in any format understood by [`parse_timestamp`]. Other fields are allowed.
+pub fn validate(document: &Value) -> Result<(), DataSourceError> {
+	let document = object(document, "document")?;
+	string(document, "participantId", "document")?;
+	timestamp(document, "submissionTimestamp", "document")?;
+	let metadatas = non_empty_array(document, "metadatas", "document")?;
+	for (index, metadata) in metadatas.iter().enumerate() {
+		let path = format!("metadatas[{}]", index);
+		let metadata = object(metadata, &path)?;
+		string(metadata, "nmi", &path)?;
+		let intervals = non_empty_array(metadata, "intervals", &path)?;
+		for (index, interval) in intervals.iter().enumerate() {
+			let path = format!("{}.intervals[{}]", path, index);
+			let interval = object(interval, &path)?;
+			number(interval, "activePowerExportLimit", &path)?;
+			number(interval, "activePowerImportLimit", &path)?;
+			let start = timestamp(interval, "diStarttime", &path)?;
+			if start > timestamp(interval, "diEndtime", &path)? {
+				return Err(invalid(&path, "diStarttime is after diEndtime"))
+			}
+		}
+	}
+	Ok(())
+}
+
+fn invalid(path: &str, reason: &str) -> DataSourceError {
+	DataSourceError::InvalidSchema(format!("{}: {}", path, reason))
+}
+
+fn field<'a>(
+	object: &'a Map<String, Value>,
+	key: &str,
+	path: &str,
+) -> Result<&'a Value, DataSourceError> {
+	object.get(key).ok_or_else(|| invalid(path, &format!("{} is missing", key)))
+}
+
+fn object<'a>(value: &'a Value, path: &str) -> Result<&'a Map<String, Value>, DataSourceError> {
+	value.as_object().ok_or_else(|| invalid(path, "expected an object"))
+}
+
+fn string<'a>(
+	object: &'a Map<String, Value>,
+	key: &str,
+	path: &str,
+) -> Result<&'a str, DataSourceError> {
+	field(object, key, path)?
+		.as_str()
+		.ok_or_else(|| invalid(path, &format!("{} should be a string", key)))
+}
+
+fn number(object: &Map<String, Value>, key: &str, path: &str) -> Result<(), DataSourceError> {
+	match field(object, key, path)? {
+		Value::Number(_) => Ok(()),
+		_ => Err(invalid(path, &format!("{} should be a number", key))),
+	}
+}
+
+fn timestamp(
+	object: &Map<String, Value>,
+	key: &str,
+	path: &str,
+) -> Result<DateTime<Utc>, DataSourceError> {
+	parse_timestamp(string(object, key, path)?)
+		.ok_or_else(|| invalid(path, &format!("{} should be a timestamp", key)))
+}
+
+fn non_empty_array<'a>(
+	object: &'a Map<String, Value>,
+	key: &str,
+	path: &str,
+) -> Result<&'a Vec<Value>, DataSourceError> {
+	match field(object, key, path)?.as_array() {
+		Some(items) if !items.is_empty() => Ok(items),
+		_ => Err(invalid(path, &format!("{} should be a non-empty array", key))),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn mock() -> Value {
+		serde_json::from_str(&std::fs::read_to_string("mock/metadatas.json").unwrap()).unwrap()
+	}
+
+	fn reason(document: &Value) -> String {
+		match validate(document) {
+			Err(DataSourceError::InvalidSchema(reason)) => reason,
+			other => panic!("expected an invalid schema, got {:?}", other),
+		}
+	}
+
+	#[test]
+	fn mock_metadatas_are_valid() {
+		assert!(validate(&mock()).is_ok());
+	}
+
+	#[test]
+	fn malformed_documents_are_rejected() {
+		assert_eq!(reason(&Value::Array(vec![])), "document: expected an object");
+
+		let mut document = mock();
+		document["metadatas"] = Value::Array(vec![]);
+		assert_eq!(reason(&document), "document: metadatas should be a non-empty array");
+
+		let mut document = mock();
+		document["submissionTimestamp"] = "yesterday".into();
+		assert_eq!(reason(&document), "document: submissionTimestamp should be a timestamp");
+
+		let mut document = mock();
+		document["metadatas"][0].as_object_mut().unwrap().remove("nmi");
+		assert_eq!(reason(&document), "metadatas[0]: nmi is missing");
+
+		let mut document = mock();
+		document["metadatas"][0]["intervals"][0]["activePowerImportLimit"] = "8".into();
+		assert_eq!(
+			reason(&document),
+			"metadatas[0].intervals[0]: activePowerImportLimit should be a number"
+		);
+
+		let mut document = mock();
+		document["metadatas"][0]["intervals"][0]["diStarttime"] = "2022-04-20T22:13:25Z".into();
+		assert_eq!(reason(&document), "metadatas[0].intervals[0]: diStarttime is after diEndtime");
+	}
+}
@@ -1,5 +1,7 @@
+mod blocks;
 mod calls;
 pub mod config;
+pub mod data_source;
 mod logic_provider;
 mod offchain;
 mod service;
@@ -18,6 +20,10 @@ pub enum PluginError {
 	AccountConversionError,
 	/// Generic runtime api error.
 	RuntimeApiError,
+	/// The runtime could not decode a call sent to it.
+	InvalidCall,
+	/// The transaction pool did not accept an extrinsic sent to it.
+	SubmissionFailed,
 	/// Something went wrong while encoding/decoding.
 	CodecError(codec::Error),
 }
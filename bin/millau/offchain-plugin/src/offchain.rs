@@ -1,10 +1,35 @@
 use codec::{Decode, Encode};
-use primitives::{client::TRACKED_STORAGE_KEYS, shared::OffchainCommitmentInfo};
+use primitives::{
+	client::{REVEAL_STATE_PREFIX, TRACKED_STORAGE_KEYS},
+	shared::{Hash, OffchainCommitmentInfo},
+};
 use sc_client_db::offchain::LocalStorage;
 use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// Where the reveal of a tracked metadata id stands. Kept in the offchain storage, so that it
+/// survives restarts of the node.
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub enum RevealState {
+	/// The commitment was sent, the reveal was not sent yet.
+	Committed,
+	/// The reveal was last sent on top of block `sent_at`, in the extrinsic `tx_hash`. It is not
+	/// on chain yet.
+	RevealSent { tx_hash: Hash, sent_at: u64 },
+	/// The reveal was accepted on chain: the commitment of the plugin holds the revealed hash.
+	Revealed,
+	/// The reveal window closed, or the round ended, before the reveal was accepted.
+	Failed,
+}
+
+impl RevealState {
+	/// Whether the metadata id is done with, so that it no longer needs to be tracked.
+	pub fn is_final(&self) -> bool {
+		matches!(self, Self::Revealed | Self::Failed)
+	}
+}
+
 // Set a key for the given metadata that the state poller will use according to its own
 // schedule
 pub async fn store_key(key: u64, offchain_storage: &Arc<Mutex<LocalStorage>>) {
@@ -24,6 +49,11 @@ pub async fn store_key(key: u64, offchain_storage: &Arc<Mutex<LocalStorage>>) {
 	}
 }
 
+// Whether a commitment for the given metadata was already stored
+pub async fn has_commit_info(key: u64, offchain_storage: &Arc<Mutex<LocalStorage>>) -> bool {
+	offchain_storage.lock().await.get(STORAGE_PREFIX, &key.encode()).is_some()
+}
+
 // Store the information related to a commitment to be retrieved later at the time of the reveal
 pub async fn store_commit_info(
 	key: u64,
@@ -38,3 +68,54 @@ pub async fn store_commit_info(
 		lock.set(STORAGE_PREFIX, &key.encode(), &commitment_info.encode())
 	}
 }
+
+// The keys the state poller tracks
+pub async fn tracked_keys(offchain_storage: &Arc<Mutex<LocalStorage>>) -> Vec<u64> {
+	let lock = offchain_storage.lock().await;
+	match lock.get(STORAGE_PREFIX, TRACKED_STORAGE_KEYS) {
+		Some(tracked_keys) => Vec::<u64>::decode(&mut &tracked_keys[..]).unwrap_or_else(|err| {
+			log::error!("Error when decoding storage value: {:?}", err);
+			Vec::new()
+		}),
+		None => Vec::new(),
+	}
+}
+
+// Stop tracking the given keys. The tracked keys are read again under the lock, so that keys
+// stored in the meantime are kept
+pub async fn untrack_keys(keys: &[u64], offchain_storage: &Arc<Mutex<LocalStorage>>) {
+	if keys.is_empty() {
+		return
+	}
+	let mut tracked_keys = tracked_keys(offchain_storage).await;
+	tracked_keys.retain(|key| !keys.contains(key));
+	offchain_storage.lock().await.set(
+		STORAGE_PREFIX,
+		TRACKED_STORAGE_KEYS,
+		&tracked_keys.encode(),
+	);
+}
+
+fn reveal_state_key(key: u64) -> Vec<u8> {
+	(REVEAL_STATE_PREFIX, key).encode()
+}
+
+// The state of the reveal for the given metadata, if it is known
+pub async fn reveal_state(
+	key: u64,
+	offchain_storage: &Arc<Mutex<LocalStorage>>,
+) -> Option<RevealState> {
+	let lock = offchain_storage.lock().await;
+	lock.get(STORAGE_PREFIX, &reveal_state_key(key))
+		.and_then(|state| RevealState::decode(&mut &state[..]).ok())
+}
+
+// Store the state of the reveal for the given metadata
+pub async fn set_reveal_state(
+	key: u64,
+	offchain_storage: &Arc<Mutex<LocalStorage>>,
+	state: &RevealState,
+) {
+	log::debug!(target: "runtime::state-poller", "Reveal state of key {}: {:?}", key, state);
+	offchain_storage.lock().await.set(STORAGE_PREFIX, &reveal_state_key(key), &state.encode())
+}
@@ -1,27 +1,26 @@
-use runtime_api::ConstructExtrinsicApi;
-use futures::StreamExt;
-use sc_client_api::HeaderBackend;
+use runtime_api::{ConstructExtrinsicApi, StorageQueryApi};
+use futures::{Stream, StreamExt};
+use sc_client_api::{BlockchainEvents, HeaderBackend};
 use sc_client_db::offchain::LocalStorage;
 use sc_keystore::LocalKeystore;
 use serde_json::{Map, Value};
 use sp_api::{BlockT, ProvideRuntimeApi};
-use std::{sync::Arc, time::Duration};
-use tokio::{
-	sync::Mutex,
-	time::{interval, Instant, Interval},
-};
+use sp_core::Pair as _;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 use crate::{
+	blocks::{finalized_blocks, new_best_blocks, BlockEvent},
 	calls::submit_call,
 	config::{config_provider::get_config, get_keypair},
 	logic_provider,
-	offchain::{store_commit_info, store_key},
+	offchain::{has_commit_info, set_reveal_state, store_commit_info, store_key, RevealState},
 };
 use primitives::shared::{LogicProviderCall, MapToCall, OffchainCommitmentInfo, Pair};
 
 // Start the module. To be initiated by the node's service.
-// In here we use a runtime interface, which consists of some logic running on an interval
-// as well as some business logic that retrieves the offchain data.
+// In here we use a runtime interface, which consists of some logic running on every new best
+// block as well as some business logic that retrieves the offchain data.
 pub async fn start<B, C: 'static>(
 	// Accept some closure that expects a `MapToCall`
 	client: Arc<C>,
@@ -29,71 +28,114 @@ pub async fn start<B, C: 'static>(
 	keystore: Arc<LocalKeystore>,
 ) where
 	B: BlockT,
-	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
-	C::Api: ConstructExtrinsicApi<B>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockchainEvents<B>,
+	C::Api: ConstructExtrinsicApi<B> + StorageQueryApi<B>,
 {
-	// Indicate some arbitrary seconds interval, where for each "tick" the business logic will be
-	// invoked
 	// Fetch a JSON object with various values configured by the node operator. In addition, it
 	// contains the first valid public key set by the node operator, set in the key
 	// `config_account_id`.
 	let config: Arc<Mutex<Map<String, Value>>> = get_config(&offchain_storage, &keystore).await;
-	let interval = interval(Duration::from_secs(27));
-	let start = Instant::now();
 	let pair = get_keypair(&config, &keystore)
 		.await
 		.expect("Could not get pair from the keystore");
+	// The business logic runs on every new best block, the config is refreshed as blocks get
+	// finalized
+	let best_blocks = new_best_blocks(&*client);
+	let finalized_blocks = finalized_blocks(&*client);
 	futures::join!(
-		run_service::<B, C>(client, pair, config.clone(), start, interval, &offchain_storage),
-		crate::config::config_provider::schedule_config_update(&offchain_storage, config)
+		run_service::<B, C>(client, pair, config.clone(), best_blocks, &offchain_storage),
+		crate::config::config_provider::schedule_config_update(
+			&offchain_storage,
+			config,
+			finalized_blocks
+		)
 	);
 }
 
 /// In here, we run our initial logic related to the logic provider flow.
-/// This includes generating the metadata id, creating metadata
+/// This includes picking up the metadata ids requested on chain, creating metadata
 /// hash, committing the hash and saving the data in the offchain storage
 /// for further reveals.
 async fn run_service<B, C: 'static>(
 	client: Arc<C>,
 	pair: Arc<Pair>,
 	config: Arc<Mutex<Map<String, Value>>>,
-	start: Instant,
-	interval: Interval,
+	blocks: impl Stream<Item = BlockEvent<B::Hash>>,
 	offchain_storage: &Arc<Mutex<LocalStorage>>,
 ) where
 	B: BlockT,
 	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
-	C::Api: ConstructExtrinsicApi<B>,
+	C::Api: ConstructExtrinsicApi<B> + StorageQueryApi<B>,
 {
-	tokio_stream::wrappers::IntervalStream::new(interval)
-		.for_each(|now| {
+	blocks
+		.for_each(|block| {
 			let client = client.clone();
 			let pair = pair.clone();
 			let config = config.clone();
 			let offchain_storage = offchain_storage.clone();
-			// Track the elapsed time for creation of unique ids for hashes sent to the pallet
-			let elapsed = now.duration_since(start).as_secs_f32();
-			let metadata_id = elapsed.trunc() as u64;
 			async move {
-				let config = config.lock().await;
-				if let Some(reveal_hash) = logic_provider::get_data(config) {
-					let (commit_hash, random_seed) =
-						logic_provider::create_commit_hash(reveal_hash);
+				// The metadata ids come from the chain, so that every node commits to the same
+				// ones. Skip those this node already committed to.
+				let pending = match client.runtime_api().get_pending_requests(block.hash) {
+					Ok(pending) => pending,
+					Err(err) => {
+						log::warn!(
+							target: "runtime::offchain-plugin",
+							"Could not get the pending requests: {:?}",
+							err
+						);
+						return
+					},
+				};
+				let mut metadata_ids = Vec::new();
+				for metadata_id in pending {
+					if has_commit_info(metadata_id, &offchain_storage).await {
+						continue
+					}
+					// Commitments are rejected once the reveal window of the round opened
+					let reveal_window =
+						client.runtime_api().get_reveal_window(block.hash, metadata_id);
+					match reveal_window {
+						Ok(Some((start, _))) if !block.lands_before(start) => {},
+						_ => metadata_ids.push(metadata_id),
+					}
+				}
+				if metadata_ids.is_empty() {
+					return
+				}
+
+				// Don't hold the config while fetching, sources may take a while to answer
+				let config = config.lock().await.clone();
+				let reveal_hash = match logic_provider::get_data(&config).await {
+					Ok(reveal_hash) => reveal_hash,
+					Err(err) => {
+						log::warn!(
+							target: "runtime::offchain-plugin",
+							"Could not get the offchain data: {:?}",
+							err
+						);
+						return
+					},
+				};
+				for metadata_id in metadata_ids {
+					let (commit_hash, salt) =
+						logic_provider::create_commit_hash(pair.public(), metadata_id, reveal_hash);
 
 					let call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 						metadata_id,
 						hash: commit_hash,
 					});
 
-					if submit_call(client, pair, call).is_ok() {
-						let commit_info =
-							OffchainCommitmentInfo { commit_hash, reveal_hash, random_seed };
+					if submit_call(client.clone(), pair.clone(), call).is_ok() {
+						let commit_info = OffchainCommitmentInfo { commit_hash, reveal_hash, salt };
 
 						// Store the relevant metadata id. This will then be tracked
 						// separately, the reveal window for it will be checked
 						store_key(metadata_id, &offchain_storage).await;
 						// Store the relevant commit information for the given metadata id
-						store_commit_info(metadata_id, offchain_storage, commit_info)
+						store_commit_info(metadata_id, offchain_storage.clone(), commit_info).await;
+						// The reveal is sent once the reveal window opens
+						set_reveal_state(metadata_id, &offchain_storage, &RevealState::Committed)
 							.await;
 					}
 				}
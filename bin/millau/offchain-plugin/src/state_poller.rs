@@ -1,20 +1,22 @@
 use codec::{Decode, Encode};
 use futures::StreamExt;
-use sc_client_api::HeaderBackend;
+use sc_client_api::{BlockchainEvents, HeaderBackend};
 use sc_client_db::offchain::LocalStorage;
 use sc_keystore::LocalKeystore;
 use sp_api::{ApiError, BlockT, ProvideRuntimeApi};
-use sp_runtime::{generic, offchain::{OffchainStorage, STORAGE_PREFIX}, SaturatedConversion};
-use std::sync::Arc;
-use tokio::{
-	sync::Mutex,
-	time::{interval, Duration},
-};
+use sp_core::Pair as _;
+use sp_runtime::offchain::{OffchainStorage, STORAGE_PREFIX};
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::Mutex;
 
-use crate::{config::get_keypair, PluginError};
-use primitives::{
-	client::TRACKED_STORAGE_KEYS,
-	shared::{LogicProviderCall, MapToCall, OffchainCommitmentInfo, MetadataId},
+use crate::{
+	blocks::{new_best_blocks, BlockEvent},
+	config::get_keypair,
+	offchain::{reveal_state, set_reveal_state, tracked_keys, untrack_keys, RevealState},
+	PluginError,
+};
+use primitives::shared::{
+	Hash, LogicProviderCall, MapToCall, MetadataId, OffchainCommitmentInfo, Pair, Public,
 };
 use runtime_api::{ConstructExtrinsicApi, StorageQueryApi};
 
@@ -22,6 +24,8 @@ impl From<runtime_api::Error> for PluginError {
 	fn from(err: runtime_api::Error) -> Self {
 		match err {
 			runtime_api::Error::AccountConversion => PluginError::AccountConversionError,
+			runtime_api::Error::InvalidCall => PluginError::InvalidCall,
+			runtime_api::Error::SubmissionFailed => PluginError::SubmissionFailed,
 		}
 	}
 }
@@ -38,7 +42,32 @@ impl From<codec::Error> for PluginError {
 	}
 }
 
-/// Start a task that polls reveal window state and reveals
+/// How many blocks a sent reveal is given to land on chain before it is sent again.
+const REVEAL_RESEND_DELAY: u64 = 2;
+
+/// Where the round of a tracked metadata id stands, as seen from some block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RoundView {
+	/// The round still waits for enough commitments, its reveal window is not known yet.
+	AwaitingQuorum,
+	/// The reveal window of the round spans these blocks, both included.
+	Window((u64, u64)),
+	/// The round is over or expired, nothing can be revealed anymore.
+	Closed,
+}
+
+/// What to do next for a tracked metadata id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Step {
+	/// Send the reveal.
+	Reveal,
+	/// Move the reveal to the given state.
+	Update(RevealState),
+	/// Nothing to do on this block.
+	Wait,
+}
+
+/// Start a task that checks the reveal window state on every new best block and reveals
 /// the hash when it's time.
 pub async fn poll_reveal_window_state<B, C>(
 	offchain_storage: Arc<Mutex<LocalStorage>>,
@@ -46,18 +75,23 @@ pub async fn poll_reveal_window_state<B, C>(
 	keystore: Arc<LocalKeystore>,
 ) where
 	B: BlockT,
-	C: ProvideRuntimeApi<B> + HeaderBackend<B> + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockchainEvents<B> + 'static,
 	C::Api: StorageQueryApi<B> + ConstructExtrinsicApi<B>,
 {
-	let repeat = interval(Duration::from_secs(12));
-
 	let offchain_storage = &offchain_storage.clone();
-	tokio_stream::wrappers::IntervalStream::new(repeat)
-		.for_each(|_now| {
+	new_best_blocks(&*client)
+		.for_each(|block| {
 			let client = client.clone();
 			let keystore = keystore.clone();
 			async move {
-				let _ = check_keys(client, offchain_storage, &keystore).await;
+				if let Err(err) = check_keys(client, block, offchain_storage, &keystore).await {
+					log::warn!(
+						target: "runtime::state-poller",
+						"Could not check the reveals on block {}: {:?}",
+						block.number,
+						err
+					);
+				}
 			}
 		})
 		.await
@@ -73,104 +107,313 @@ async fn get_offchain_data_for_key(
 		.map(|retrieved| OffchainCommitmentInfo::decode(&mut &retrieved[..]))
 }
 
-/// Send reveal call to the runtime.
+/// Send reveal call to the runtime, signed with `pair`. Returns the hash of the extrinsic.
 ///
 /// The reveal will be sent once we get to the reveal window.
-async fn send_commitment_reveal<B, C>(
+fn send_commitment_reveal<B, C>(
 	client: Arc<C>,
-	offchain_storage: &Arc<Mutex<LocalStorage>>,
-	keystore: &Arc<LocalKeystore>,
+	pair: Arc<Pair>,
 	key: MetadataId,
 	commit_info: &OffchainCommitmentInfo,
-) -> Result<(), PluginError>
+) -> Result<Hash, PluginError>
 where
 	B: BlockT,
 	C: ProvideRuntimeApi<B> + HeaderBackend<B> + 'static,
 	C::Api: ConstructExtrinsicApi<B> + StorageQueryApi<B>,
 {
-	let OffchainCommitmentInfo { reveal_hash, random_seed, .. } = commit_info;
-	let config = crate::config::config_provider::get_config(offchain_storage, keystore).await;
-	let pair = get_keypair(&config, keystore).await?;
+	let OffchainCommitmentInfo { reveal_hash, salt, .. } = commit_info;
 	let call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 		reveal_hash: *reveal_hash,
-		random_seed: *random_seed,
+		salt: *salt,
 		metadata_id: key,
 	});
-	crate::calls::submit_call(client, pair, call).map_err(|_| PluginError::RuntimeApiError)?;
-	Ok(())
+	crate::calls::submit_call(client, pair, call)
+}
+
+/// Where the round of the metadata id `key` stands at `block`. `pending` are the requests whose
+/// round still accepts commitments at that block.
+fn round_view<B, C>(
+	client: &C,
+	block: BlockEvent<B::Hash>,
+	key: MetadataId,
+	pending: &[MetadataId],
+) -> Result<RoundView, ApiError>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: StorageQueryApi<B>,
+{
+	Ok(match client.runtime_api().get_reveal_window(block.hash, key)? {
+		Some(window) => RoundView::Window(window),
+		None if pending.contains(&key) => RoundView::AwaitingQuorum,
+		None => RoundView::Closed,
+	})
 }
 
-/// A function to check if we are in the reveal window for some metadata id `key`.
-async fn within_reveal_window_for_key<B, C>(
+/// Whether the reveal of `public` for the metadata id `key` is on chain at `block`. A reveal
+/// extrinsic that is included but failed to dispatch doesn't count.
+fn has_revealed<B, C>(
+	client: &C,
+	block: BlockEvent<B::Hash>,
 	key: MetadataId,
-	client: &Arc<C>,
+	public: Public,
 ) -> Result<bool, ApiError>
 where
 	B: BlockT,
-	C: ProvideRuntimeApi<B> + HeaderBackend<B> + 'static,
-	C::Api: ConstructExtrinsicApi<B> + StorageQueryApi<B>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: StorageQueryApi<B>,
 {
-	let current_block = client.info().best_number.saturated_into::<u64>();
-	let current_hash = client.info().best_hash;
-	let query = client
-		.runtime_api()
-		.get_reveal_window(current_hash, key)?;
-
-	if let Some((window_start, window_end)) = query {
-		Ok(current_block >= window_start && current_block <= window_end)
-	} else {
-		Ok(false)
+	client.runtime_api().has_revealed(block.hash, key, public)
+}
+
+/// What to do for a metadata id whose reveal is in `state`, on top of `block`. `revealed` tells
+/// whether the reveal is on chain at `block`.
+fn next_step<H>(
+	state: &RevealState,
+	block: &BlockEvent<H>,
+	view: RoundView,
+	revealed: bool,
+) -> Step {
+	let resend_due = match state {
+		RevealState::Committed => true,
+		RevealState::RevealSent { sent_at, .. } =>
+			block.number >= sent_at.saturating_add(REVEAL_RESEND_DELAY),
+		RevealState::Revealed | RevealState::Failed => return Step::Wait,
+	};
+	// Checked for committed keys too, the reveal may have been sent before a restart
+	if revealed {
+		return Step::Update(RevealState::Revealed)
+	}
+	match view {
+		RoundView::Window(window) if block.can_land_within(window) =>
+			if resend_due {
+				Step::Reveal
+			} else {
+				Step::Wait
+			},
+		RoundView::Window((start, _)) if block.lands_before(start) => Step::Wait,
+		RoundView::AwaitingQuorum => Step::Wait,
+		// The window can't be reached anymore
+		RoundView::Window(_) | RoundView::Closed => Step::Update(RevealState::Failed),
 	}
 }
 
-/// Check the reveal windows for all saved metadatas, and send the reveals if needed.
+/// Move the reveal of every tracked metadata id forward on `block`, and stop tracking those that
+/// are done with. Returns the metadata ids whose reveal should be sent.
+///
+/// Metadata ids missing from `rounds` are left as they are, `revealed` are the metadata ids whose
+/// reveal is on chain at `block`.
+async fn advance_keys<H>(
+	offchain_storage: &Arc<Mutex<LocalStorage>>,
+	block: &BlockEvent<H>,
+	rounds: &BTreeMap<MetadataId, RoundView>,
+	revealed: &[MetadataId],
+) -> Vec<MetadataId> {
+	let mut to_reveal = Vec::new();
+	let mut finished = Vec::new();
+	for key in tracked_keys(offchain_storage).await {
+		let view = match rounds.get(&key) {
+			Some(view) => *view,
+			None => continue,
+		};
+		// Keys tracked before the reveal states were stored have only been committed
+		let state = reveal_state(key, offchain_storage).await.unwrap_or(RevealState::Committed);
+		if state.is_final() {
+			finished.push(key);
+			continue
+		}
+		match next_step(&state, block, view, revealed.contains(&key)) {
+			Step::Reveal => to_reveal.push(key),
+			Step::Update(state) => {
+				if state.is_final() {
+					finished.push(key);
+				}
+				set_reveal_state(key, offchain_storage, &state).await;
+			},
+			Step::Wait => {},
+		}
+	}
+	untrack_keys(&finished, offchain_storage).await;
+	to_reveal
+}
+
+/// Record that the reveal for `key` was sent on top of block `sent_at`, in the extrinsic
+/// `tx_hash`.
+async fn record_reveal_sent(
+	offchain_storage: &Arc<Mutex<LocalStorage>>,
+	key: MetadataId,
+	tx_hash: Hash,
+	sent_at: u64,
+) {
+	set_reveal_state(key, offchain_storage, &RevealState::RevealSent { tx_hash, sent_at }).await
+}
+
+/// Check the reveal windows for all tracked metadatas, check whether the reveals sent earlier
+/// landed on chain, and send the reveals that are due.
 async fn check_keys<B, C>(
 	client: Arc<C>,
+	block: BlockEvent<B::Hash>,
 	offchain_storage: &Arc<Mutex<LocalStorage>>,
 	keystore: &Arc<LocalKeystore>,
-) -> Result<Option<()>, PluginError>
+) -> Result<(), PluginError>
 where
 	B: BlockT,
 	C: ProvideRuntimeApi<B> + HeaderBackend<B> + 'static,
 	C::Api: ConstructExtrinsicApi<B> + StorageQueryApi<B>,
 {
-	let keys_lock = offchain_storage.lock().await;
-	if let Some(stored_keys) = keys_lock.get(STORAGE_PREFIX, TRACKED_STORAGE_KEYS) {
-		drop(keys_lock);
-		let mut tracked_keys = Vec::<MetadataId>::decode(&mut &stored_keys[..])?;
-
-		let mut to_remove = Vec::new();
-		for idx_rd in 0..tracked_keys.len() {
-			let key = tracked_keys[idx_rd];
-			let client = client.clone();
-			let keystore = keystore.clone();
-			if within_reveal_window_for_key(key, &client).await? {
-				if let Some(commit_data) = get_offchain_data_for_key(offchain_storage, &key).await {
-					let commit_data = commit_data?;
-					send_commitment_reveal(
-						client.clone(),
-						offchain_storage,
-						&keystore,
+	let tracked_keys = tracked_keys(offchain_storage).await;
+	if tracked_keys.is_empty() {
+		return Ok(())
+	}
+	let config = crate::config::config_provider::get_config(offchain_storage, keystore).await;
+	let pair = get_keypair(&config, keystore).await?;
+
+	let pending = client.runtime_api().get_pending_requests(block.hash)?;
+	let mut rounds = BTreeMap::new();
+	let mut revealed = Vec::new();
+	for key in tracked_keys {
+		// Keys whose round can't be queried are looked at again on the next block
+		let view = round_view(&*client, block, key, &pending).and_then(|view| {
+			Ok((view, has_revealed(&*client, block, key, pair.public())?))
+		});
+		match view {
+			Ok((view, is_revealed)) => {
+				rounds.insert(key, view);
+				if is_revealed {
+					revealed.push(key);
+				}
+			},
+			Err(err) => log::warn!(
+				target: "runtime::state-poller",
+				"Could not get the reveal state for metadata id {}: {:?}",
+				key,
+				err
+			),
+		}
+	}
+
+	for key in advance_keys(offchain_storage, &block, &rounds, &revealed).await {
+		match get_offchain_data_for_key(offchain_storage, &key).await {
+			Some(Ok(commit_info)) => {
+				// A failed send doesn't hold back the other reveals, it is retried on the next
+				// block
+				match send_commitment_reveal(client.clone(), pair.clone(), key, &commit_info) {
+					Ok(tx_hash) =>
+						record_reveal_sent(offchain_storage, key, tx_hash, block.number).await,
+					Err(err) => log::warn!(
+						target: "runtime::state-poller",
+						"Could not send the reveal for metadata id {}: {:?}",
 						key,
-						&commit_data,
-					)
-					.await?;
-					to_remove.push(idx_rd);
-				};
-			}
+						err
+					),
+				}
+			},
+			// Commitments stored before the switch to 32-byte salts can't be revealed
+			// anymore, the runtime expires their rounds on upgrade.
+			Some(Err(err)) => {
+				log::warn!(
+					"Dropping undecodable commitment info for metadata id {}: {:?}",
+					key,
+					err
+				);
+				set_reveal_state(key, offchain_storage, &RevealState::Failed).await;
+				untrack_keys(&[key], offchain_storage).await;
+			},
+			None => {},
 		}
+	}
+	Ok(())
+}
 
-		to_remove.into_iter().for_each(|to_remove| {
-			tracked_keys.remove(to_remove);
-		});
+#[cfg(test)]
+mod tests {
+	use super::{advance_keys, record_reveal_sent, RoundView};
+	use crate::{
+		blocks::BlockEvent,
+		config::test_utils::create_local_storage,
+		offchain::{reveal_state, set_reveal_state, store_key, tracked_keys, RevealState},
+	};
+	use primitives::shared::Hash;
+	use std::collections::BTreeMap;
+
+	fn at(number: u64) -> BlockEvent<()> {
+		BlockEvent { number, hash: () }
+	}
+
+	#[tokio::test]
+	async fn several_keys_are_revealed_in_the_same_tick() {
+		let storage = create_local_storage();
+		for key in 1..=5 {
+			store_key(key, &storage).await;
+			set_reveal_state(key, &storage, &RevealState::Committed).await;
+		}
+		let rounds = BTreeMap::from([
+			(1, RoundView::Window((11, 11))),
+			(2, RoundView::Window((11, 16))),
+			(3, RoundView::AwaitingQuorum),
+			(4, RoundView::Closed),
+		]);
+
+		// Key 5 couldn't be queried, it is left as it is
+		assert_eq!(advance_keys(&storage, &at(10), &rounds, &[]).await, vec![1, 2]);
+		assert_eq!(reveal_state(4, &storage).await, Some(RevealState::Failed));
+		assert_eq!(reveal_state(5, &storage).await, Some(RevealState::Committed));
+		assert_eq!(tracked_keys(&storage).await, vec![1, 2, 3, 5]);
+
+		record_reveal_sent(&storage, 1, Hash::repeat_byte(1), 10).await;
+		record_reveal_sent(&storage, 2, Hash::repeat_byte(2), 10).await;
+		assert!(advance_keys(&storage, &at(11), &rounds, &[2, 1]).await.is_empty());
+		assert_eq!(reveal_state(1, &storage).await, Some(RevealState::Revealed));
+		assert_eq!(reveal_state(2, &storage).await, Some(RevealState::Revealed));
+		assert_eq!(tracked_keys(&storage).await, vec![3, 5]);
+	}
+
+	#[tokio::test]
+	async fn reveals_are_sent_again_until_on_chain() {
+		let storage = create_local_storage();
+		store_key(1, &storage).await;
+		store_key(2, &storage).await;
+		let rounds =
+			BTreeMap::from([(1, RoundView::Window((11, 16))), (2, RoundView::Window((11, 12)))]);
+
+		// Keys without a state count as committed
+		assert_eq!(advance_keys(&storage, &at(10), &rounds, &[]).await, vec![1, 2]);
+		record_reveal_sent(&storage, 1, Hash::repeat_byte(1), 10).await;
+		record_reveal_sent(&storage, 2, Hash::repeat_byte(2), 10).await;
+
+		// The reveals are given some time to land on chain
+		assert!(advance_keys(&storage, &at(11), &rounds, &[]).await.is_empty());
 
-		offchain_storage.lock().await.set(
-			STORAGE_PREFIX,
-			TRACKED_STORAGE_KEYS,
-			&tracked_keys.encode(),
+		// The window of key 2 can't be reached anymore
+		assert_eq!(advance_keys(&storage, &at(12), &rounds, &[]).await, vec![1]);
+		assert_eq!(reveal_state(2, &storage).await, Some(RevealState::Failed));
+		record_reveal_sent(&storage, 1, Hash::repeat_byte(3), 12).await;
+		assert_eq!(
+			reveal_state(1, &storage).await,
+			Some(RevealState::RevealSent { tx_hash: Hash::repeat_byte(3), sent_at: 12 })
 		);
-		Ok(Some(()))
-	} else {
-		Ok(None)
+
+		// Only a reveal accepted on chain counts, whichever send it came from
+		assert!(advance_keys(&storage, &at(13), &rounds, &[]).await.is_empty());
+		assert!(advance_keys(&storage, &at(14), &rounds, &[1]).await.is_empty());
+		assert_eq!(reveal_state(1, &storage).await, Some(RevealState::Revealed));
+		assert!(tracked_keys(&storage).await.is_empty());
+	}
+
+	#[tokio::test]
+	async fn reveal_in_the_block_that_settles_the_round_counts() {
+		let storage = create_local_storage();
+		store_key(1, &storage).await;
+		store_key(2, &storage).await;
+		record_reveal_sent(&storage, 1, Hash::repeat_byte(1), 15).await;
+		record_reveal_sent(&storage, 2, Hash::repeat_byte(2), 15).await;
+
+		// Both rounds were settled in block 16, the last one of their window. Only the reveal of
+		// key 1 landed in it
+		let rounds = BTreeMap::from([(1, RoundView::Closed), (2, RoundView::Closed)]);
+		assert!(advance_keys(&storage, &at(16), &rounds, &[1]).await.is_empty());
+		assert_eq!(reveal_state(1, &storage).await, Some(RevealState::Revealed));
+		assert_eq!(reveal_state(2, &storage).await, Some(RevealState::Failed));
+		assert!(tracked_keys(&storage).await.is_empty());
 	}
 }
@@ -7,10 +7,13 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod migrations;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 	use frame_support::{pallet_prelude::*, PalletError};
+	use frame_system::pallet_prelude::*;
 	use sp_core::{H256};
 
 	use sp_runtime::traits::{CheckEqual, MaybeDisplay, SimpleBitOps, SaturatedConversion};
@@ -18,16 +21,39 @@ pub mod pallet {
 	use sp_std::{fmt::Debug, prelude::*};
 
 	// A unique identifier of the current subject that commit/reveals are centered on
-	type CommitKey = u64;
+	pub type CommitKey = u64;
+
+	// Random bytes hashed along with an answer, so that the answer can not be guessed from the
+	// commitment before it is revealed. The preimage layout is shared with the offchain plugin
+	pub use primitives::shared::{commitment_preimage, Salt, COMMITMENT_DOMAIN_TAG};
+
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
 	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self, I>>
+			+ IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		// Amount of blocks a round stays open for commitments before it expires, unless a quorum
+		// of commitments is reached in the meantime
+		#[pallet::constant]
+		type OpenPhaseLength: Get<u32>;
+		// Amount of blocks in which commitments are still accepted once a quorum is reached,
+		// before the reveal window opens
+		#[pallet::constant]
+		type CommitPhaseLength: Get<u32>;
 		// Amount of blocks in which reveals of a given key are allowed, following a commit window
 		// for the same key
-		type RevealWindowLength: Get<u8>;
+		#[pallet::constant]
+		type RevealWindowLength: Get<u32>;
 		#[pallet::constant]
 		type MaxParticipants: Get<u32>;
+		// Maximum number of rounds whose phase is checked in a single block. Rounds due in a
+		// full block are checked in the next one with room left
+		#[pallet::constant]
+		type MaxPhaseChangesPerBlock: Get<u32>;
 		type Hash: From<H256>
 			+ Parameter
 			+ Member
@@ -43,9 +69,12 @@ pub mod pallet {
 			+ AsRef<[u8]>
 			+ AsMut<[u8]>
 			+ MaxEncodedLen;
+		/// Notified when a round expires, so that consumers can release whatever they hold for it.
+		type OnRoundExpired: OnRoundExpired;
 	}
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T, I = ()>(_);
 
@@ -105,17 +134,50 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The phase a commit/reveal round is in. Block numbers are inclusive.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	pub enum RoundPhase {
+		/// Accepting commitments until a quorum is reached; the round expires at `expires_at`
+		/// otherwise.
+		Open { expires_at: u64 },
+		/// A quorum was reached; late commitments are accepted until the reveal window opens at
+		/// `ends_at`.
+		Committing { ends_at: u64 },
+		/// Commitments can be revealed from `starts_at` up to `ends_at`.
+		Revealing { starts_at: u64, ends_at: u64 },
+		/// The consumer has concluded the round.
+		Finalized,
+		/// No quorum was reached in time and the commitments were dropped.
+		Expired,
+	}
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_round)]
+	/// The phase of every round that has not been finalized or expired yet. Phases that only
+	/// depend on the passing of blocks are moved forward in `on_initialize`, use
+	/// [`Pallet::round_phase`] for the phase at the current block.
+	pub type Rounds<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, CommitKey, RoundPhase, OptionQuery>;
+
 	#[pallet::storage]
-	#[pallet::getter(fn get_reveal_window_start)]
-	/// The window of blocks for which a given subject's reveal window is scheduled.
-	pub type RevealWindow<T: Config<I>, I: 'static = ()> = StorageMap<
+	#[pallet::getter(fn get_phase_changes)]
+	/// The rounds whose phase runs out at a given block, checked in `on_initialize` of that
+	/// block. A round may be listed after it moved on, in which case nothing happens.
+	pub type PhaseChanges<T: Config<I>, I: 'static = ()> = StorageMap<
 		_,
-		Blake2_128Concat,
-		CommitKey,
+		Twox64Concat,
 		u64,
-		OptionQuery,
+		BoundedVec<CommitKey, T::MaxPhaseChangesPerBlock>,
+		ValueQuery,
 	>;
 
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// The round of `commit_key` moved to `phase`.
+		RoundPhaseChanged { commit_key: CommitKey, phase: RoundPhase },
+	}
+
 	#[derive(Encode, Decode, TypeInfo, PalletError, Debug, PartialEq)]
 	pub enum CommitmentError {
 		/// Attempted to reveal a hash that did not match the original contribution
@@ -124,25 +186,162 @@ pub mod pallet {
 		NoCommitmentFound,
 		/// If the commitment was revealed once, we don't allow for further reveals.
 		AlreadyRevealed,
+		/// Attempted to commit once the reveal window of the round has opened
+		CommitWindowClosed,
+		/// Attempted to commit to a round that has been finalized or has expired
+		RoundClosed,
+	}
+
+	/// Hook for consumers keeping their own state per round, e.g. funds locked by committers.
+	pub trait OnRoundExpired {
+		/// Called before the commitments of an expired round are dropped. Returns the weight
+		/// consumed.
+		fn on_round_expired(commit_key: CommitKey) -> Weight;
+	}
+
+	impl OnRoundExpired for () {
+		fn on_round_expired(_commit_key: CommitKey) -> Weight {
+			Weight::zero()
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		// Move rounds forward whose current phase has run out of blocks. Only the rounds
+		// scheduled for this block are looked at.
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			let now = Self::current_block();
+			let due = PhaseChanges::<T, I>::take(now);
+			let mut weight = T::DbWeight::get().reads_writes(1 + due.len() as u64, 1);
+
+			for commit_key in due {
+				let phase = match Rounds::<T, I>::get(commit_key) {
+					Some(phase) => phase,
+					None => continue,
+				};
+				match Self::advance(phase, now) {
+					RoundPhase::Expired =>
+						weight = weight.saturating_add(Self::expire_round(commit_key)),
+					next if next != phase => {
+						Self::set_phase(commit_key, next);
+						// The phase, and the schedule of the next change
+						weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 2));
+					},
+					_ => {},
+				}
+			}
+			weight
+		}
+
+		fn integrity_test() {
+			assert!(T::MaxPhaseChangesPerBlock::get() > 0, "Phase changes could not be scheduled");
+		}
 	}
 
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
-		pub fn is_in_reveal_window(commit_key: CommitKey) -> bool {
-			if let Some(window_start) = Self::get_reveal_window_start(commit_key) {
-				let window_end = window_start + T::RevealWindowLength::get() as u64;
-				let current_block = <frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+		fn current_block() -> u64 {
+			<frame_system::Pallet<T>>::block_number().saturated_into::<u64>()
+		}
 
-				window_start <= current_block && current_block <= window_end
-			} else {
-				false
+		// The phase `phase` has moved to at block `now`, not taking any consumer action into
+		// account.
+		fn advance(phase: RoundPhase, now: u64) -> RoundPhase {
+			match phase {
+				RoundPhase::Open { expires_at } if now >= expires_at => RoundPhase::Expired,
+				RoundPhase::Committing { ends_at } if now >= ends_at => RoundPhase::Revealing {
+					starts_at: ends_at,
+					ends_at: ends_at + T::RevealWindowLength::get() as u64,
+				},
+				phase => phase,
 			}
 		}
 
+		fn set_phase(commit_key: CommitKey, phase: RoundPhase) {
+			Rounds::<T, I>::insert(commit_key, phase);
+			Self::schedule_phase_change(commit_key, phase);
+			Self::deposit_event(Event::RoundPhaseChanged { commit_key, phase });
+		}
+
+		// Have `on_initialize` look at the round of `commit_key` once `phase` runs out, if it
+		// ever does on its own. The check happens at the first block from then on with room
+		// left, and never before the next block.
+		pub(crate) fn schedule_phase_change(commit_key: CommitKey, phase: RoundPhase) {
+			let mut at = match phase {
+				RoundPhase::Open { expires_at } => expires_at,
+				RoundPhase::Committing { ends_at } => ends_at,
+				_ => return,
+			}
+			.max(Self::current_block() + 1);
+			while PhaseChanges::<T, I>::mutate(at, |due| due.try_push(commit_key)).is_err() {
+				at += 1;
+			}
+		}
+
+		/// The phase of the round of `commit_key` at the current block, if such a round exists.
+		pub fn round_phase(commit_key: CommitKey) -> Option<RoundPhase> {
+			Self::get_round(commit_key).map(|phase| Self::advance(phase, Self::current_block()))
+		}
+
+		/// Open a round for `commit_key`, unless one already exists.
+		pub fn open_round(commit_key: CommitKey) {
+			if !Rounds::<T, I>::contains_key(commit_key) {
+				let expires_at = Self::current_block() + T::OpenPhaseLength::get() as u64;
+				Self::set_phase(commit_key, RoundPhase::Open { expires_at });
+			}
+		}
+
+		/// Signal that the round of `commit_key` gathered enough commitments. The reveal window
+		/// opens `CommitPhaseLength` blocks later. Does nothing unless the round is open.
+		pub fn start_committing(commit_key: CommitKey) {
+			if let Some(RoundPhase::Open { .. }) = Self::round_phase(commit_key) {
+				let ends_at = Self::current_block() + T::CommitPhaseLength::get() as u64;
+				Self::set_phase(commit_key, RoundPhase::Committing { ends_at });
+			}
+		}
+
+		/// Conclude the round of `commit_key`. Its commitments are left for the consumer to
+		/// clean up.
+		pub fn finalize_round(commit_key: CommitKey) {
+			if Rounds::<T, I>::take(commit_key).is_some() {
+				Self::deposit_event(Event::RoundPhaseChanged {
+					commit_key,
+					phase: RoundPhase::Finalized,
+				});
+			}
+		}
+
+		pub(crate) fn expire_round(commit_key: CommitKey) -> Weight {
+			let weight = T::OnRoundExpired::on_round_expired(commit_key);
+			Commits::<T, I>::remove(commit_key);
+			Rounds::<T, I>::remove(commit_key);
+			Self::deposit_event(Event::RoundPhaseChanged {
+				commit_key,
+				phase: RoundPhase::Expired,
+			});
+			weight.saturating_add(T::DbWeight::get().writes(2))
+		}
+
+		pub fn is_in_reveal_window(commit_key: CommitKey) -> bool {
+			match Self::round_phase(commit_key) {
+				Some(RoundPhase::Revealing { ends_at, .. }) => Self::current_block() <= ends_at,
+				_ => false,
+			}
+		}
+
+		/// The first and last block of the reveal window of `commit_key`, once it is scheduled.
 		pub fn get_reveal_window(
 			commit_key: CommitKey,
 		) -> Option<(u64, u64)> {
-			Self::get_reveal_window_start(commit_key)
-				.map(|start| (start, start + T::RevealWindowLength::get() as u64))
+			match Self::round_phase(commit_key)? {
+				RoundPhase::Committing { ends_at } =>
+					Some((ends_at, ends_at + T::RevealWindowLength::get() as u64)),
+				RoundPhase::Revealing { starts_at, ends_at } => Some((starts_at, ends_at)),
+				_ => None,
+			}
+		}
+
+		pub fn get_reveal_window_start(commit_key: CommitKey) -> Option<u64> {
+			Self::get_reveal_window(commit_key).map(|(start, _)| start)
 		}
 	}
 
@@ -158,12 +357,17 @@ pub mod pallet {
 	where
 		Hash: From<sp_core::H256>,
 	{
-		fn recreate_commit_hash(answer: Hash, random_seed: u8) -> sp_core::H256;
+		fn recreate_commit_hash(
+			committer: &AccountId,
+			commit_key: CommitKey,
+			answer: Hash,
+			salt: &Salt,
+		) -> sp_core::H256;
 		fn reveal(
 			committer: AccountId,
 			reveal_hash: Hash,
 			commit_key: CommitKey,
-			random_seed: u8,
+			salt: Salt,
 		) -> Result<(), CommitmentError>;
 	}
 
@@ -176,11 +380,20 @@ pub mod pallet {
 			commit_key: CommitKey,
 		) -> Result<(), CommitmentError> {
 			// Basic checks expected in wrapper function
+			match Self::round_phase(commit_key) {
+				None | Some(RoundPhase::Open { .. }) | Some(RoundPhase::Committing { .. }) => {},
+				Some(RoundPhase::Revealing { .. }) =>
+					return Err(CommitmentError::CommitWindowClosed),
+				Some(RoundPhase::Finalized) | Some(RoundPhase::Expired) =>
+					return Err(CommitmentError::RoundClosed),
+			}
 			Commits::<T, I>::try_mutate(commit_key, |commitments| {
 				commitments
 					.try_push(Commitment::new(commit_hash, who))
 					.map_err(|_| CommitmentError::NoCommitmentFound)
-			})
+			})?;
+			Self::open_round(commit_key);
+			Ok(())
 		}
 	}
 
@@ -189,32 +402,35 @@ pub mod pallet {
 		<T as pallet::Config<I>>::Hash: From<sp_core::H256>,
 	{
 		// Attempt to recreate the commitment hash through the process that committers
-		// are expected to follow in creating their commitment hashes: hash(reveal_hash +
-		// random_seed)
+		// are expected to follow in creating their commitment hashes:
+		// hash(COMMITMENT_DOMAIN_TAG ++ committer ++ commit_key ++ reveal_hash ++ salt).
+		// Binding the committer and the commit key into the preimage keeps a participant from
+		// copying someone else's commitment and revealing it once the original answer is public
 		fn recreate_commit_hash(
+			committer: &T::AccountId,
+			commit_key: CommitKey,
 			original_hash: <T as pallet::Config<I>>::Hash,
-			random_seed: u8,
+			salt: &Salt,
 		) -> sp_core::H256 {
-			let mut combined = original_hash.encode();
-			combined.push(random_seed);
-			let hash = sp_io::hashing::blake2_256(&combined);
-			sp_core::H256(hash)
+			let preimage = commitment_preimage(committer, commit_key, &original_hash, salt);
+			sp_core::H256(sp_io::hashing::blake2_256(&preimage))
 		}
 
 		/// Attempt to "reveal" an answer from the POV of the original committer, by
-		/// recreating their original commit message using the random seed that was
+		/// recreating their original commit message using the salt that was
 		/// originally used by the committer to do the same
 		fn reveal(
 			who: T::AccountId,
 			reveal_hash: <T as pallet::Config<I>>::Hash,
 			commit_key: CommitKey,
-			random_seed: u8,
+			salt: Salt,
 		) -> Result<(), CommitmentError> {
 			Commits::<T, I>::try_mutate(commit_key, |commitment| {
 				for commit in commitment.iter_mut() {
 					if commit.submitter == who {
 						ensure!(commit.fulfillment.is_none(), CommitmentError::AlreadyRevealed);
-						let recreated_commit = Self::recreate_commit_hash(reveal_hash, random_seed);
+						let recreated_commit =
+							Self::recreate_commit_hash(&who, commit_key, reveal_hash, &salt);
 						ensure!(
 							commit.commit == recreated_commit.into(),
 							CommitmentError::IncorrectRevealedHash
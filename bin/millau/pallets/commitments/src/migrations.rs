@@ -0,0 +1,109 @@
+//! Storage migrations for the commitments pallet.
+
+use super::*;
+use frame_support::{
+	pallet_prelude::*,
+	storage_alias,
+	traits::{OnRuntimeUpgrade, StorageVersion},
+};
+use sp_std::{collections::btree_set::BTreeSet, marker::PhantomData};
+
+const LOG_TARGET: &str = "runtime::commitments";
+
+pub mod v1 {
+	use super::*;
+
+	/// The first block of the reveal window of each key, as scheduled before rounds were tracked
+	/// explicitly.
+	#[storage_alias]
+	pub(crate) type RevealWindow<T: Config<I>, I: 'static> =
+		StorageMap<Pallet<T, I>, Blake2_128Concat, CommitKey, u64>;
+
+	/// Expire every round that is still in progress.
+	///
+	/// Commitments made with the former `u8` random seed can not be revealed against a 32-byte
+	/// [`Salt`], and with only 256 possible seeds their answers may already be known to anyone.
+	/// Rounds that have a reveal window or a phase scheduled, or in which nothing was revealed
+	/// yet, are expired through [`OnRoundExpired`] so that consumers release what they hold for
+	/// them. Rounds that are already concluded are left untouched.
+	pub struct ExpireLegacyCommitments<T, I = ()>(PhantomData<(T, I)>);
+
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for ExpireLegacyCommitments<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T, I>::on_chain_storage_version();
+			if on_chain_version >= 1 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v1, on-chain storage version is {:?}",
+					on_chain_version
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut reads = 1u64;
+			let mut writes = 1u64;
+			let mut in_progress = BTreeSet::new();
+			for (commit_key, _) in RevealWindow::<T, I>::drain() {
+				in_progress.insert(commit_key);
+				reads += 1;
+				writes += 1;
+			}
+			for commit_key in Rounds::<T, I>::iter_keys() {
+				in_progress.insert(commit_key);
+				reads += 1;
+			}
+			for (commit_key, commitments) in Commits::<T, I>::iter() {
+				if commitments.iter().all(|commitment| commitment.get_fulfillment().is_none()) {
+					in_progress.insert(commit_key);
+				}
+				reads += 1;
+			}
+
+			let mut weight = T::DbWeight::get().reads_writes(reads, writes);
+			log::info!(
+				target: LOG_TARGET,
+				"Expiring {} rounds with legacy commitments",
+				in_progress.len()
+			);
+			for commit_key in in_progress {
+				weight = weight.saturating_add(Pallet::<T, I>::expire_round(commit_key));
+			}
+
+			StorageVersion::new(1).put::<Pallet<T, I>>();
+			weight
+		}
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Schedule the phase changes of the rounds in progress, which used to be found by going
+	/// through every round on each block.
+	pub struct ScheduleRoundPhaseChanges<T, I = ()>(PhantomData<(T, I)>);
+
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for ScheduleRoundPhaseChanges<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T, I>::on_chain_storage_version();
+			if on_chain_version != 1 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v2, on-chain storage version is {:?}",
+					on_chain_version
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut rounds = 0u64;
+			for (commit_key, phase) in Rounds::<T, I>::iter() {
+				Pallet::<T, I>::schedule_phase_change(commit_key, phase);
+				rounds += 1;
+			}
+			log::info!(target: LOG_TARGET, "Scheduled the phase changes of {} rounds", rounds);
+
+			StorageVersion::new(2).put::<Pallet<T, I>>();
+			// Every round is read, and every schedule read and written at least once
+			T::DbWeight::get().reads_writes(1 + 2 * rounds, 1 + rounds)
+		}
+	}
+}
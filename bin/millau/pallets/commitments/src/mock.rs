@@ -4,6 +4,7 @@ use frame_support::{
 	pallet_prelude::ConstU32,
 	parameter_types,
 	traits::{ConstU128, ConstU16, ConstU64},
+	weights::{constants::RocksDbWeight, Weight},
 	BoundedVec,
 };
 use frame_system as system;
@@ -50,7 +51,7 @@ impl system::Config for Test {
 	type Header = Header;
 	type RuntimeEvent = RuntimeEvent;
 	type BlockHashCount = ConstU64<250>;
-	type DbWeight = ();
+	type DbWeight = RocksDbWeight;
 	type Version = ();
 	type PalletInfo = PalletInfo;
 	type AccountData = AccountData<u128>;
@@ -84,13 +85,31 @@ pub fn get_accounts() -> BoundedVec<AccountId32, MaxParticipants> {
 }
 
 parameter_types! {
-	pub const RevealWindowLength:u8 = 3;
+	pub const OpenPhaseLength: u32 = 10;
+	pub const CommitPhaseLength: u32 = 2;
+	pub const RevealWindowLength: u32 = 3;
+	pub const MaxPhaseChangesPerBlock: u32 = 2;
+	/// Keys passed to `OnRoundExpired`
+	pub static ExpiredRounds: Vec<u64> = Vec::new();
+}
+
+pub struct MockOnRoundExpired;
+impl pallet_commitments::OnRoundExpired for MockOnRoundExpired {
+	fn on_round_expired(commit_key: u64) -> Weight {
+		ExpiredRounds::mutate(|keys| keys.push(commit_key));
+		Weight::zero()
+	}
 }
 
 impl pallet_commitments::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type OpenPhaseLength = OpenPhaseLength;
+	type CommitPhaseLength = CommitPhaseLength;
 	type RevealWindowLength = RevealWindowLength;
 	type MaxParticipants = MaxParticipants;
+	type MaxPhaseChangesPerBlock = MaxPhaseChangesPerBlock;
 	type Hash = H256;
+	type OnRoundExpired = MockOnRoundExpired;
 }
 
 // Build genesis storage according to the mock runtime.
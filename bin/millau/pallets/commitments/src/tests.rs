@@ -1,16 +1,49 @@
-use crate::{mock::*, Commit, Commitment, CommitmentError, Commits, Pallet, Reveal, RevealWindow};
+use crate::{
+	migrations, mock::*, Commit, Commitment, CommitmentError, Commits, Event, Pallet,
+	PhaseChanges, Reveal, RoundPhase, Rounds, Salt, COMMITMENT_DOMAIN_TAG,
+};
 
-use frame_support::{assert_err, assert_ok, pallet_prelude::*};
+use frame_support::{
+	assert_err, assert_ok,
+	pallet_prelude::*,
+	traits::{OnRuntimeUpgrade, StorageVersion},
+};
 use rand::Rng;
+use sp_core::crypto::AccountId32;
 use sp_io::hashing::blake2_256;
 
-pub fn create_commit_hash(original_data: H256) -> (H256, u8) {
+pub fn create_commit_hash(
+	who: &AccountId32,
+	commit_key: u64,
+	original_data: H256,
+) -> (H256, Salt) {
 	let mut rng = rand::thread_rng();
-	let random_seed = rng.gen::<u8>();
-	let mut combined = original_data.encode();
-	combined.push(random_seed);
+	let salt = rng.gen::<Salt>();
+	let mut combined = COMMITMENT_DOMAIN_TAG.to_vec();
+	combined.extend_from_slice(&who.encode());
+	combined.extend_from_slice(&commit_key.encode());
+	combined.extend_from_slice(&original_data.encode());
+	combined.extend_from_slice(&salt);
 	let hash = blake2_256(&combined);
-	(H256(hash), random_seed)
+	(H256(hash), salt)
+}
+
+fn open_reveal_window(commit_key: u64) {
+	Rounds::<Test>::insert(
+		commit_key,
+		RoundPhase::Revealing { starts_at: 0, ends_at: RevealWindowLength::get() as u64 },
+	);
+}
+
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Commitments::on_initialize(System::block_number());
+	}
+}
+
+fn phase_changed(commit_key: u64, phase: RoundPhase) -> RuntimeEvent {
+	Event::<Test>::RoundPhaseChanged { commit_key, phase }.into()
 }
 
 #[test]
@@ -35,21 +68,21 @@ fn reject_duplicate_reveal() {
 		let original_hash = H256::from_low_u64_be(456);
 		let commit_key = 1;
 
-		let (commit_hash, random_seed) = create_commit_hash(original_hash);
+		let (commit_hash, salt) = create_commit_hash(&alice, commit_key, original_hash);
 		let mut commitment = Commitment::new(commit_hash, alice.clone());
 
-		RevealWindow::<Test>::insert(commit_key, 0);
+		open_reveal_window(commit_key);
 		Commits::<Test>::try_mutate(commit_key, |commitments| {
 			commitments.try_push(commitment.clone())
 		})
 		.unwrap();
 
-		assert_ok!(Commitments::reveal(alice.clone(), original_hash, commit_key, random_seed));
+		assert_ok!(Commitments::reveal(alice.clone(), original_hash, commit_key, salt));
 		commitment.fulfill(original_hash);
 		commitment.set_reveal_status();
 		assert_eq!(Commitments::get_commitments(commit_key)[0], commitment);
 		assert_eq!(
-			Commitments::reveal(alice, original_hash, commit_key, random_seed),
+			Commitments::reveal(alice, original_hash, commit_key, salt),
 			Err(CommitmentError::AlreadyRevealed)
 		);
 	})
@@ -64,16 +97,16 @@ fn add_successful_reveal() {
 		let original_hash = H256::from_low_u64_be(123);
 		let commit_key = 1;
 
-		let (commit_hash, random_seed) = create_commit_hash(original_hash);
+		let (commit_hash, salt) = create_commit_hash(&alice, commit_key, original_hash);
 		let mut commitment = Commitment::new(commit_hash, alice.clone());
 
-		RevealWindow::<Test>::insert(commit_key, 0);
+		open_reveal_window(commit_key);
 		Commits::<Test>::try_mutate(commit_key, |commitments| {
 			commitments.try_push(commitment.clone())
 		})
 		.unwrap();
 
-		assert_ok!(Commitments::reveal(alice.clone(), original_hash, commit_key, random_seed));
+		assert_ok!(Commitments::reveal(alice.clone(), original_hash, commit_key, salt));
 		commitment.fulfill(original_hash);
 		commitment.set_reveal_status();
 		assert_eq!(Commitments::get_commitments(commit_key)[0], commitment);
@@ -89,16 +122,16 @@ fn reveal_fails_if_answer_is_incorrect() {
 		let original_hash = H256::from_low_u64_be(123);
 		let incorrect_hash = H256::from_low_u64_be(456);
 		let commit_key = 1;
-		let (commit_hash, random_seed) = create_commit_hash(incorrect_hash);
+		let (commit_hash, salt) = create_commit_hash(&alice, commit_key, incorrect_hash);
 
-		RevealWindow::<Test>::insert(commit_key, 0);
+		open_reveal_window(commit_key);
 		Commits::<Test>::try_mutate(commit_key, |commitments| {
 			commitments.try_push(Commitment::new(commit_hash, alice.clone()))
 		})
 		.unwrap();
 
 		assert_err!(
-			Commitments::reveal(alice.clone(), original_hash, commit_key, random_seed),
+			Commitments::reveal(alice.clone(), original_hash, commit_key, salt),
 			CommitmentError::IncorrectRevealedHash
 		);
 	})
@@ -112,7 +145,7 @@ fn reveal_period_false_if_not_revealed_inside_period() {
 		let alice = accounts.pop().unwrap();
 		let original_hash = H256::from_low_u64_be(123);
 		let commit_key = 1;
-		let (commit_hash, random_seed) = create_commit_hash(original_hash);
+		let (commit_hash, salt) = create_commit_hash(&alice, commit_key, original_hash);
 		let mut commitment = Commitment::new(commit_hash, alice.clone());
 		Commits::<Test>::try_mutate(commit_key, |commitments| {
 			commitments.try_push(commitment.clone())
@@ -120,7 +153,7 @@ fn reveal_period_false_if_not_revealed_inside_period() {
 		.unwrap();
 
 		// No Reveal window set
-		assert_ok!(Commitments::reveal(alice.clone(), original_hash, commit_key, random_seed));
+		assert_ok!(Commitments::reveal(alice.clone(), original_hash, commit_key, salt));
 		commitment.fulfill(original_hash);
 		assert_eq!(Commitments::get_commitments(commit_key)[0], commitment);
 	})
@@ -135,12 +168,12 @@ fn reveal_fails_if_no_commitment_made() {
 		let original_hash = H256::from_low_u64_be(123);
 		let commit_key = 1;
 		let mut rng = rand::thread_rng();
-		let random_seed = rng.gen::<u8>();
+		let salt = rng.gen::<Salt>();
 
-		RevealWindow::<Test>::insert(commit_key, 0);
+		open_reveal_window(commit_key);
 
 		assert_err!(
-			Commitments::reveal(alice, original_hash, commit_key, random_seed),
+			Commitments::reveal(alice, original_hash, commit_key, salt),
 			CommitmentError::NoCommitmentFound
 		);
 	})
@@ -150,9 +183,253 @@ fn reveal_fails_if_no_commitment_made() {
 fn recreate_commit_hash_creates_commit() {
 	let mut test_externalities = new_test_ext();
 	test_externalities.execute_with(|| {
+		let alice = get_accounts().pop().unwrap();
 		let original_hash = H256::from_low_u64_be(123);
-		let (original_commit_hash, random_seed) = create_commit_hash(original_hash);
-		let recreated_original = Pallet::<Test>::recreate_commit_hash(original_hash, random_seed);
+		let commit_key = 1;
+		let (original_commit_hash, salt) = create_commit_hash(&alice, commit_key, original_hash);
+		let recreated_original =
+			Pallet::<Test>::recreate_commit_hash(&alice, commit_key, original_hash, &salt);
 		assert_eq!(original_commit_hash, recreated_original);
 	})
 }
+
+#[test]
+fn copied_commitment_can_not_be_revealed() {
+	let mut test_externalities = new_test_ext();
+	test_externalities.execute_with(|| {
+		let mut accounts = get_accounts();
+		let (alice, bob) = (accounts.pop().unwrap(), accounts.pop().unwrap());
+		let original_hash = H256::from_low_u64_be(123);
+		let commit_key = 1;
+		let (commit_hash, salt) = create_commit_hash(&alice, commit_key, original_hash);
+
+		// Bob submits Alice's commitment as his own, and Alice reuses it in another round
+		assert_ok!(Commitments::commit(alice.clone(), commit_hash, commit_key));
+		assert_ok!(Commitments::commit(bob.clone(), commit_hash, commit_key));
+		assert_ok!(Commitments::commit(alice.clone(), commit_hash, commit_key + 1));
+		open_reveal_window(commit_key);
+		open_reveal_window(commit_key + 1);
+
+		// Once Alice revealed, her answer and salt are public but can't fulfill Bob's copy
+		assert_ok!(Commitments::reveal(alice.clone(), original_hash, commit_key, salt));
+		assert_err!(
+			Commitments::reveal(bob, original_hash, commit_key, salt),
+			CommitmentError::IncorrectRevealedHash
+		);
+		assert_err!(
+			Commitments::reveal(alice, original_hash, commit_key + 1, salt),
+			CommitmentError::IncorrectRevealedHash
+		);
+	})
+}
+
+#[test]
+fn first_commit_opens_round() {
+	new_test_ext().execute_with(|| {
+		let account = get_accounts().pop().unwrap();
+		let commit_key = 1;
+
+		assert_ok!(Commitments::commit(account, H256::from_low_u64_be(123), commit_key));
+
+		let phase = RoundPhase::Open { expires_at: 1 + OpenPhaseLength::get() as u64 };
+		assert_eq!(Commitments::get_round(commit_key), Some(phase));
+		System::assert_last_event(phase_changed(commit_key, phase));
+		assert_eq!(Commitments::get_reveal_window(commit_key), None);
+	})
+}
+
+#[test]
+fn round_moves_through_commit_and_reveal_phases() {
+	new_test_ext().execute_with(|| {
+		let mut accounts = get_accounts();
+		let commit_key = 1;
+		assert_ok!(Commitments::commit(
+			accounts.pop().unwrap(),
+			H256::from_low_u64_be(1),
+			commit_key
+		));
+
+		Commitments::start_committing(commit_key);
+		let commit_end = 1 + CommitPhaseLength::get() as u64;
+		let reveal_end = commit_end + RevealWindowLength::get() as u64;
+		System::assert_last_event(phase_changed(
+			commit_key,
+			RoundPhase::Committing { ends_at: commit_end },
+		));
+		assert_eq!(Commitments::get_reveal_window(commit_key), Some((commit_end, reveal_end)));
+
+		// Late commitments are still accepted
+		assert_ok!(Commitments::commit(
+			accounts.pop().unwrap(),
+			H256::from_low_u64_be(2),
+			commit_key
+		));
+
+		run_to_block(commit_end);
+		let revealing = RoundPhase::Revealing { starts_at: commit_end, ends_at: reveal_end };
+		assert_eq!(Commitments::get_round(commit_key), Some(revealing));
+		System::assert_last_event(phase_changed(commit_key, revealing));
+		assert!(Commitments::is_in_reveal_window(commit_key));
+		assert_err!(
+			Commitments::commit(accounts.pop().unwrap(), H256::from_low_u64_be(3), commit_key),
+			CommitmentError::CommitWindowClosed
+		);
+
+		// Only the consumer concludes a round, the reveal phase merely runs out
+		run_to_block(reveal_end + 1);
+		assert!(!Commitments::is_in_reveal_window(commit_key));
+		assert_eq!(Commitments::get_round(commit_key), Some(revealing));
+
+		Commitments::finalize_round(commit_key);
+		System::assert_last_event(phase_changed(commit_key, RoundPhase::Finalized));
+		assert_eq!(Commitments::get_round(commit_key), None);
+		assert_eq!(Commitments::get_commitments(commit_key).len(), 2);
+	})
+}
+
+#[test]
+fn round_without_quorum_expires() {
+	new_test_ext().execute_with(|| {
+		let account = get_accounts().pop().unwrap();
+		let commit_key = 1;
+		assert_ok!(Commitments::commit(account.clone(), H256::from_low_u64_be(1), commit_key));
+
+		let expires_at = 1 + OpenPhaseLength::get() as u64;
+		run_to_block(expires_at - 1);
+		assert!(ExpiredRounds::get().is_empty());
+
+		run_to_block(expires_at);
+		assert_eq!(ExpiredRounds::get(), vec![commit_key]);
+		System::assert_last_event(phase_changed(commit_key, RoundPhase::Expired));
+		assert_eq!(Commitments::get_round(commit_key), None);
+		assert!(Commitments::get_commitments(commit_key).is_empty());
+
+		// The key can be committed to again, in a fresh round
+		assert_ok!(Commitments::commit(account, H256::from_low_u64_be(1), commit_key));
+		assert_eq!(
+			Commitments::get_round(commit_key),
+			Some(RoundPhase::Open { expires_at: expires_at + OpenPhaseLength::get() as u64 })
+		);
+	})
+}
+
+#[test]
+fn start_committing_ignores_rounds_past_open_phase() {
+	new_test_ext().execute_with(|| {
+		let commit_key = 1;
+		Commitments::start_committing(commit_key);
+		assert_eq!(Commitments::get_round(commit_key), None);
+
+		open_reveal_window(commit_key);
+		Commitments::start_committing(commit_key);
+		assert!(matches!(
+			Commitments::get_round(commit_key),
+			Some(RoundPhase::Revealing { .. })
+		));
+	})
+}
+
+#[test]
+fn phase_changes_are_scheduled_by_block() {
+	new_test_ext().execute_with(|| {
+		let db = <Test as frame_system::Config>::DbWeight::get();
+		let expires_at = 1 + OpenPhaseLength::get() as u64;
+		for commit_key in 1..=3 {
+			Commitments::open_round(commit_key);
+		}
+		// The third round doesn't fit in the block its phase runs out at
+		assert_eq!(PhaseChanges::<Test>::get(expires_at).into_inner(), vec![1, 2]);
+		assert_eq!(PhaseChanges::<Test>::get(expires_at + 1).into_inner(), vec![3]);
+
+		// A quorum moves the second round on, its expiry is left to be skipped
+		Commitments::start_committing(2);
+		let commit_end = 1 + CommitPhaseLength::get() as u64;
+		assert_eq!(PhaseChanges::<Test>::get(commit_end).into_inner(), vec![2]);
+
+		// Blocks without scheduled changes only read the schedule
+		System::set_block_number(commit_end - 1);
+		assert_eq!(Commitments::on_initialize(commit_end - 1), db.reads_writes(1, 1));
+
+		System::set_block_number(commit_end);
+		assert_eq!(Commitments::on_initialize(commit_end), db.reads_writes(3, 3));
+		assert!(matches!(Commitments::get_round(2), Some(RoundPhase::Revealing { .. })));
+		assert!(PhaseChanges::<Test>::get(commit_end).is_empty());
+
+		System::set_block_number(expires_at);
+		Commitments::on_initialize(expires_at);
+		assert_eq!(ExpiredRounds::get(), vec![1]);
+		assert!(matches!(Commitments::get_round(2), Some(RoundPhase::Revealing { .. })));
+
+		// Rounds checked late still expire
+		System::set_block_number(expires_at + 1);
+		Commitments::on_initialize(expires_at + 1);
+		assert_eq!(ExpiredRounds::get(), vec![1, 3]);
+		assert_eq!(Rounds::<Test>::iter().count(), 1);
+	})
+}
+
+#[test]
+fn v2_migration_schedules_rounds_in_progress() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<Commitments>();
+		Rounds::<Test>::insert(1, RoundPhase::Open { expires_at: 5 });
+		Rounds::<Test>::insert(2, RoundPhase::Committing { ends_at: 1 });
+		open_reveal_window(3);
+
+		migrations::v2::ScheduleRoundPhaseChanges::<Test>::on_runtime_upgrade();
+
+		assert_eq!(PhaseChanges::<Test>::get(5).into_inner(), vec![1]);
+		// Changes already due are checked on the next block
+		assert_eq!(PhaseChanges::<Test>::get(2).into_inner(), vec![2]);
+		assert_eq!(PhaseChanges::<Test>::iter().count(), 2);
+		assert_eq!(Commitments::on_chain_storage_version(), StorageVersion::new(2));
+
+		// Running it again is a no-op
+		migrations::v2::ScheduleRoundPhaseChanges::<Test>::on_runtime_upgrade();
+		assert_eq!(PhaseChanges::<Test>::get(5).into_inner(), vec![1]);
+	})
+}
+
+#[test]
+fn v1_migration_expires_rounds_in_progress() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<Commitments>();
+		let mut accounts = get_accounts();
+		let (alice, bob) = (accounts.pop().unwrap(), accounts.pop().unwrap());
+		let (scheduled, unrevealed, concluded) = (1, 2, 3);
+		let commit = |commit_key, who: &AccountId32| {
+			Commits::<Test>::try_mutate(commit_key, |commitments| {
+				commitments.try_push(Commitment::new(H256::from_low_u64_be(1), who.clone()))
+			})
+			.unwrap();
+		};
+
+		// A reveal window scheduled the legacy way, a round nobody revealed in yet and a round
+		// that was already revealed in
+		commit(scheduled, &alice);
+		Commits::<Test>::mutate(scheduled, |commitments| {
+			commitments[0].fulfill(H256::from_low_u64_be(1))
+		});
+		migrations::v1::RevealWindow::<Test>::insert(scheduled, 5);
+		commit(unrevealed, &alice);
+		commit(unrevealed, &bob);
+		commit(concluded, &bob);
+		Commits::<Test>::mutate(concluded, |commitments| {
+			commitments[0].fulfill(H256::from_low_u64_be(1))
+		});
+
+		migrations::v1::ExpireLegacyCommitments::<Test>::on_runtime_upgrade();
+
+		assert_eq!(ExpiredRounds::get(), vec![scheduled, unrevealed]);
+		assert!(Commitments::get_commitments(scheduled).is_empty());
+		assert!(Commitments::get_commitments(unrevealed).is_empty());
+		assert_eq!(Commitments::get_commitments(concluded).len(), 1);
+		assert_eq!(migrations::v1::RevealWindow::<Test>::iter().count(), 0);
+		assert_eq!(Commitments::on_chain_storage_version(), StorageVersion::new(1));
+
+		// Running it again is a no-op
+		commit(unrevealed, &alice);
+		migrations::v1::ExpireLegacyCommitments::<Test>::on_runtime_upgrade();
+		assert_eq!(ExpiredRounds::get(), vec![scheduled, unrevealed]);
+	})
+}
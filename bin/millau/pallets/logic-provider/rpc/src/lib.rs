@@ -0,0 +1,83 @@
+//! RPC interface to inspect the rounds of the logic-provider pallet.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use primitives::shared::{Hash, MetadataId, RoundSummary};
+use runtime_api::LogicProviderApi as LogicProviderRuntimeApi;
+use serde::Serialize;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+#[rpc(client, server, namespace = "logicProvider")]
+pub trait LogicProviderApi<BlockHash, Summary> {
+	/// The state of the round for `metadata_id`: its status, the participants along with what
+	/// they revealed so far, the leading answer and the result the round settled on.
+	///
+	/// Returns `None` if there is no such round or it expired.
+	#[method(name = "roundSummary")]
+	fn round_summary(
+		&self,
+		metadata_id: MetadataId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<Summary>>;
+}
+
+/// Error codes returned by the logic-provider RPC.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+/// Serves the [`LogicProviderApiServer`] from the runtime of `client`.
+pub struct LogicProvider<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> LogicProvider<C, Block> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId, Balance>
+	LogicProviderApiServer<<Block as BlockT>::Hash, RoundSummary<AccountId, Balance, Hash>>
+	for LogicProvider<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: LogicProviderRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec + Serialize + Send + Sync + 'static,
+	Balance: Codec + Serialize + Send + Sync + 'static,
+{
+	fn round_summary(
+		&self,
+		metadata_id: MetadataId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<RoundSummary<AccountId, Balance, Hash>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client.runtime_api().round_summary(at, metadata_id).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to query the round summary.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+}
@@ -0,0 +1,148 @@
+//! Strategies to settle the result of a round from the answers revealed in it.
+
+use frame_support::traits::Get;
+use num_rational::Ratio;
+use primitives::shared::{decode_numeric_answer, numeric_answer};
+use sp_core::H256;
+use sp_runtime::{helpers_128bit::multiply_by_rational_with_rounding, PerThing, Perbill, Rounding};
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData, vec::Vec};
+
+/// Turns the answers revealed in a round into the round's result.
+pub trait Aggregator<Value> {
+	/// Settle on a result given every answer revealed in time, along with the weight of its vote.
+	///
+	/// Returns `None` unless the answers agreeing with the result hold more than `threshold` of
+	/// the total weight.
+	fn aggregate(votes: &[(Value, u128)], threshold: Perbill) -> Option<Value>;
+
+	/// Whether `answer` agrees with the `result` the round settled on. Participants whose answer
+	/// does not are slashed.
+	fn agrees(answer: &Value, result: &Value) -> bool;
+}
+
+/// Whether `weight` is more than `threshold` of `total`.
+pub fn exceeds(weight: u128, total: u128, threshold: Perbill) -> bool {
+	total > 0 &&
+		Ratio::new_raw(weight, total) >
+			Ratio::new_raw(threshold.deconstruct() as u128, Perbill::ACCURACY as u128)
+}
+
+/// The result is the answer with the largest share of the votes, which only agrees with itself.
+pub struct ExactMatch;
+
+impl<Value: Ord + Copy> Aggregator<Value> for ExactMatch {
+	fn aggregate(votes: &[(Value, u128)], threshold: Perbill) -> Option<Value> {
+		let mut weights = BTreeMap::<Value, u128>::new();
+		let mut total = 0u128;
+		for (value, weight) in votes {
+			let entry = weights.entry(*value).or_default();
+			*entry = entry.saturating_add(*weight);
+			total = total.saturating_add(*weight);
+		}
+		let (value, weight) = weights.into_iter().max_by_key(|item| item.1)?;
+		exceeds(weight, total, threshold).then_some(value)
+	}
+
+	fn agrees(answer: &Value, result: &Value) -> bool {
+		answer == result
+	}
+}
+
+/// How [`NumericAggregator`] combines the answers into a result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericStrategy {
+	/// The weighted median of the answers.
+	Median,
+	/// The weighted mean of the answers, leaving out the given share of the total weight at
+	/// either end.
+	TrimmedMean(Perbill),
+}
+
+/// Aggregates numeric answers, e.g. power limits. Answers are `u128`s, SCALE-encoded into the
+/// leading bytes of the revealed value (see [`numeric_answer`]).
+///
+/// Answers within `Tolerance` of the result agree with it; they have to hold more than the
+/// threshold of the total weight for the result to be accepted. Answers that do not decode as
+/// numbers never agree.
+pub struct NumericAggregator<Strategy, Tolerance>(PhantomData<(Strategy, Tolerance)>);
+
+impl<Strategy, Tolerance> NumericAggregator<Strategy, Tolerance> {
+	/// The weighted median of `votes`, which must be sorted by answer.
+	fn median(votes: &[(u128, u128)], total: u128) -> Option<u128> {
+		let mut cumulative = 0u128;
+		votes
+			.iter()
+			.find(|(_, weight)| {
+				cumulative = cumulative.saturating_add(*weight);
+				cumulative >= total - total / 2
+			})
+			.map(|(value, _)| *value)
+	}
+
+	/// The weighted mean of `votes`, which must be sorted by answer, leaving out `trim` of the
+	/// total weight at either end.
+	fn trimmed_mean(votes: &[(u128, u128)], total: u128, trim: Perbill) -> Option<u128> {
+		let mut kept = votes.to_vec();
+		let cut = trim * total;
+		Self::drop_weight(kept.iter_mut(), cut);
+		Self::drop_weight(kept.iter_mut().rev(), cut);
+
+		let remaining = kept.iter().fold(0u128, |sum, (_, weight)| sum.saturating_add(*weight));
+		if remaining == 0 {
+			return None
+		}
+		kept.iter().try_fold(0u128, |mean, (value, weight)| {
+			let share =
+				multiply_by_rational_with_rounding(*value, *weight, remaining, Rounding::Down)?;
+			Some(mean.saturating_add(share))
+		})
+	}
+
+	/// Take `amount` of weight away from `votes`, in order.
+	fn drop_weight<'a>(votes: impl Iterator<Item = &'a mut (u128, u128)>, mut amount: u128) {
+		for (_, weight) in votes {
+			let dropped = amount.min(*weight);
+			*weight -= dropped;
+			amount -= dropped;
+		}
+	}
+}
+
+impl<Value, Strategy, Tolerance> Aggregator<Value> for NumericAggregator<Strategy, Tolerance>
+where
+	Value: From<H256> + AsRef<[u8]>,
+	Strategy: Get<NumericStrategy>,
+	Tolerance: Get<u128>,
+{
+	fn aggregate(votes: &[(Value, u128)], threshold: Perbill) -> Option<Value> {
+		let total = votes.iter().fold(0u128, |sum, (_, weight)| sum.saturating_add(*weight));
+		let mut numbers = votes
+			.iter()
+			.filter_map(|(value, weight)| Some((decode_numeric_answer(value.as_ref())?, *weight)))
+			.collect::<Vec<_>>();
+		numbers.sort_unstable_by_key(|(value, _)| *value);
+		let numeric_total =
+			numbers.iter().fold(0u128, |sum, (_, weight)| sum.saturating_add(*weight));
+		if numeric_total == 0 {
+			return None
+		}
+
+		let result = match Strategy::get() {
+			NumericStrategy::Median => Self::median(&numbers, numeric_total)?,
+			NumericStrategy::TrimmedMean(trim) =>
+				Self::trimmed_mean(&numbers, numeric_total, trim)?,
+		};
+		let agreeing = numbers
+			.iter()
+			.filter(|(value, _)| value.abs_diff(result) <= Tolerance::get())
+			.fold(0u128, |sum, (_, weight)| sum.saturating_add(*weight));
+		exceeds(agreeing, total, threshold).then(|| numeric_answer(result).into())
+	}
+
+	fn agrees(answer: &Value, result: &Value) -> bool {
+		match (decode_numeric_answer(answer.as_ref()), decode_numeric_answer(result.as_ref())) {
+			(Some(answer), Some(result)) => answer.abs_diff(result) <= Tolerance::get(),
+			_ => false,
+		}
+	}
+}
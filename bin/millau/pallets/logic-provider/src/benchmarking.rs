@@ -6,18 +6,29 @@ use codec::Encode;
 use crate::benchmarking::vec::Vec;
 #[allow(unused)]
 use crate::Pallet as Template;
-use frame_benchmarking::{account, benchmarks, vec, whitelist_account};
-use frame_support::traits::Currency;
+use frame_benchmarking::{account, benchmarks, vec, whitelist_account, whitelisted_caller};
+use frame_support::{
+	traits::{Currency, Get, Hooks},
+	BoundedVec,
+};
 use frame_system::RawOrigin;
+use pallet_commitments::Reveal;
 use sp_core::H256;
-use sp_io::hashing::blake2_256;
-use sp_runtime::traits::Bounded;
-
-fn recreate_commit_hash(original_hash: H256, random_seed: u8) -> H256 {
-	let mut combined = original_hash.encode();
-	combined.push(random_seed);
-	let hash = blake2_256(&combined);
-	H256(hash)
+use sp_runtime::{traits::Bounded, Perbill};
+
+fn recreate_commit_hash<T: Config>(
+	public: &Public,
+	metadata_id: MetadataId,
+	original_hash: H256,
+	salt: Salt,
+) -> H256 {
+	let who = Pallet::<T>::to_account_id(public.clone()).unwrap();
+	<pallet_commitments::Pallet<T> as Reveal<_, _>>::recreate_commit_hash(
+		&who,
+		metadata_id,
+		original_hash.into(),
+		&salt,
+	)
 }
 
 fn get_pub_keys<T: Config>(len: u32) -> Vec<Public>
@@ -31,6 +42,7 @@ where
 		T::LocalCurrency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 		let encoded_caller = caller.encode();
 		let public: Public = Public::try_from(&encoded_caller[..]).unwrap();
+		T::ParticipantRegistry::activate(&public);
 		keys.push(public);
 	}
 	keys
@@ -41,6 +53,8 @@ benchmarks! {
 		let hash = H256([0; 32]);
 		let public = sp_core::sr25519::Public::from_raw([0;32]);
 		let acct = Pallet::<T>::to_account_id(public.into()).unwrap();
+		T::ParticipantRegistry::activate(&public.into());
+		T::MetadataRequests::open(0);
 		whitelist_account!(acct);
 		T::LocalCurrency::make_free_balance_be(&acct, BalanceOf::<T>::max_value());
 		let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
@@ -57,13 +71,15 @@ benchmarks! {
 	reveal_processing_result_hash {
 		let hash = H256([0; 32]);
 		let public = sp_core::sr25519::Public::from_raw([0;32]);
-		let random_seed: u8 = 10;
+		let salt: Salt = [10; 32];
 		let metadata_id = 1;
+		T::MetadataRequests::open(metadata_id);
 		let acct = Pallet::<T>::to_account_id(public.into()).unwrap();
+		T::ParticipantRegistry::activate(&public.into());
 		whitelist_account!(acct);
 		T::LocalCurrency::make_free_balance_be(&acct, BalanceOf::<T>::max_value());
 
-		let committed_hash = recreate_commit_hash(hash, random_seed);
+		let committed_hash = recreate_commit_hash::<T>(&public.into(), metadata_id, hash, salt);
 		let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 			metadata_id: metadata_id,
 			hash: committed_hash,
@@ -74,16 +90,20 @@ benchmarks! {
 		);
 		Pallet::<T>::commit_processing_result_hash(RawOrigin::None.into(), commit_call, commit_signature.into(), public.into()).unwrap();
 
-		let reveal_window_starting_block = <frame_system::Pallet<T>>::block_number();
-		pallet_commitments::RevealWindow::<T>::insert(
-					metadata_id,
-					reveal_window_starting_block,
-				);
+		let reveal_window_starting_block =
+			<frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+		pallet_commitments::Rounds::<T>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: reveal_window_starting_block,
+				ends_at: reveal_window_starting_block + T::RevealWindowLength::get() as u64,
+			},
+		);
 
 		let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 			metadata_id: metadata_id,
 			reveal_hash: hash,
-			random_seed
+			salt
 		}).encode();
 		let reveal_signature = sp_core::sr25519::Signature::from_raw(
 			hex_literal::hex!("d25f660f3da64719b4b241fc1d50cee735b17f75e87ce576bcf7343cea8ef9132e102e57475b9e6fbb10186fcd877caf254b1244cbfd86d822717297901c268c")
@@ -94,20 +114,23 @@ benchmarks! {
 	issue_rewards_to_some_participants {
 		let metadata_id = 1;
 		let s in 10 .. 1024; // total submissions
+		T::MetadataRequests::open(metadata_id);
 		let correct_hash = H256([0; 32]);
 		let other_hash = H256([1; 32]);
 		let keys = get_pub_keys::<T>(s);
 
 		let public = sp_core::sr25519::Public::from_raw([0;32]);
-		// let random_seed: u8 = 10;
+		// let salt: Salt = [10; 32];
 		let acct = Pallet::<T>::to_account_id(public.into()).unwrap();
+		T::ParticipantRegistry::activate(&public.into());
 		whitelist_account!(acct);
 		T::LocalCurrency::make_free_balance_be(&acct, BalanceOf::<T>::max_value());
 
 		// Commit hash
 		for (index, pub_key) in keys.iter().enumerate() {
 			if index < keys.len() * 2 / 3  {
-				let committed_hash = recreate_commit_hash(correct_hash, index as u8);
+				let committed_hash =
+					recreate_commit_hash::<T>(pub_key, metadata_id, correct_hash, [index as u8; 32]);
 
 				let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 					metadata_id: metadata_id,
@@ -119,7 +142,8 @@ benchmarks! {
 				);
 				Pallet::<T>::commit_processing_result_hash(RawOrigin::None.into(), commit_call, commit_signature.into(), pub_key.clone()).unwrap();
 			} else if index < keys.len() * 3 / 4 {
-				let committed_hash = recreate_commit_hash(other_hash, index as u8);
+				let committed_hash =
+					recreate_commit_hash::<T>(pub_key, metadata_id, other_hash, [index as u8; 32]);
 				let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 					metadata_id: metadata_id,
 					hash: committed_hash,
@@ -130,7 +154,8 @@ benchmarks! {
 				);
 				Pallet::<T>::commit_processing_result_hash(RawOrigin::None.into(), commit_call, commit_signature.into(), pub_key.clone()).unwrap();
 			} else {
-				let committed_hash = recreate_commit_hash(correct_hash, index as u8);
+				let committed_hash =
+					recreate_commit_hash::<T>(pub_key, metadata_id, correct_hash, [index as u8; 32]);
 				let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 					metadata_id: metadata_id,
 					hash: committed_hash,
@@ -143,11 +168,15 @@ benchmarks! {
 			}
 		}
 
-		let reveal_window_starting_block = <frame_system::Pallet<T>>::block_number();
-		pallet_commitments::RevealWindow::<T>::insert(
-					metadata_id,
-					reveal_window_starting_block,
-				);
+		let reveal_window_starting_block =
+			<frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+		pallet_commitments::Rounds::<T>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: reveal_window_starting_block,
+				ends_at: reveal_window_starting_block + T::RevealWindowLength::get() as u64,
+			},
+		);
 
 		// Reveal hash
 		for (index, pub_key) in keys.iter().enumerate() {
@@ -155,7 +184,7 @@ benchmarks! {
 				let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 					metadata_id: metadata_id,
 					reveal_hash: correct_hash,
-					random_seed: index as u8
+					salt: [index as u8; 32]
 				}).encode();
 				let reveal_signature = sp_core::sr25519::Signature::from_raw(
 					hex_literal::hex!("d25f660f3da64719b4b241fc1d50cee735b17f75e87ce576bcf7343cea8ef9132e102e57475b9e6fbb10186fcd877caf254b1244cbfd86d822717297901c268c")
@@ -166,7 +195,7 @@ benchmarks! {
 				let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 					metadata_id: metadata_id,
 					reveal_hash: other_hash,
-					random_seed: index as u8
+					salt: [index as u8; 32]
 				}).encode();
 				let reveal_signature = sp_core::sr25519::Signature::from_raw(
 					hex_literal::hex!("d25f660f3da64719b4b241fc1d50cee735b17f75e87ce576bcf7343cea8ef9132e102e57475b9e6fbb10186fcd877caf254b1244cbfd86d822717297901c268c")
@@ -180,13 +209,15 @@ benchmarks! {
 	issue_rewards_to_all_participants {
 		let metadata_id = 1;
 		let s in 10 .. 1024; // total submissions
+		T::MetadataRequests::open(metadata_id);
 		let correct_hash = H256([0; 32]);
 		let other_hash = H256([1; 32]);
 		let keys = get_pub_keys::<T>(s);
 
 		// Commit hash
 		for (index, pub_key) in keys.iter().enumerate() {
-			let committed_hash = recreate_commit_hash(correct_hash, index as u8);
+			let committed_hash =
+				recreate_commit_hash::<T>(pub_key, metadata_id, correct_hash, [index as u8; 32]);
 			let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id: metadata_id,
 				hash: committed_hash,
@@ -199,18 +230,22 @@ benchmarks! {
 
 		}
 
-		let reveal_window_starting_block = <frame_system::Pallet<T>>::block_number();
-		pallet_commitments::RevealWindow::<T>::insert(
-					metadata_id,
-					reveal_window_starting_block,
-				);
+		let reveal_window_starting_block =
+			<frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+		pallet_commitments::Rounds::<T>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: reveal_window_starting_block,
+				ends_at: reveal_window_starting_block + T::RevealWindowLength::get() as u64,
+			},
+		);
 
 		// Reveal hash
 		for (index, pub_key) in keys.iter().enumerate() {
 			let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id: metadata_id,
 				reveal_hash: correct_hash,
-				random_seed: index as u8
+				salt: [index as u8; 32]
 			}).encode();
 			let reveal_signature = sp_core::sr25519::Signature::from_raw(
 				hex_literal::hex!("d25f660f3da64719b4b241fc1d50cee735b17f75e87ce576bcf7343cea8ef9132e102e57475b9e6fbb10186fcd877caf254b1244cbfd86d822717297901c268c")
@@ -221,19 +256,25 @@ benchmarks! {
 	}: issue_rewards(RawOrigin::None, metadata_id)
 
 	set_majority_type {
-		let majority_type = Majority::OneHalf;
-	}: _(RawOrigin::Root, majority_type)
+		let commit_quorum = Majority::Custom(Perbill::from_percent(75));
+		let reveal_agreement = Majority::OneHalf;
+	}: _(RawOrigin::Root, commit_quorum, reveal_agreement)
+	verify {
+		assert_eq!(Pallet::<T>::reveal_agreement(), Majority::OneHalf);
+	}
 
 	resolve_metadata_dispute {
 		let metadata_id = 1;
 		let s in 10 .. 1024; // total submissions
+		T::MetadataRequests::open(metadata_id);
 		let correct_hash = H256([0; 32]);
 		let other_hash = H256([1; 32]);
 		let keys = get_pub_keys::<T>(s);
 		// commit hash
 		for (index, pub_key) in keys.iter().enumerate() {
 			if index < keys.len() / 2 {
-				let committed_hash = recreate_commit_hash(correct_hash, index as u8);
+				let committed_hash =
+					recreate_commit_hash::<T>(pub_key, metadata_id, correct_hash, [index as u8; 32]);
 				let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 					metadata_id: metadata_id,
 					hash: committed_hash,
@@ -243,7 +284,8 @@ benchmarks! {
 				);
 				Pallet::<T>::commit_processing_result_hash(RawOrigin::None.into(), commit_call, commit_signature.into(), pub_key.clone()).unwrap();
 			} else {
-				let committed_hash = recreate_commit_hash(other_hash, index as u8);
+				let committed_hash =
+					recreate_commit_hash::<T>(pub_key, metadata_id, other_hash, [index as u8; 32]);
 				let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 					metadata_id: metadata_id,
 					hash: committed_hash,
@@ -255,18 +297,22 @@ benchmarks! {
 			}
 		}
 
-		let reveal_window_starting_block = <frame_system::Pallet<T>>::block_number();
-		pallet_commitments::RevealWindow::<T>::insert(
-					metadata_id,
-					reveal_window_starting_block,
-				);
+		let reveal_window_starting_block =
+			<frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+		pallet_commitments::Rounds::<T>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: reveal_window_starting_block,
+				ends_at: reveal_window_starting_block + T::RevealWindowLength::get() as u64,
+			},
+		);
 
 		for (index, pub_key) in keys.iter().enumerate() {
 			if index < keys.len() / 2 {
 				let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 					metadata_id: metadata_id,
 					reveal_hash: correct_hash,
-					random_seed: index as u8
+					salt: [index as u8; 32]
 				}).encode();
 				let reveal_signature = sp_core::sr25519::Signature::from_raw(
 					hex_literal::hex!("d25f660f3da64719b4b241fc1d50cee735b17f75e87ce576bcf7343cea8ef9132e102e57475b9e6fbb10186fcd877caf254b1244cbfd86d822717297901c268c")
@@ -277,7 +323,7 @@ benchmarks! {
 				let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 					metadata_id: metadata_id,
 					reveal_hash: other_hash,
-					random_seed: index as u8
+					salt: [index as u8; 32]
 				}).encode();
 				let reveal_signature = sp_core::sr25519::Signature::from_raw(
 					hex_literal::hex!("d25f660f3da64719b4b241fc1d50cee735b17f75e87ce576bcf7343cea8ef9132e102e57475b9e6fbb10186fcd877caf254b1244cbfd86d822717297901c268c")
@@ -288,4 +334,133 @@ benchmarks! {
 
 		let _  = Pallet::<T>::issue_rewards(RawOrigin::None.into(), 1); // this will return an error
 	}: _(RawOrigin::Root, metadata_id, correct_hash.into()) // resolve in favor of the correct hash
+
+	on_initialize {
+		let r in 1 .. T::MaxQueuedRounds::get(); // rounds whose reveal window ends in this block
+		// Rounds are scheduled in an earlier block, at the latest the one before they end
+		let now = <frame_system::Pallet<T>>::block_number() + 1u32.into();
+		for metadata_id in 0..r {
+			pallet_commitments::Rounds::<T>::insert(
+				metadata_id as MetadataId,
+				pallet_commitments::RoundPhase::Revealing {
+					starts_at: now.saturated_into::<u64>(),
+					ends_at: now.saturated_into::<u64>(),
+				},
+			);
+			Pallet::<T>::schedule_settlement(metadata_id as MetadataId, now.saturated_into::<u64>());
+		}
+		<frame_system::Pallet<T>>::set_block_number(now);
+	}: { Pallet::<T>::on_initialize(now) }
+	verify {
+		assert_eq!(
+			SettlementQueue::<T>::get().len() as u32,
+			r.min(T::MaxQueuedRounds::get()),
+		);
+	}
+
+	settle_round {
+		let metadata_id = 1;
+		let s in 10 .. 1024; // total submissions
+		T::MetadataRequests::open(metadata_id);
+		let correct_hash = H256([0; 32]);
+		let keys = get_pub_keys::<T>(s);
+
+		for (index, pub_key) in keys.iter().enumerate() {
+			let committed_hash =
+				recreate_commit_hash::<T>(pub_key, metadata_id, correct_hash, [index as u8; 32]);
+			let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+				metadata_id: metadata_id,
+				hash: committed_hash,
+			}).encode();
+			let commit_signature =  sp_core::sr25519::Signature::from_raw(
+				hex_literal::hex!("761382dc2ecc84f80fe8b1c8e193f9c950d311bc59f214ed578091f0b6173e69333c11c9076e8874fde7c67b9a7abab086b5bceb9219596b53cf847d3140ce8d")
+			);
+			Pallet::<T>::commit_processing_result_hash(RawOrigin::None.into(), commit_call, commit_signature.into(), pub_key.clone()).unwrap();
+		}
+
+		// The reveal window ends in this very block
+		let now = <frame_system::Pallet<T>>::block_number();
+		pallet_commitments::Rounds::<T>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: now.saturated_into::<u64>(),
+				ends_at: now.saturated_into::<u64>(),
+			},
+		);
+
+		for (index, pub_key) in keys.iter().enumerate() {
+			let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
+				metadata_id: metadata_id,
+				reveal_hash: correct_hash,
+				salt: [index as u8; 32]
+			}).encode();
+			let reveal_signature = sp_core::sr25519::Signature::from_raw(
+				hex_literal::hex!("d25f660f3da64719b4b241fc1d50cee735b17f75e87ce576bcf7343cea8ef9132e102e57475b9e6fbb10186fcd877caf254b1244cbfd86d822717297901c268c")
+			);
+			Pallet::<T>::reveal_processing_result_hash(RawOrigin::None.into(), reveal_call, reveal_signature.into(), pub_key.clone()).unwrap();
+		}
+
+		SettlementQueue::<T>::put(BoundedVec::truncate_from(vec![metadata_id]));
+		RoundsToSettle::<T>::put(1);
+	}: { Pallet::<T>::on_finalize(now) }
+	verify {
+		assert!(SettlementQueue::<T>::get().is_empty());
+		assert_eq!(RoundStates::<T>::get(metadata_id), Some(RoundState::Completed));
+	}
+
+	retry_result_delivery {
+		let metadata_id = 1;
+		let now = <frame_system::Pallet<T>>::block_number();
+		OutboundResults::<T>::insert(
+			metadata_id,
+			OutboundResult {
+				payload: CrossChainResult::new(
+					metadata_id,
+					H256([0; 32]).into(),
+					RoundInfo::default(),
+				),
+				status: DeliveryStatus::Pending,
+				attempts: 1,
+			},
+		);
+		DeliveryRetries::<T>::insert(now.saturated_into::<u64>(), metadata_id, ());
+	}: { Pallet::<T>::retry_deliveries() }
+	verify {
+		assert_eq!(OutboundResults::<T>::get(metadata_id).unwrap().attempts, 2);
+	}
+
+	confirm_delivery {
+		let n in 1 .. 128;
+		for nonce in 1..=n as u64 {
+			let metadata_id = nonce as MetadataId;
+			OutboundResults::<T>::insert(
+				metadata_id,
+				OutboundResult {
+					payload: CrossChainResult::new(
+						metadata_id,
+						H256([0; 32]).into(),
+						RoundInfo::default(),
+					),
+					status: DeliveryStatus::Sent([0; 32]),
+					attempts: 1,
+				},
+			);
+			SentMessages::<T>::insert(nonce, metadata_id);
+		}
+	}: { Pallet::<T>::confirm_delivery(1..=n as u64) }
+	verify {
+		assert_eq!(OutboundResults::<T>::get(1).unwrap().status, DeliveryStatus::Delivered);
+	}
+
+	retry_slash {
+		let metadata_id = 1;
+		let caller: T::AccountId = whitelisted_caller();
+		let participant: T::AccountId = account("participant", 0, 0);
+		// The participant can't cover the slash yet, so it gets deferred
+		Pallet::<T>::slash(&participant, metadata_id, T::FundsToLock::get(), SlashReason::IncorrectResult);
+		T::LocalCurrency::make_free_balance_be(&participant, BalanceOf::<T>::max_value());
+	}: _(RawOrigin::Signed(caller), participant.clone(), metadata_id)
+	verify {
+		assert!(PendingSlashes::<T>::get(&participant, metadata_id).is_none());
+	}
 }
@@ -7,7 +7,7 @@ pub use pallet::*;
 
 use frame_support::{
 	fail,
-	traits::{Currency, LockableCurrency, WithdrawReasons},
+	traits::{Currency, LockableCurrency, OnUnbalanced, WithdrawReasons},
 };
 #[cfg(test)]
 mod mock;
@@ -18,10 +18,18 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod aggregator;
+pub mod migrations;
 pub mod weights;
+pub use aggregator::{Aggregator, ExactMatch, NumericAggregator, NumericStrategy};
 pub use weights::*;
 
-pub use primitives::shared::{Hash, LogicProviderCall, MapToCall, MetadataId, Public};
+pub use bin_types::{CrossChainResult, DeliveryStatus, OutboundResult, RoundInfo};
+pub use pallet_metadata_requests::MetadataRequests;
+pub use pallet_participant_registry::ParticipantRegistry;
+pub use primitives::shared::{
+	Hash, LogicProviderCall, MapToCall, MetadataId, ParticipantReputation, Public, Salt,
+};
 use sp_core::crypto::AccountId32;
 pub use sp_runtime::{RuntimeAppPublic, traits::SaturatedConversion};
 
@@ -33,25 +41,58 @@ pub mod pallet {
 		pallet_prelude::*,
 		traits::ExistenceRequirement,
 	};
-	use primitives::shared::Signature;
+	use primitives::shared::{ParticipantSummary, RoundStatus, RoundSummary, Signature};
 	use sp_std::{fmt::Debug, prelude::*};
 
 	use frame_system::pallet_prelude::*;
 	use itertools::Itertools;
-	use num_rational::Ratio;
-	use pallet_commitments::{Commit, CommitmentError, Reveal};
-	use sp_runtime::traits::CheckedSub;
-	use sp_std::vec::Vec;
+	use pallet_commitments::{Commit, CommitmentError, Reveal, RoundPhase};
+	use sp_runtime::{
+		traits::{CheckedSub, Saturating, Zero},
+		Perbill,
+	};
+	use sp_std::{collections::btree_map::BTreeMap, ops::RangeInclusive, vec::Vec};
 
 	pub type BalanceOf<T> =
 		<<T as Config>::LocalCurrency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub type NegativeImbalanceOf<T> = <<T as Config>::LocalCurrency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::NegativeImbalance;
+	pub type RoundSummaryOf<T> = RoundSummary<
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+		<T as pallet_commitments::Config>::Hash,
+	>;
 
 	pub trait TemplateBridgedXcm<T: Config> {
+		/// Send `result` to the bridged chain, or to the chain of `destination` if it was
+		/// requested from another location. Returns the hash of the message, along with the fees
+		/// its delivery and dispatch are estimated to cost.
 		fn send_transact(
 			origin: OriginFor<T>,
-			proof: Vec<u8>,
-			delivery_and_dispatch_fee: u64,
+			result: CrossChainResult<<T as pallet_commitments::Config>::Hash>,
+			destination: Option<xcm::v3::MultiLocation>,
 		) -> Result<([u8; 32], xcm::v3::MultiAssets), xcm::v3::SendError>;
+
+		/// The nonce the bridge assigned to the last message sent, if it tells. A result is only
+		/// marked as delivered once the bridge confirms the message carrying it, see
+		/// [`Pallet::confirm_delivery`].
+		fn last_sent_nonce() -> Option<u64>;
+	}
+
+	/// Determines how much a participant bonds when committing to a round.
+	///
+	/// Only consulted in stake-weighted mode, where the bonded amount becomes the participant's
+	/// vote weight. The amount actually locked is never less than [`Config::FundsToLock`], so the
+	/// `()` implementation simply bonds `FundsToLock` for everyone.
+	pub trait BondedAmount<AccountId, Balance> {
+		fn bonded_amount(who: &AccountId) -> Balance;
+	}
+
+	impl<AccountId, Balance: Zero> BondedAmount<AccountId, Balance> for () {
+		fn bonded_amount(_who: &AccountId) -> Balance {
+			Zero::zero()
+		}
 	}
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -68,16 +109,75 @@ pub mod pallet {
 		type Reward: Get<BalanceOf<Self>>;
 		#[pallet::constant]
 		type FundsToLock: Get<BalanceOf<Self>>;
+		/// If `true`, every revealed hash is weighted by the stake its submitter bonded, the
+		/// majority is evaluated against the total revealed stake, and rewards are split in
+		/// proportion to the winners' stakes. Otherwise each participant counts as one vote.
+		#[pallet::constant]
+		type StakeWeightedConsensus: Get<bool>;
+		/// Amount bonded by each participant in stake-weighted mode.
+		type BondedAmount: BondedAmount<Self::AccountId, BalanceOf<Self>>;
+		/// Decides which keys may commit. Use `()` to let every key participate.
+		type ParticipantRegistry: ParticipantRegistry<Public>;
+		/// The metadata requested for processing; rounds only accept commitments for open
+		/// requests. Use `()` to accept commitments for any metadata.
+		type MetadataRequests: MetadataRequests<Self::AccountId>;
+		/// Settles the result of a round from the answers revealed in it, e.g. [`ExactMatch`].
+		type Aggregator: Aggregator<<Self as pallet_commitments::Config>::Hash>;
+		/// Score a participant gains for revealing the agreed result within the reveal window.
+		#[pallet::constant]
+		type CorrectRevealScore: Get<u32>;
+		/// Score a participant loses for revealing another result, or for not revealing at all.
+		#[pallet::constant]
+		type FaultPenalty: Get<u32>;
+		/// Score a participant loses for revealing the agreed result after the reveal window.
+		#[pallet::constant]
+		type LateRevealPenalty: Get<u32>;
+		/// Share of a participant's score that fades away every `ReputationDecayPeriod` blocks.
+		#[pallet::constant]
+		type ReputationDecay: Get<Perbill>;
+		#[pallet::constant]
+		type ReputationDecayPeriod: Get<u64>;
+		/// Participants whose score is below this may not commit. `None` lets everybody in.
+		type MinimumReputation: Get<Option<i64>>;
+		/// Maximum number of rounds waiting to be settled once their reveal window is over. Rounds
+		/// that do not fit are queued in a later block.
+		#[pallet::constant]
+		type MaxQueuedRounds: Get<u32>;
+		/// Weight that settling rounds may take up in a single block. Rounds that do not fit stay
+		/// queued for the next block.
+		#[pallet::constant]
+		type MaxRoundSettlementWeight: Get<Weight>;
+		/// Number of times sending the result of a round over the bridge is attempted before it
+		/// is given up on.
+		#[pallet::constant]
+		type MaxDeliveryAttempts: Get<u32>;
+		/// Blocks to wait before sending a result again after a failed attempt. The delay doubles
+		/// with every further failed attempt.
+		#[pallet::constant]
+		type DeliveryRetryDelay: Get<u32>;
+		/// Maximum number of results sent again in a single block, the rest wait for the next one.
+		#[pallet::constant]
+		type MaxDeliveryRetriesPerBlock: Get<u32>;
+		/// Blocks to wait for the bridge to confirm the delivery of a result before it is sent
+		/// again, which counts as another attempt.
+		#[pallet::constant]
+		type DeliveryTimeout: Get<u32>;
 		type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 		type WeightInfo: WeightInfo;
 		// Outer types
 		type LocalCurrency: Currency<<Self as frame_system::Config>::AccountId>
 			+ LockableCurrency<Self::AccountId, Moment = u64>;
+		/// Receives the funds slashed from participants, e.g. a treasury. Use `()` to burn them.
+		type SlashHandler: OnUnbalanced<NegativeImbalanceOf<Self>>;
 		type Bridging: crate::TemplateBridgedXcm<Self>;
 	}
 
+	/// The in-code storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub (super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	// Run per metadata / metadata ids
@@ -89,12 +189,25 @@ pub mod pallet {
 		ManuallyResolved,
 	}
 
-	/// The majority type
+	/// Share of the participants, or of their votes, that has to be exceeded.
 	#[derive(Debug, Clone, PartialEq, Encode, Decode, TypeInfo, MaxEncodedLen)]
 	#[codec(mel_bound())]
 	pub enum Majority {
 		TwoThirds,
 		OneHalf,
+		/// Any share below 100%.
+		Custom(Perbill),
+	}
+
+	/// Why a participant's stake was slashed.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	pub enum SlashReason {
+		/// The revealed answer does not match the participant's commitment.
+		IncorrectReveal,
+		/// The revealed answer differs from the result the round settled on.
+		IncorrectResult,
+		/// The participant did not reveal its answer within the reveal window.
+		MissedReveal,
 	}
 
 	impl Default for Majority {
@@ -104,12 +217,26 @@ pub mod pallet {
 	}
 
 	impl Majority {
-		pub fn to_ratio(self) -> Ratio<u32> {
+		pub fn threshold(&self) -> Perbill {
 			match self {
-				Majority::OneHalf => Ratio::new_raw(50u32, 100u32),
-				Majority::TwoThirds => Ratio::new_raw(66u32, 100u32),
+				Majority::OneHalf => Perbill::from_percent(50),
+				Majority::TwoThirds => Perbill::from_rational(2u32, 3u32),
+				Majority::Custom(threshold) => *threshold,
 			}
 		}
+
+		/// Whether `part` is more than this majority of `total`.
+		pub fn is_exceeded_by(&self, part: u128, total: u128) -> bool {
+			aggregator::exceeds(part, total, self.threshold())
+		}
+
+		/// A threshold of 100% can never be exceeded, and a reveal agreement of less than half
+		/// would let conflicting results through.
+		fn is_valid(&self, is_reveal_agreement: bool) -> bool {
+			let threshold = self.threshold();
+			threshold < Perbill::one() &&
+				(!is_reveal_agreement || threshold >= Perbill::from_percent(50))
+		}
 	}
 
 	impl<T> From<CommitmentError> for Error<T> {
@@ -118,6 +245,8 @@ pub mod pallet {
 				CommitmentError::IncorrectRevealedHash => Error::<T>::IncorrectRevealedHash,
 				CommitmentError::NoCommitmentFound => Error::<T>::NoCommitmentFound,
 				CommitmentError::AlreadyRevealed => Error::<T>::AlreadyRevealed,
+				CommitmentError::CommitWindowClosed => Error::<T>::AttemptedCommitInRevealPeriod,
+				CommitmentError::RoundClosed => Error::<T>::RoundClosed,
 			}
 		}
 	}
@@ -133,8 +262,37 @@ pub mod pallet {
 	pub(super) type CommitmentBlockNumbers<T: Config> =
 		StorageMap<_, Blake2_128Concat, MetadataId, CommittedSubmissions<T>, ValueQuery>;
 
+	pub type ParticipantStakesOf<T> = BoundedVec<
+		(<T as frame_system::Config>::AccountId, BalanceOf<T>),
+		<T as pallet_commitments::Config>::MaxParticipants,
+	>;
+
+	/// The amount each participant has locked for a given metadata.
+	#[pallet::storage]
+	#[pallet::getter(fn get_participant_stakes)]
+	pub(super) type ParticipantStakes<T: Config> =
+		StorageMap<_, Blake2_128Concat, MetadataId, ParticipantStakesOf<T>, ValueQuery>;
+
+	pub type RevealersOf<T> = BoundedVec<
+		<T as frame_system::Config>::AccountId,
+		<T as pallet_commitments::Config>::MaxParticipants,
+	>;
+
+	/// The participants whose reveal was accepted in a settled round. Settling a round drops its
+	/// commitments, this is what tells a reveal included in the block that settled the round.
 	#[pallet::storage]
-	pub(super) type MajorityType<T: Config> = StorageValue<_, Majority, ValueQuery>;
+	pub(super) type SettledReveals<T: Config> =
+		StorageMap<_, Blake2_128Concat, MetadataId, RevealersOf<T>, ValueQuery>;
+
+	/// Share of `MaxParticipants` that has to commit before the reveal window is scheduled.
+	#[pallet::storage]
+	#[pallet::getter(fn commit_quorum)]
+	pub(super) type CommitQuorum<T: Config> = StorageValue<_, Majority, ValueQuery>;
+
+	/// Share of the votes revealed in time that has to agree with a result for it to be accepted.
+	#[pallet::storage]
+	#[pallet::getter(fn reveal_agreement)]
+	pub(super) type RevealAgreement<T: Config> = StorageValue<_, Majority, ValueQuery>;
 
 	/// The storage element that tracks the result of executed consensus for each metadata.
 	#[pallet::storage]
@@ -151,6 +309,77 @@ pub mod pallet {
 		<T as pallet_commitments::Config>::Hash,
 	>;
 
+	/// Slashes that could not be settled when they were issued, by participant and metadata.
+	#[pallet::storage]
+	#[pallet::getter(fn get_pending_slash)]
+	pub(super) type PendingSlashes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		MetadataId,
+		(BalanceOf<T>, SlashReason),
+	>;
+
+	/// Rounds whose reveal window is over and that are waiting to be settled, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn settlement_queue)]
+	pub(super) type SettlementQueue<T: Config> =
+		StorageValue<_, BoundedVec<MetadataId, T::MaxQueuedRounds>, ValueQuery>;
+
+	/// How many rounds at the front of the `SettlementQueue` are settled at the end of the current
+	/// block, as charged for in `on_initialize`. Cleared in `on_finalize`.
+	#[pallet::storage]
+	pub(super) type RoundsToSettle<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The rounds whose reveal window ends at a given block, queued for settlement in
+	/// `on_initialize` of that block. Rounds that don't fit in a block are moved to the next one.
+	#[pallet::storage]
+	#[pallet::getter(fn rounds_ending_at)]
+	pub(super) type RoundsEndingAt<T: Config> =
+		StorageMap<_, Twox64Concat, u64, BoundedVec<MetadataId, T::MaxQueuedRounds>, ValueQuery>;
+
+	/// The results sent over the bridge, and how far they got.
+	#[pallet::storage]
+	#[pallet::getter(fn outbound_result)]
+	pub(super) type OutboundResults<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		MetadataId,
+		OutboundResult<<T as pallet_commitments::Config>::Hash>,
+	>;
+
+	/// Results to send again, or whose delivery confirmation times out, by the block at which to
+	/// do so.
+	#[pallet::storage]
+	pub(super) type DeliveryRetries<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, u64, Blake2_128Concat, MetadataId, (), OptionQuery>;
+
+	/// The metadata whose result is carried by each bridge message waiting for a delivery
+	/// confirmation, by message nonce.
+	#[pallet::storage]
+	pub(super) type SentMessages<T: Config> =
+		StorageMap<_, Twox64Concat, u64, MetadataId, OptionQuery>;
+
+	/// The nonce of the next result sent over the bridge. Only sends that succeeded use one up, so
+	/// the receiving side sees the nonces strictly increase.
+	#[pallet::storage]
+	#[pallet::getter(fn next_result_nonce)]
+	pub(super) type NextResultNonce<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// Where the results of the metadata requested by other chains are sent, instead of the
+	/// default destination of [`Config::Bridging`].
+	#[pallet::storage]
+	#[pallet::getter(fn result_destination)]
+	pub(super) type ResultDestinations<T: Config> =
+		StorageMap<_, Blake2_128Concat, MetadataId, xcm::v3::MultiLocation, OptionQuery>;
+
+	/// How every participant behaved across the rounds it took part in. Scores are stored as of
+	/// `last_decayed_at`; use [`Pallet::reputation`] to read them decayed up to now.
+	#[pallet::storage]
+	pub(super) type Reputation<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, ParticipantReputation, ValueQuery>;
+
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/v3/runtime/events-and-errors
 	#[pallet::event]
@@ -158,6 +387,35 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		HashCommitted(<T as pallet_commitments::Config>::Hash),
 		HashRevealed(<T as pallet_commitments::Config>::Hash),
+		/// `amount` was slashed from `who` and handed to the `SlashHandler`.
+		ParticipantSlashed {
+			who: T::AccountId,
+			metadata_id: MetadataId,
+			amount: BalanceOf<T>,
+			reason: SlashReason,
+		},
+		/// Slashing `amount` from `who` failed; it is kept in `PendingSlashes` to be retried.
+		SlashDeferred {
+			who: T::AccountId,
+			metadata_id: MetadataId,
+			amount: BalanceOf<T>,
+			reason: SlashReason,
+		},
+		/// The majorities required for a round to proceed were changed.
+		MajorityTypeSet { commit_quorum: Majority, reveal_agreement: Majority },
+		/// The result of `metadata_id` was handed to the bridge as the message `message_hash`,
+		/// whose delivery and dispatch are estimated to cost `fees`.
+		ResultSent { metadata_id: MetadataId, message_hash: [u8; 32], fees: xcm::v3::MultiAssets },
+		/// Sending the result of `metadata_id` failed, it is sent again at `retry_at`.
+		ResultSendFailed { metadata_id: MetadataId, attempts: u32, retry_at: u64 },
+		/// Sending the result of `metadata_id` failed, or its delivery was not confirmed,
+		/// `MaxDeliveryAttempts` times, it is given up on.
+		ResultDeliveryFailed { metadata_id: MetadataId },
+		/// The bridge did not confirm the delivery of the result of `metadata_id` within
+		/// `DeliveryTimeout` blocks of its last attempt.
+		ResultDeliveryTimedOut { metadata_id: MetadataId, attempts: u32 },
+		/// The bridge confirmed the delivery of the result of `metadata_id`.
+		ResultDelivered { metadata_id: MetadataId },
 	}
 
 	// Errors inform users that something went wrong.
@@ -196,39 +454,47 @@ pub mod pallet {
 		InvalidCallPayload,
 		/// Incoming vector containing payload was larger than expected
 		EncodedCallTooLarge,
+		/// The submitting key does not belong to an active registered participant
+		NotRegisteredParticipant,
+		/// Attempted to commit to a round that was already concluded
+		RoundClosed,
+		/// There is no pending slash for the given participant and metadata
+		NoPendingSlash,
+		/// The participant's reputation is below `MinimumReputation`
+		ReputationTooLow,
+		/// The majority can never be reached, or would accept conflicting results
+		InvalidMajority,
+		/// The metadata was not requested for processing, or the request is closed
+		RequestNotOpen,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		// During finalization, check what metadatas are ready
-		// to be scheduled & schedule them.
+		// Settle the rounds charged for in on_initialize, the rest of the queue is left for the
+		// next block.
 		fn on_finalize(_current_block: BlockNumberFor<T>) {
-			for (metadata_id, starting_block) in pallet_commitments::RevealWindow::<T>::iter() {
-				if <frame_system::Pallet<T>>::block_number().saturated_into::<u64>() >=
-					starting_block + T::RevealWindowLength::get() as u64
-				{
-					if let Err(error) = Pallet::<T>::issue_rewards(
-						RawOrigin::None.into(),
-						metadata_id,
-					) {
-						log::error!(target: "runtime::template", "Consensus for metadata {} has failed. ({:?})", metadata_id, error);
-						// Consensus not reached.
-						RoundStates::<T>::insert(metadata_id, RoundState::Disputed);
-					}
+			let mut due = SettlementQueue::<T>::get().into_inner();
+			let remaining = due.split_off((RoundsToSettle::<T>::take() as usize).min(due.len()));
+			SettlementQueue::<T>::put(BoundedVec::truncate_from(remaining));
 
-					pallet_commitments::RevealWindow::<T>::remove(metadata_id);
-				}
+			for metadata_id in due {
+				Self::settle_round(metadata_id);
 			}
 		}
 
-		// Return the weight consumed in on_finalize to make sure
-		// that the block does not get overweight
-		// due to the computations in on_finalize
+		// Queue the rounds whose reveal window is over by the end of this block, and charge for
+		// settling as many of them as fit in `MaxRoundSettlementWeight` in on_finalize, so that
+		// the block does not get overweight.
 		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
-			<T as Config>::WeightInfo::on_finalize(
-				pallet_commitments::RevealWindow::<T>::iter_keys().count() as u32,
-				T::MaxParticipants::get(),
-			)
+			let retry_weight = Self::retry_deliveries();
+
+			let scanned = Self::queue_finished_rounds();
+			let (count, settlement_weight) = Self::plan_settlement();
+			RoundsToSettle::<T>::put(count);
+
+			<T as Config>::WeightInfo::on_initialize(scanned)
+				.saturating_add(settlement_weight)
+				.saturating_add(retry_weight)
 		}
 	}
 
@@ -237,17 +503,20 @@ pub mod pallet {
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Commit the resulting hash wrapped with random seed of metadata processing.
+		/// Commit the resulting hash wrapped with a salt of metadata processing.
 		///
 		/// # Parameters
 		/// * `metadata_id` - the metadatar id
-		/// * `hash` - the hashed result (resultant hash + random seed)
+		/// * `hash` - the hashed result (resultant hash + salt)
 		/// * `public` - the public key of the sender
 		///
 		/// # Errors
 		/// Returns a `SubmissionExceedsMaxParticipantCount` error when this submission exceeds the
 		/// maximum number of consensus participants.
 		/// Returns a `AlreadyCommitted` error when this submission is duplicate.
+		/// Returns a `NotRegisteredParticipant` error when `public` is not an active participant of
+		/// the configured `ParticipantRegistry`.
+		/// Returns a `ReputationTooLow` error when the sender's score is below `MinimumReputation`.
 		#[pallet::weight(<T as Config>::WeightInfo::commit_processing_result_hash())]
 		pub fn commit_processing_result_hash(
 			origin: OriginFor<T>,
@@ -256,7 +525,12 @@ pub mod pallet {
 			public: Public,
 		) -> DispatchResult {
 			ensure_none(origin)?;
+			ensure!(
+				T::ParticipantRegistry::is_active(&public),
+				Error::<T>::NotRegisteredParticipant
+			);
 			let who = Self::to_account_id(public)?;
+			ensure!(Self::has_sufficient_reputation(&who), Error::<T>::ReputationTooLow);
 			ensure!(
 				payload.len() < T::MaxCallPayloadLength::get().into(),
 				Error::<T>::EncodedCallTooLarge
@@ -285,19 +559,24 @@ pub mod pallet {
 					!ProcessedHashes::<T>::contains_key(metadata_id),
 					Error::<T>::AlreadyProcessedMetadata
 				);
+				ensure!(
+					T::MetadataRequests::is_open(metadata_id),
+					Error::<T>::RequestNotOpen
+				);
 				ensure!(
 					!pallet_commitments::Pallet::<T>::is_in_reveal_window(metadata_id),
 					Error::<T>::AttemptedCommitInRevealPeriod
 				);
 
+				let stake = Self::stake_to_bond(&who);
 				let new_balance = T::LocalCurrency::free_balance(&who)
-					.checked_sub(&T::FundsToLock::get())
+					.checked_sub(&stake)
 					.ok_or(Error::<T>::InsufficientBalance)?;
 
 				// check for balance is available to lock or not
 				T::LocalCurrency::ensure_can_withdraw(
 					&who,
-					T::FundsToLock::get(),
+					stake,
 					WithdrawReasons::all(),
 					new_balance,
 				)?;
@@ -306,11 +585,12 @@ pub mod pallet {
 					metadata_id,
 					hash.into(),
 					who.clone(),
+					stake,
 				)?;
 
 				// Lock afterwards to ensure that lock only happens after checks in commit
 				// pallet
-				Self::lock_tokens(metadata_id, &who, T::FundsToLock::get())
+				Self::lock_tokens(metadata_id, &who, stake)
 			} else {
 				fail!(Error::<T>::InvalidCallPayload);
 			}
@@ -320,10 +600,14 @@ pub mod pallet {
 		///
 		/// # Parameters
 		/// * `original_hash` - the original hash
-		/// * `random_seed` - any random seed which was use at the time of commitment
+		/// * `salt` - the salt which was used at the time of commitment
 		/// * `metadata_id` - the metadatar id
 		/// * `public` - the public key of the sender
 		///
+		/// A reveal that doesn't match its commitment slashes the stake of the participant and
+		/// drops its commitment. The call still succeeds in that case, so that the penalty isn't
+		/// rolled back along with it.
+		///
 		/// # Errors
 		/// Returns a `CommitmentError` error generated from commitment pallet.
 		/// Returns a `InvalidGivenPublicKey` error when the provided public key is invalid
@@ -344,7 +628,7 @@ pub mod pallet {
 				MapToCall::decode(&mut &payload[..]).map_err(|_| Error::<T>::InvalidCallPayload)?;
 			if let MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
-				random_seed,
+				salt,
 				reveal_hash,
 				..
 			}) = decoded_call
@@ -353,14 +637,20 @@ pub mod pallet {
 					who.clone(),
 					reveal_hash.into(),
 					metadata_id,
-					random_seed,
+					salt,
 				);
-				if reveal_result.is_err() {
-					if reveal_result.as_ref().unwrap_err() ==
-						&CommitmentError::IncorrectRevealedHash
-					{
+				match reveal_result {
+					Ok(()) => {
+						Self::deposit_event(Event::HashRevealed(reveal_hash.into()));
+						Ok(())
+					},
+					Err(CommitmentError::IncorrectRevealedHash) => {
+						let stake = Self::participant_stakes(metadata_id)
+							.get(&who)
+							.copied()
+							.unwrap_or_else(T::FundsToLock::get);
 						Self::release_tokens(metadata_id, &who);
-						Self::burn_tokens(&who, T::FundsToLock::get());
+						Self::slash(&who, metadata_id, stake, SlashReason::IncorrectReveal);
 						CommitmentBlockNumbers::<T>::try_mutate::<
 							MetadataId,
 							(),
@@ -374,6 +664,9 @@ pub mod pallet {
 							committed_submission.remove(index);
 							Ok(())
 						})?;
+						ParticipantStakes::<T>::mutate(metadata_id, |stakes| {
+							stakes.retain(|(account, _)| account != &who)
+						});
 						pallet_commitments::Commits::<T>::try_mutate::<
 							MetadataId,
 							(),
@@ -387,12 +680,11 @@ pub mod pallet {
 							committed_submission.remove(index);
 							Ok(())
 						})?;
-					}
-					fail!(Error::<T>::from(reveal_result.unwrap_err()));
+						// Failing here would roll the slash and the clean up back
+						Ok(())
+					},
+					Err(err) => fail!(Error::<T>::from(err)),
 				}
-				Self::deposit_event(Event::HashRevealed(reveal_hash.into()));
-
-				Ok(())
 			} else {
 				fail!(Error::<T>::InvalidCallPayload)
 			}
@@ -405,7 +697,7 @@ pub mod pallet {
 		/// will *not* get any reward.
 		/// The correct solution in the hash which occurs in more that majority of the submissions
 		/// (e.g. 67 out of 100 nodes submit the same hash, regardless of the block number, or >50%
-		/// - that's configured using the [`MajorityType`]).
+		/// - that's configured using the [`RevealAgreement`]).
 		///
 		/// # Parameters
 		/// * `metadata_id` - the metadata which reached the target block_number for
@@ -421,37 +713,29 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			metadata_id: MetadataId,
 		) -> DispatchResult {
-			ensure_none(origin.clone())?;
+			ensure_none(origin)?;
 			match Self::calculate_rewards(metadata_id, None) {
-				Ok((winners, reward)) => {
-					Self::do_issue_rewards(&winners, reward);
+				Ok((winning_hash, winners)) => {
+					Self::do_issue_rewards(&winners);
 					RoundStates::<T>::insert(metadata_id, RoundState::Completed);
 
-					let winning_hash = pallet_commitments::Commits::<T>::get(metadata_id)
-						.into_iter()
-						.find(|commitment| commitment.get_submitter() == &winners[0])
-						.map(|commitment| {
-							commitment.get_fulfillment().map(ToOwned::to_owned).unwrap()
-						})
-						.ok_or(Error::<T>::ConsensusError)?;
-
 					Self::release_tokens_of_participants(metadata_id)?;
-					Self::burn_eligible_account_tokens(&winning_hash, metadata_id)?;
+					Self::slash_eligible_accounts(&winning_hash, metadata_id)?;
+					Self::update_reputation(&winning_hash, metadata_id);
+					let round_info = Self::round_info(metadata_id, &winning_hash);
 
 					// cleaning up storage
 					pallet_commitments::Commits::<T>::remove(metadata_id);
 					CommitmentBlockNumbers::<T>::remove(metadata_id);
+					ParticipantStakes::<T>::remove(metadata_id);
 					ProcessedHashes::<T>::insert(metadata_id, winning_hash);
+					if let Some(location) = T::MetadataRequests::reply_to(metadata_id) {
+						ResultDestinations::<T>::insert(metadata_id, location);
+					}
+					Self::close_request(metadata_id, &winners);
 
-					// Consider moving out into a shared crate. What we end up sending should
-					// probably end up being something that can prove what happened at the pallet
-					// level here, but probably not know too much about this business logic
-					let proof = (metadata_id, winning_hash).encode();
-
-					let _xcm_send = T::Bridging::send_transact(
-						origin, proof, // Enough gas
-						4259640838,
-					);
+					let payload = CrossChainResult::new(metadata_id, winning_hash, round_info);
+					Self::send_result(payload, 0);
 
 					Ok(())
 				},
@@ -463,16 +747,32 @@ pub mod pallet {
 			}
 		}
 
-		/// Set majority type.
-		/// Use this extrinsic to set majority type in runtime.
+		/// Set the majorities required for a round to proceed.
 		/// # Parameters
-		/// * `majority_type` - the [majority type][`Majority`] to set.
+		/// * `commit_quorum` - the share of `MaxParticipants` that has to commit before the reveal
+		///   window is scheduled.
+		/// * `reveal_agreement` - the share of the votes revealed in time that has to agree with
+		///   a result for it to be accepted.
+		///
+		/// # Errors
+		/// Returns a `InvalidMajority` error when either majority is 100% or more, or when
+		/// `reveal_agreement` is below 50%.
 		#[pallet::weight(<T as Config>::WeightInfo::set_majority_type())]
-		pub fn set_majority_type(origin: OriginFor<T>, majority_type: Majority) -> DispatchResult {
+		pub fn set_majority_type(
+			origin: OriginFor<T>,
+			commit_quorum: Majority,
+			reveal_agreement: Majority,
+		) -> DispatchResult {
 			let _ = T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(
+				commit_quorum.is_valid(false) && reveal_agreement.is_valid(true),
+				Error::<T>::InvalidMajority
+			);
 
-			MajorityType::<T>::put(majority_type);
+			CommitQuorum::<T>::put(commit_quorum.clone());
+			RevealAgreement::<T>::put(reveal_agreement.clone());
 
+			Self::deposit_event(Event::MajorityTypeSet { commit_quorum, reveal_agreement });
 			Ok(())
 		}
 
@@ -494,19 +794,20 @@ pub mod pallet {
 				Error::<T>::IllegalState
 			);
 			match Self::calculate_rewards(metadata_id, Some(force_hash)) {
-				Ok((winners, reward)) => {
-					Self::do_issue_rewards(&winners, reward);
+				Ok((_, winners)) => {
+					Self::do_issue_rewards(&winners);
 					RoundStates::<T>::insert(metadata_id, RoundState::ManuallyResolved);
+					Self::close_request(metadata_id, &winners);
 
 					Self::release_tokens_of_participants(metadata_id)?;
 
-					Self::burn_eligible_account_tokens(&force_hash, metadata_id)?;
+					Self::slash_eligible_accounts(&force_hash, metadata_id)?;
+					Self::update_reputation(&force_hash, metadata_id);
 
 					// cleaning up storage
 					pallet_commitments::Commits::<T>::remove(metadata_id);
 					CommitmentBlockNumbers::<T>::remove(metadata_id);
-
-					CommitmentBlockNumbers::<T>::remove(metadata_id);
+					ParticipantStakes::<T>::remove(metadata_id);
 
 					Ok(())
 				},
@@ -514,6 +815,28 @@ pub mod pallet {
 			}
 		}
 
+		/// Retry a slash that could not be settled when it was issued.
+		///
+		/// Anyone may call this, e.g. once the participant's free balance covers the amount again.
+		///
+		/// # Errors
+		/// Returns a `NoPendingSlash` error when nothing is owed by `who` for `metadata_id`.
+		/// Returns a `InsufficientBalance` error when the slash still can't be settled.
+		#[pallet::weight(<T as Config>::WeightInfo::retry_slash())]
+		pub fn retry_slash(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			metadata_id: MetadataId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+			let (amount, reason) =
+				PendingSlashes::<T>::get(&who, metadata_id).ok_or(Error::<T>::NoPendingSlash)?;
+			Self::try_slash(&who, metadata_id, amount, reason)
+				.map_err(|_| Error::<T>::InsufficientBalance)?;
+			PendingSlashes::<T>::remove(&who, metadata_id);
+			Ok(())
+		}
+
 		#[pallet::weight(10000000)]
 		/// Some call that muse be defined and interpreted(decoded on the target chain)
 		pub fn target_chain_call(origin: OriginFor<T>, _val_1: u8, _val_2: u8) -> DispatchResult {
@@ -527,6 +850,7 @@ pub mod pallet {
 			metadata_id: MetadataId,
 			hash: <T as pallet_commitments::Config>::Hash,
 			submitter: T::AccountId,
+			stake: BalanceOf<T>,
 		) -> DispatchResult {
 			let state = RoundStates::<T>::get(metadata_id);
 			ensure!(state.is_none(), Error::<T>::IllegalState);
@@ -543,32 +867,33 @@ pub mod pallet {
 						metadata_id,
 						|committed_submission| {
 							committed_submission
-								.try_push((submitter, current_block))
+								.try_push((submitter.clone(), current_block))
 								.map_err(|_| Error::<T>::SubmissionExceedsMaxParticipantCount)
 						},
 					)?;
-					let commitment_count =
-						CommitmentBlockNumbers::<T>::get(metadata_id).len() as u32;
-					let commit_ratio =
-						Ratio::<u32>::new_raw(commitment_count as u32, T::MaxParticipants::get());
-
-					// If the amount of committers comprises a majority of available committers
-					// and we have not yet set a scheduled "reveal" window for this new key
-					if commit_ratio > MajorityType::<T>::get().to_ratio() &&
-						pallet_commitments::Pallet::<T>::get_reveal_window_start(
-							metadata_id,
-						)
-						.is_none()
-					{
-						let reveal_window_starting_block: u64 =
-							current_block + T::RevealWindowLength::get() as u64;
-
-						// Designate the first block of the reveal period, where participants can
-						// begin revealing answers
-						pallet_commitments::RevealWindow::<T>::insert(
-							metadata_id,
-							reveal_window_starting_block,
-						);
+					ParticipantStakes::<T>::try_mutate(metadata_id, |stakes| {
+						stakes
+							.try_push((submitter, stake))
+							.map_err(|_| Error::<T>::SubmissionExceedsMaxParticipantCount)
+					})?;
+					let commitment_count = CommitmentBlockNumbers::<T>::get(metadata_id).len();
+
+					// If the amount of committers comprises a quorum of available committers,
+					// close the open phase of the round; the reveal window follows once the
+					// commit phase is over. Rounds past their open phase are left as they are.
+					let quorum = CommitQuorum::<T>::get()
+						.is_exceeded_by(commitment_count as u128, T::MaxParticipants::get().into());
+					if quorum &&
+						matches!(
+							pallet_commitments::Pallet::<T>::round_phase(metadata_id),
+							Some(RoundPhase::Open { .. })
+						) {
+						pallet_commitments::Pallet::<T>::start_committing(metadata_id);
+						if let Some((_, ends_at)) =
+							pallet_commitments::Pallet::<T>::get_reveal_window(metadata_id)
+						{
+							Self::schedule_settlement(metadata_id, ends_at);
+						}
 					}
 					Self::deposit_event(Event::HashCommitted(hash));
 					Ok(())
@@ -577,11 +902,16 @@ pub mod pallet {
 			}
 		}
 
+		/// Determine the result of a round, and its winners along with the reward each of them is
+		/// entitled to.
 		#[allow(clippy::type_complexity)]
 		pub fn calculate_rewards(
 			metadata_id: MetadataId,
 			force_correct_result: Option<<T as pallet_commitments::Config>::Hash>,
-		) -> Result<(Vec<T::AccountId>, BalanceOf<T>), Error<T>> {
+		) -> Result<
+			(<T as pallet_commitments::Config>::Hash, Vec<(T::AccountId, BalanceOf<T>)>),
+			Error<T>,
+		> {
 			// AccountId and their block_num when they submits the result in the form of
 			// CommittedSubmissions.
 			let committed_blocks = CommitmentBlockNumbers::<T>::get(metadata_id);
@@ -601,18 +931,19 @@ pub mod pallet {
 
 			ensure!(!final_submissions_vec.is_empty(), Error::<T>::ConsensusNotReached);
 
-			// get most frequent hashes
-			let most_frequent_hash =
-				Self::get_most_frequent_hash(&final_submissions_vec, force_correct_result)?;
+			let stakes = Self::participant_stakes(metadata_id);
+
+			let result =
+				Self::aggregate_result(&final_submissions_vec, &stakes, force_correct_result)?;
 
 			// Arrange submissions as per block number
 			let mut submissions_with_correct_order =
 				committed_blocks.into_iter().sorted_by_key(|(_acc, block)| *block);
 
-			// Figures out the correct submissions by assuming the correct hash is most frequent one
+			// Figures out the submissions that agree with the result
 			let correct_submissions = final_submissions_vec
 				.into_iter()
-				.filter(|submission| submission.1 == most_frequent_hash);
+				.filter(|submission| T::Aggregator::agrees(&submission.1, &result));
 
 			// list of account_ids who submitted correct result
 			let accounts_with_correct_submission =
@@ -642,46 +973,423 @@ pub mod pallet {
 				.collect::<Vec<<T as frame_system::Config>::AccountId>>();
 
 			// amount which will be rewarded to winning participants
-			let reward_for_each_participant =
-				T::Reward::get() / (participants_to_reward.len() as u32).into();
-			Ok((participants_to_reward, reward_for_each_participant))
+			Ok((result, Self::share_reward(participants_to_reward, &stakes)))
+		}
+
+		/// Split [`Config::Reward`] between the winners: evenly, or in proportion to their stakes
+		/// in stake-weighted mode.
+		fn share_reward(
+			winners: Vec<T::AccountId>,
+			stakes: &BTreeMap<T::AccountId, BalanceOf<T>>,
+		) -> Vec<(T::AccountId, BalanceOf<T>)> {
+			if T::StakeWeightedConsensus::get() {
+				let stake_of = |account: &T::AccountId| {
+					stakes.get(account).copied().unwrap_or_else(T::FundsToLock::get)
+				};
+				let total_stake = winners.iter().fold(BalanceOf::<T>::zero(), |total, account| {
+					total.saturating_add(stake_of(account))
+				});
+				winners
+					.into_iter()
+					.map(|account| {
+						let share = Perbill::from_rational(stake_of(&account), total_stake);
+						(account, share * T::Reward::get())
+					})
+					.collect()
+			} else {
+				let reward_for_each_participant =
+					T::Reward::get() / (winners.len() as u32).into();
+				winners.into_iter().map(|account| (account, reward_for_each_participant)).collect()
+			}
 		}
 
-		fn get_most_frequent_hash(
+		/// Settle on the result of a round through [`Config::Aggregator`], unless it is forced.
+		fn aggregate_result(
 			submissions_vec: &[(T::AccountId, <T as pallet_commitments::Config>::Hash)],
+			stakes: &BTreeMap<T::AccountId, BalanceOf<T>>,
 			force_correct_result: Option<<T as pallet_commitments::Config>::Hash>,
 		) -> Result<<T as pallet_commitments::Config>::Hash, Error<T>> {
 			if let Some(correct_result) = force_correct_result {
 				return Ok(correct_result)
 			}
-			let mut hash_counts = sp_std::collections::btree_map::BTreeMap::<
-				<T as pallet_commitments::Config>::Hash,
-				u16,
-			>::new();
-			for hash in submissions_vec.iter().map(|submission| submission.1) {
-				*hash_counts.entry(hash).or_default() += 1;
+			let threshold = RevealAgreement::<T>::get().threshold();
+			T::Aggregator::aggregate(&Self::votes(submissions_vec, stakes), threshold)
+				.ok_or(Error::<T>::ConsensusNotReached)
+		}
+
+		/// Pair every answer with the weight of its vote.
+		fn votes(
+			submissions_vec: &[(T::AccountId, <T as pallet_commitments::Config>::Hash)],
+			stakes: &BTreeMap<T::AccountId, BalanceOf<T>>,
+		) -> Vec<(<T as pallet_commitments::Config>::Hash, u128)> {
+			// Each submission counts once, unless we weigh it by the submitter's stake
+			let vote_weight = |account: &T::AccountId| -> u128 {
+				if T::StakeWeightedConsensus::get() {
+					stakes
+						.get(account)
+						.copied()
+						.unwrap_or_else(T::FundsToLock::get)
+						.saturated_into::<u128>()
+				} else {
+					1
+				}
+			};
+			submissions_vec.iter().map(|(account, hash)| (*hash, vote_weight(account))).collect()
+		}
+
+		/// Have the round of `metadata_id` queued for settlement at block `ends_at`, or at the
+		/// first block from then on with room left. The current block was already initialized,
+		/// so a round whose window already ended is queued on the next block.
+		pub(crate) fn schedule_settlement(metadata_id: MetadataId, ends_at: u64) {
+			let current_block = <frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+			let mut at = ends_at.max(current_block + 1);
+			while RoundsEndingAt::<T>::mutate(at, |due| due.try_push(metadata_id)).is_err() {
+				at += 1;
+			}
+		}
+
+		/// Append the rounds whose reveal window ends in the current block to the
+		/// `SettlementQueue`, as long as it has room. Returns the number of rounds looked at.
+		fn queue_finished_rounds() -> u32 {
+			let current_block = <frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+			let due = RoundsEndingAt::<T>::take(current_block);
+			let scanned = due.len() as u32;
+			let mut left_over = Vec::new();
+			SettlementQueue::<T>::mutate(|queue| {
+				for metadata_id in due {
+					match pallet_commitments::Pallet::<T>::round_phase(metadata_id) {
+						Some(RoundPhase::Revealing { ends_at, .. })
+							if ends_at <= current_block && !queue.contains(&metadata_id) =>
+							if queue.try_push(metadata_id).is_err() {
+								left_over.push(metadata_id);
+							},
+						_ => {},
+					}
+				}
+			});
+			// The queue is full, the remaining rounds are looked at again on the next block
+			for metadata_id in left_over {
+				Self::schedule_settlement(metadata_id, current_block + 1);
+			}
+			scanned
+		}
+
+		/// How many rounds at the front of the `SettlementQueue` fit in
+		/// `MaxRoundSettlementWeight`, along with the weight of settling them. The first round is
+		/// always included, so that a round heavier than the budget can't hold up the queue.
+		fn plan_settlement() -> (u32, Weight) {
+			let budget = T::MaxRoundSettlementWeight::get();
+			let mut count = 0;
+			let mut weight = Weight::zero();
+			for metadata_id in SettlementQueue::<T>::get() {
+				let submissions =
+					pallet_commitments::Commits::<T>::decode_len(metadata_id).unwrap_or_default();
+				let total = weight
+					.saturating_add(<T as Config>::WeightInfo::settle_round(submissions as u32));
+				if count > 0 && total.any_gt(budget) {
+					break
+				}
+				count += 1;
+				weight = total;
+			}
+			(count, weight)
+		}
+
+		/// How the round of `metadata_id` went, to be sent along with its `result`. Must be called
+		/// before the commitments of the round are cleaned up.
+		fn round_info(
+			metadata_id: MetadataId,
+			result: &<T as pallet_commitments::Config>::Hash,
+		) -> RoundInfo {
+			let commitments = pallet_commitments::Pallet::<T>::get_commitments(metadata_id);
+			let agreeing = commitments
+				.iter()
+				.filter(|commitment| commitment.was_in_reveal_period())
+				.filter_map(|commitment| commitment.get_fulfillment())
+				.filter(|answer| T::Aggregator::agrees(answer, result))
+				.count();
+			RoundInfo {
+				settled_at: <frame_system::Pallet<T>>::block_number().saturated_into::<u64>(),
+				participants: commitments.len() as u32,
+				agreeing: agreeing as u32,
+			}
+		}
+
+		/// Hand `payload` to the bridge, recording the outcome in the `OutboundResults` ledger. A
+		/// failed attempt is retried after a delay that doubles with every attempt, until
+		/// `MaxDeliveryAttempts` is reached. A sent result is checked on again after
+		/// `DeliveryTimeout` blocks, see [`Pallet::time_out_delivery`].
+		fn send_result(
+			mut payload: CrossChainResult<<T as pallet_commitments::Config>::Hash>,
+			previous_attempts: u32,
+		) {
+			let metadata_id = payload.metadata_id;
+			let attempts = previous_attempts.saturating_add(1);
+			payload.nonce = NextResultNonce::<T>::get();
+
+			let destination = ResultDestinations::<T>::get(metadata_id);
+			let sent = T::Bridging::send_transact(RawOrigin::None.into(), payload, destination);
+			let now = <frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+			let status = match sent {
+				Ok((message_hash, fees)) => {
+					NextResultNonce::<T>::put(payload.nonce.saturating_add(1));
+					if let Some(nonce) = T::Bridging::last_sent_nonce() {
+						SentMessages::<T>::insert(nonce, metadata_id);
+					}
+					let timeout_at = now.saturating_add(T::DeliveryTimeout::get().max(1) as u64);
+					DeliveryRetries::<T>::insert(timeout_at, metadata_id, ());
+					Self::deposit_event(Event::ResultSent { metadata_id, message_hash, fees });
+					DeliveryStatus::Sent(message_hash)
+				},
+				Err(error) => {
+					log::warn!(
+						target: "runtime::template",
+						"Sending the result of metadata {} failed. ({:?})",
+						metadata_id,
+						error
+					);
+					if attempts < T::MaxDeliveryAttempts::get() {
+						let delay = T::DeliveryRetryDelay::get()
+							.saturating_mul(2u32.saturating_pow(attempts - 1));
+						let retry_at = now.saturating_add(delay.max(1) as u64);
+						DeliveryRetries::<T>::insert(retry_at, metadata_id, ());
+						Self::deposit_event(Event::ResultSendFailed {
+							metadata_id,
+							attempts,
+							retry_at,
+						});
+						DeliveryStatus::Pending
+					} else {
+						Self::deposit_event(Event::ResultDeliveryFailed { metadata_id });
+						DeliveryStatus::Failed
+					}
+				},
+			};
+			OutboundResults::<T>::insert(metadata_id, OutboundResult { payload, status, attempts });
+		}
+
+		/// Send the results due at the current block again, or time out their delivery, as many as
+		/// `MaxDeliveryRetriesPerBlock` allows; the rest are moved to the next block. Returns the
+		/// weight consumed.
+		pub(crate) fn retry_deliveries() -> Weight {
+			let now = <frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+			let due = DeliveryRetries::<T>::drain_prefix(now).map(|(id, _)| id).collect::<Vec<_>>();
+			let limit = T::MaxDeliveryRetriesPerBlock::get() as usize;
+
+			for metadata_id in due.iter().skip(limit) {
+				DeliveryRetries::<T>::insert(now + 1, metadata_id, ());
+			}
+			for metadata_id in due.iter().take(limit) {
+				match OutboundResults::<T>::get(metadata_id) {
+					Some(OutboundResult { payload, status: DeliveryStatus::Pending, attempts }) =>
+						Self::send_result(payload, attempts),
+					Some(OutboundResult { payload, status: DeliveryStatus::Sent(_), attempts }) =>
+						Self::time_out_delivery(payload, attempts),
+					// Delivered, or given up on, in the meantime
+					_ => {},
+				}
+			}
+
+			let retried = due.len().min(limit) as u64;
+			let postponed = due.len().saturating_sub(limit) as u64;
+			<T as Config>::WeightInfo::retry_result_delivery()
+				.saturating_mul(retried)
+				.saturating_add(T::DbWeight::get().reads_writes(1 + postponed, postponed))
+		}
+
+		/// The bridge did not confirm the delivery of `payload` in time: send it again under a new
+		/// nonce, or give up on it once it ran out of attempts. A late confirmation of the earlier
+		/// message still marks the result as delivered.
+		fn time_out_delivery(
+			payload: CrossChainResult<<T as pallet_commitments::Config>::Hash>,
+			attempts: u32,
+		) {
+			let metadata_id = payload.metadata_id;
+			Self::deposit_event(Event::ResultDeliveryTimedOut { metadata_id, attempts });
+			if attempts < T::MaxDeliveryAttempts::get() {
+				Self::send_result(payload, attempts);
+			} else {
+				OutboundResults::<T>::insert(
+					metadata_id,
+					OutboundResult { payload, status: DeliveryStatus::Failed, attempts },
+				);
+				Self::deposit_event(Event::ResultDeliveryFailed { metadata_id });
 			}
-			let different_hash_count = submissions_vec.len();
+		}
 
-			let (most_frequent_hash, occurrences) = hash_counts
+		/// Mark the results carried by the bridge messages `nonces` as delivered. Meant to be
+		/// called when the bridge confirms the delivery of messages, e.g. from the runtime's
+		/// `DeliveryConfirmationPayments`, which has to charge for
+		/// [`WeightInfo::confirm_delivery`].
+		pub fn confirm_delivery(nonces: RangeInclusive<u64>) {
+			for nonce in nonces {
+				if let Some(metadata_id) = SentMessages::<T>::take(nonce) {
+					OutboundResults::<T>::mutate(metadata_id, |entry| {
+						if let Some(entry) = entry {
+							entry.status = DeliveryStatus::Delivered;
+						}
+					});
+					Self::deposit_event(Event::ResultDelivered { metadata_id });
+				}
+			}
+		}
+
+		/// Issue the rewards of a round whose reveal window is over and conclude it. The round is
+		/// marked as disputed if the participants did not agree on a result.
+		fn settle_round(metadata_id: MetadataId) {
+			let revealers = pallet_commitments::Pallet::<T>::get_commitments(metadata_id)
 				.into_iter()
-				.max_by_key(|item| item.1)
-				.ok_or(Error::<T>::ConsensusError)?;
+				.filter(|commitment| commitment.get_fulfillment().is_some())
+				.map(|commitment| commitment.get_submitter().clone())
+				.collect::<Vec<_>>();
+			SettledReveals::<T>::insert(metadata_id, RevealersOf::<T>::truncate_from(revealers));
+
+			if let Err(error) = Self::issue_rewards(RawOrigin::None.into(), metadata_id) {
+				log::error!(
+					target: "runtime::template",
+					"Consensus for metadata {} has failed. ({:?})",
+					metadata_id,
+					error
+				);
+				// Consensus not reached.
+				RoundStates::<T>::insert(metadata_id, RoundState::Disputed);
+			}
 
-			// Normally we wouldn't get such an error, as each hash in the map has occurred at
-			// least once.
-			let correctness_percentage =
-				Ratio::<u32>::new_raw(occurrences as u32, different_hash_count as u32);
-			ensure!(
-				correctness_percentage > MajorityType::<T>::get().to_ratio(),
-				Error::<T>::ConsensusNotReached
-			);
+			pallet_commitments::Pallet::<T>::finalize_round(metadata_id);
+		}
+
+		/// Hand the request for `metadata_id` its winners, which get its escrowed reward in the
+		/// same proportions as they got [`Config::Reward`].
+		fn close_request(metadata_id: MetadataId, winners: &[(T::AccountId, BalanceOf<T>)]) {
+			let winners = winners
+				.iter()
+				.map(|(account, reward)| (account.clone(), (*reward).saturated_into::<u128>()))
+				.collect::<Vec<_>>();
+			T::MetadataRequests::on_settled(metadata_id, &winners);
+		}
+
+		/// The metadata requested for processing whose round still accepts commitments, oldest
+		/// first, as served by the `StorageQueryApi`.
+		pub fn pending_requests() -> Vec<MetadataId> {
+			T::MetadataRequests::open_requests()
+				.into_iter()
+				.filter(|metadata_id| Self::accepts_commitments(*metadata_id))
+				.collect()
+		}
 
-			Ok(most_frequent_hash)
+		/// Whether the participant signing with `public` has revealed its hash for `metadata_id`,
+		/// as served by the `StorageQueryApi`.
+		///
+		/// Reveals stay visible once the round is settled, including those that landed in the
+		/// block that settled it.
+		pub fn has_revealed(metadata_id: MetadataId, public: Public) -> bool {
+			let who = match Self::to_account_id(public) {
+				Ok(who) => who,
+				Err(_) => return false,
+			};
+			SettledReveals::<T>::get(metadata_id).contains(&who) ||
+				pallet_commitments::Pallet::<T>::get_commitments(metadata_id).iter().any(
+					|commitment| {
+						commitment.get_submitter() == &who &&
+							commitment.get_fulfillment().is_some()
+					},
+				)
+		}
+
+		fn accepts_commitments(metadata_id: MetadataId) -> bool {
+			if RoundStates::<T>::contains_key(metadata_id) ||
+				ProcessedHashes::<T>::contains_key(metadata_id)
+			{
+				return false
+			}
+			matches!(
+				pallet_commitments::Pallet::<T>::round_phase(metadata_id),
+				None | Some(RoundPhase::Open { .. }) | Some(RoundPhase::Committing { .. })
+			)
 		}
 
-		pub fn do_issue_rewards(accounts: &[T::AccountId], reward: BalanceOf<T>) {
-			for account in accounts.iter() {
+		/// The state of the round for `metadata_id`, as served by the `LogicProviderApi`.
+		///
+		/// Returns `None` if no round is known for `metadata_id`, or if it expired.
+		pub fn round_summary(metadata_id: MetadataId) -> Option<RoundSummaryOf<T>> {
+			let status = match RoundStates::<T>::get(metadata_id) {
+				Some(RoundState::Completed) => RoundStatus::Completed,
+				Some(RoundState::Disputed) => RoundStatus::Disputed,
+				Some(RoundState::ManuallyResolved) => RoundStatus::ManuallyResolved,
+				None => match pallet_commitments::Pallet::<T>::round_phase(metadata_id)? {
+					RoundPhase::Open { expires_at } => RoundStatus::Open { expires_at },
+					RoundPhase::Committing { ends_at } => RoundStatus::Committing { ends_at },
+					RoundPhase::Revealing { starts_at, ends_at } =>
+						RoundStatus::Revealing { starts_at, ends_at },
+					RoundPhase::Finalized | RoundPhase::Expired => return None,
+				},
+			};
+
+			let stakes = Self::participant_stakes(metadata_id);
+			let commitments = pallet_commitments::Pallet::<T>::get_commitments(metadata_id);
+			let participants = CommitmentBlockNumbers::<T>::get(metadata_id)
+				.into_iter()
+				.map(|(account, committed_at)| {
+					let commitment = commitments
+						.iter()
+						.find(|commitment| commitment.get_submitter() == &account);
+					ParticipantSummary {
+						stake: stakes.get(&account).copied().unwrap_or_else(T::FundsToLock::get),
+						revealed: commitment
+							.and_then(|commitment| commitment.get_fulfillment())
+							.copied(),
+						revealed_in_window: commitment
+							.map_or(false, |commitment| commitment.was_in_reveal_period()),
+						account,
+						committed_at,
+					}
+				})
+				.collect::<Vec<_>>();
+
+			// Only answers revealed in time count towards the result, see `calculate_rewards`
+			let timely_submissions = participants
+				.iter()
+				.filter(|participant| participant.revealed_in_window)
+				.filter_map(|participant| {
+					Some((participant.account.clone(), participant.revealed?))
+				})
+				.collect::<Vec<_>>();
+			let votes = Self::votes(&timely_submissions, &stakes);
+			let total = votes.iter().fold(0u128, |sum, (_, weight)| sum.saturating_add(*weight));
+			let leading_hash = T::Aggregator::aggregate(&votes, Perbill::zero()).map(|leading| {
+				let agreeing = votes
+					.iter()
+					.filter(|(hash, _)| T::Aggregator::agrees(hash, &leading))
+					.fold(0u128, |sum, (_, weight)| sum.saturating_add(*weight));
+				(leading, Perbill::from_rational(agreeing, total))
+			});
+
+			Some(RoundSummary {
+				metadata_id,
+				status,
+				participants,
+				leading_hash,
+				result: ProcessedHashes::<T>::get(metadata_id),
+			})
+		}
+
+		/// The amount a participant is going to lock when committing.
+		fn stake_to_bond(who: &T::AccountId) -> BalanceOf<T> {
+			if T::StakeWeightedConsensus::get() {
+				T::BondedAmount::bonded_amount(who).max(T::FundsToLock::get())
+			} else {
+				T::FundsToLock::get()
+			}
+		}
+
+		/// The amounts locked by the participants of a given metadata.
+		pub fn participant_stakes(metadata_id: MetadataId) -> BTreeMap<T::AccountId, BalanceOf<T>> {
+			ParticipantStakes::<T>::get(metadata_id).into_iter().collect()
+		}
+
+		pub fn do_issue_rewards(rewards: &[(T::AccountId, BalanceOf<T>)]) {
+			for (account, reward) in rewards.iter() {
+				let reward = *reward;
 				let imbalance = T::LocalCurrency::issue(reward);
 				#[cfg(not(feature = "runtime-benchmarks"))]
 				log::info!(target: "runtime::logic-provider", "Issued {:?} tokens", reward);
@@ -702,26 +1410,107 @@ pub mod pallet {
 			}
 		}
 
-		pub fn burn_eligible_account_tokens(
+		pub fn slash_eligible_accounts(
 			correct_hash: &<T as pallet_commitments::Config>::Hash,
 			metadata_id: MetadataId,
 		) -> Result<(), Error<T>> {
 			let eligible_participants =
 				pallet_commitments::Pallet::<T>::get_commitments(metadata_id)
 					.iter()
-					.filter(|&commitment| {
-						commitment.clone().get_fulfillment() != Some(correct_hash) ||
-							(T::EnforceBurningTokens::get() &&
-								!commitment.clone().was_in_reveal_period())
+					.filter_map(|commitment| {
+						let reason = match commitment.get_fulfillment() {
+							None => SlashReason::MissedReveal,
+							Some(hash) if !T::Aggregator::agrees(hash, correct_hash) =>
+								SlashReason::IncorrectResult,
+							Some(_)
+								if T::EnforceBurningTokens::get() &&
+									!commitment.was_in_reveal_period() =>
+								SlashReason::MissedReveal,
+							Some(_) => return None,
+						};
+						Some((commitment.get_submitter().clone(), reason))
 					})
-					.map(|commitment| commitment.get_submitter().clone())
 					.collect::<Vec<_>>();
-			for participant in eligible_participants {
-				Self::burn_tokens(&participant, T::FundsToLock::get());
+			let stakes = Self::participant_stakes(metadata_id);
+			for (participant, reason) in eligible_participants {
+				// Slash whatever the participant has at stake
+				let stake = stakes.get(&participant).copied().unwrap_or_else(T::FundsToLock::get);
+				Self::slash(&participant, metadata_id, stake, reason);
 			}
 			Ok(())
 		}
 
+		/// Record how each participant of a settled round behaved, given the result the round
+		/// settled on.
+		pub fn update_reputation(
+			correct_hash: &<T as pallet_commitments::Config>::Hash,
+			metadata_id: MetadataId,
+		) {
+			let now = <frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+			for commitment in pallet_commitments::Pallet::<T>::get_commitments(metadata_id) {
+				let mut reputation =
+					Self::decay_reputation(Reputation::<T>::get(commitment.get_submitter()), now);
+				let score_change = match commitment.get_fulfillment() {
+					Some(hash)
+						if T::Aggregator::agrees(hash, correct_hash) &&
+							commitment.was_in_reveal_period() =>
+					{
+						reputation.correct_reveals = reputation.correct_reveals.saturating_add(1);
+						i64::from(T::CorrectRevealScore::get())
+					},
+					Some(hash) if T::Aggregator::agrees(hash, correct_hash) => {
+						reputation.late_reveals = reputation.late_reveals.saturating_add(1);
+						-i64::from(T::LateRevealPenalty::get())
+					},
+					Some(_) => {
+						reputation.wrong_reveals = reputation.wrong_reveals.saturating_add(1);
+						-i64::from(T::FaultPenalty::get())
+					},
+					None => {
+						reputation.missing_reveals = reputation.missing_reveals.saturating_add(1);
+						-i64::from(T::FaultPenalty::get())
+					},
+				};
+				reputation.score = reputation.score.saturating_add(score_change);
+				Reputation::<T>::insert(commitment.get_submitter(), reputation);
+			}
+		}
+
+		/// The reputation of `who`, with its score decayed up to the current block.
+		pub fn reputation(who: &T::AccountId) -> ParticipantReputation {
+			let now = <frame_system::Pallet<T>>::block_number().saturated_into::<u64>();
+			Self::decay_reputation(Reputation::<T>::get(who), now)
+		}
+
+		/// Whether `who` may take part in new rounds according to `MinimumReputation`.
+		pub fn has_sufficient_reputation(who: &T::AccountId) -> bool {
+			T::MinimumReputation::get()
+				.map_or(true, |minimum| Self::reputation(who).score >= minimum)
+		}
+
+		/// Fade `reputation.score` by `ReputationDecay` for every full `ReputationDecayPeriod`
+		/// that passed until `now`.
+		fn decay_reputation(
+			mut reputation: ParticipantReputation,
+			now: u64,
+		) -> ParticipantReputation {
+			let period = T::ReputationDecayPeriod::get();
+			if period == 0 {
+				return reputation
+			}
+			let periods = now.saturating_sub(reputation.last_decayed_at) / period;
+			if periods > 0 {
+				let retained = (Perbill::one() - T::ReputationDecay::get())
+					.saturating_pow(periods.saturated_into::<usize>());
+				let magnitude =
+					i64::try_from(retained * reputation.score.unsigned_abs()).unwrap_or(i64::MAX);
+				reputation.score = if reputation.score < 0 { -magnitude } else { magnitude };
+				reputation.last_decayed_at =
+					reputation.last_decayed_at.saturating_add(periods.saturating_mul(period));
+			}
+			reputation
+		}
+
 		// Release funds of accounts who has submitted the correct result but not in first correct
 		// block
 		pub fn release_tokens_of_participants(
@@ -764,23 +1553,55 @@ pub mod pallet {
 			T::LocalCurrency::remove_lock(metadata_id.to_le_bytes(), submitter);
 		}
 
-		pub fn burn_tokens(account: &T::AccountId, amount: BalanceOf<T>) {
-			let imbalance = T::LocalCurrency::burn(amount);
+		/// Slash `amount` from `account` and hand it to [`Config::SlashHandler`].
+		///
+		/// A slash that can't be settled right away, e.g. because the free balance of `account`
+		/// does not cover it, is recorded in [`PendingSlashes`] so that it can be retried with
+		/// `retry_slash`.
+		pub fn slash(
+			account: &T::AccountId,
+			metadata_id: MetadataId,
+			amount: BalanceOf<T>,
+			reason: SlashReason,
+		) {
+			if Self::try_slash(account, metadata_id, amount, reason).is_err() {
+				#[cfg(not(feature = "runtime-benchmarks"))]
+				log::info!(target: "runtime::logic-provider", "Unable to slash {:?} tokens of {:?}", amount, &account);
+				PendingSlashes::<T>::mutate(account, metadata_id, |pending| {
+					let owed = pending.map_or(amount, |(owed, _)| owed.saturating_add(amount));
+					*pending = Some((owed, reason));
+				});
+				Self::deposit_event(Event::SlashDeferred {
+					who: account.clone(),
+					metadata_id,
+					amount,
+					reason,
+				});
+			}
+		}
 
-			if T::LocalCurrency::settle(
+		fn try_slash(
+			account: &T::AccountId,
+			metadata_id: MetadataId,
+			amount: BalanceOf<T>,
+			reason: SlashReason,
+		) -> DispatchResult {
+			let imbalance = T::LocalCurrency::withdraw(
 				account,
-				imbalance,
+				amount,
 				WithdrawReasons::all(),
 				ExistenceRequirement::KeepAlive,
-			)
-			.is_ok()
-			{
-				#[cfg(not(feature = "runtime-benchmarks"))]
-				log::info!(target: "runtime::logic-provider", "Burning {:?} tokens of {:?}", amount, &account);
-			} else {
-				#[cfg(not(feature = "runtime-benchmarks"))]
-				log::info!(target: "runtime::logic-provider", "Unable to burn {:?} tokens of {:?}", amount, &account);
-			}
+			)?;
+			T::SlashHandler::on_unbalanced(imbalance);
+			#[cfg(not(feature = "runtime-benchmarks"))]
+			log::info!(target: "runtime::logic-provider", "Slashed {:?} tokens of {:?}", amount, &account);
+			Self::deposit_event(Event::ParticipantSlashed {
+				who: account.clone(),
+				metadata_id,
+				amount,
+				reason,
+			});
+			Ok(())
 		}
 
 		// Specific checks for the unique calls expected by the logic provider pallet
@@ -790,7 +1611,13 @@ pub mod pallet {
 			mapped_call: &[u8],
 			signature: &Signature,
 		) -> TransactionValidity {
+			// Keys that are not registered, or are unbonding, may not join new rounds.
+			if !T::ParticipantRegistry::is_active(public) {
+				return InvalidTransaction::BadSigner.into()
+			}
 			match Pallet::<T>::to_account_id(public.clone()) {
+				Ok(account) if !Pallet::<T>::has_sufficient_reputation(&account) =>
+					InvalidTransaction::BadSigner.into(),
 				Ok(_account) if public.verify(&mapped_call, signature) => {
 					// We only want to mark unsigned submit_processing_result_hash extrinsics as
 					// valid if the signature sent in the body is verified by the given public
@@ -821,7 +1648,7 @@ pub mod pallet {
 		// Specific checks for the unique calls expected by the logic provider pallet
 		fn verify_call_public_key_reveal(
 			public: &Public,
-			random_seed: &u8,
+			salt: &Salt,
 			reveal_hash: &<T as pallet_commitments::Config>::Hash,
 			mapped_call: &[u8],
 			signature: &Signature,
@@ -843,7 +1670,7 @@ pub mod pallet {
 						// are the block producer), since for instance in some schemes
 						// others may copy your solution and claim a reward.
 						.propagate(true)
-						.and_provides((public.clone(), *random_seed, *reveal_hash))
+						.and_provides((public.clone(), *salt, *reveal_hash))
 						.build()
 				},
 				_ => InvalidTransaction::BadSigner.into(),
@@ -857,16 +1684,20 @@ pub mod pallet {
 		// submission.
 		T: Config + frame_system::offchain::SendTransactionTypes<Call<T>>,
 	{
+		/// Submit the commit or reveal in `payload` to the transaction pool as an unsigned
+		/// extrinsic. Returns the hash of the extrinsic, as the transaction pool knows it.
 		#[allow(clippy::result_unit_err)]
 		pub fn create_extrinsic_from_external_call(
 			payload: Vec<u8>,
 			public: Public,
 			signature: Signature,
-		) -> Result<(), ()>
+		) -> Result<<T as frame_system::Config>::Hash, ()>
 		where
 			<T as pallet_commitments::Config>::Hash: From<sp_core::H256>,
 		{
-			use frame_system::offchain::SubmitTransaction;
+			use frame_system::offchain::SendTransactionTypes;
+			use sp_runtime::traits::{Extrinsic, Hash as _};
+
 			let external_call = MapToCall::decode(&mut &payload[..]).unwrap();
 			let call = match external_call {
 				MapToCall::LogicProviderCall(LogicProviderCall::CommitHash { .. }) =>
@@ -875,8 +1706,16 @@ pub mod pallet {
 					Call::reveal_processing_result_hash { payload, signature, public },
 			};
 
-			let result =
-				SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.clone().into());
+			// What `SubmitTransaction::submit_unsigned_transaction` does, keeping the encoding to
+			// hash it the way the transaction pool does
+			let extrinsic = <T as SendTransactionTypes<Call<T>>>::Extrinsic::new(
+				call.clone().into(),
+				None,
+			)
+			.ok_or(())?
+			.encode();
+			let tx_hash = <T as frame_system::Config>::Hashing::hash(&extrinsic);
+			let result = sp_io::offchain::submit_transaction(extrinsic);
 
 			match result {
 				Ok(()) => log::info!(
@@ -891,7 +1730,21 @@ pub mod pallet {
 					e,
 				),
 			}
-			result
+			result.map(|()| tx_hash)
+		}
+	}
+
+	/// Rounds that never reach a quorum hand their participants' funds back.
+	impl<T: Config> pallet_commitments::OnRoundExpired for Pallet<T> {
+		fn on_round_expired(metadata_id: MetadataId) -> Weight {
+			let commitments = pallet_commitments::Pallet::<T>::get_commitments(metadata_id);
+			for commitment in commitments.iter() {
+				Self::release_tokens(metadata_id, commitment.get_submitter());
+			}
+			CommitmentBlockNumbers::<T>::remove(metadata_id);
+			ParticipantStakes::<T>::remove(metadata_id);
+
+			T::DbWeight::get().reads_writes(1, commitments.len() as u64 + 2)
 		}
 	}
 
@@ -925,13 +1778,13 @@ pub mod pallet {
 
 					if let MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 						reveal_hash,
-						random_seed,
+						salt,
 						..
 					}) = decoded_call
 					{
 						Pallet::<T>::verify_call_public_key_reveal(
 							public,
-							&random_seed,
+							&salt,
 							&reveal_hash.into(),
 							payload,
 							signature,
@@ -0,0 +1,89 @@
+//! Storage migrations for the logic-provider pallet.
+
+use super::*;
+use frame_support::{
+	pallet_prelude::*,
+	storage_alias,
+	traits::{OnRuntimeUpgrade, StorageVersion},
+};
+use sp_std::marker::PhantomData;
+
+const LOG_TARGET: &str = "runtime::logic-provider";
+
+pub mod v1 {
+	use super::*;
+
+	/// The single majority that used to apply to both the commit quorum and the reveal
+	/// agreement.
+	#[storage_alias]
+	pub(crate) type MajorityType<T: Config> = StorageValue<Pallet<T>, Majority>;
+
+	/// Carry the former `MajorityType` over to both [`CommitQuorum`] and [`RevealAgreement`].
+	///
+	/// `TwoThirds` now stands for an actual two thirds rather than 66%.
+	pub struct SplitMajorityType<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for SplitMajorityType<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version >= 1 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v1, on-chain storage version is {:?}",
+					on_chain_version
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let majority = MajorityType::<T>::take().unwrap_or_default();
+			log::info!(
+				target: LOG_TARGET,
+				"Carrying majority {:?} over to both thresholds",
+				majority
+			);
+			CommitQuorum::<T>::put(majority.clone());
+			RevealAgreement::<T>::put(majority);
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(2, 4)
+		}
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Schedule the settlement of the rounds past their open phase, which used to be found by
+	/// going through every round on each block.
+	pub struct ScheduleSettlements<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for ScheduleSettlements<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 1 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v2, on-chain storage version is {:?}",
+					on_chain_version
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let mut rounds = 0u64;
+			let mut scheduled = 0u64;
+			for metadata_id in pallet_commitments::Rounds::<T>::iter_keys() {
+				rounds += 1;
+				if let Some((_, ends_at)) =
+					pallet_commitments::Pallet::<T>::get_reveal_window(metadata_id)
+				{
+					Pallet::<T>::schedule_settlement(metadata_id, ends_at);
+					scheduled += 1;
+				}
+			}
+			log::info!(target: LOG_TARGET, "Scheduled the settlement of {} rounds", scheduled);
+
+			StorageVersion::new(2).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(1 + 2 * rounds + scheduled, 1 + scheduled)
+		}
+	}
+}
@@ -1,9 +1,13 @@
 use codec::Encode;
-use crate::{self as logic_provider, TemplateBridgedXcm};
+use crate::{
+	self as logic_provider, Aggregator, CrossChainResult, ExactMatch, MetadataId, NumericAggregator,
+	NumericStrategy, TemplateBridgedXcm,
+};
 use frame_support::{
 	pallet_prelude::ConstU32,
 	parameter_types,
-	traits::{ConstU128, ConstU16, ConstU64},
+	traits::{ConstU128, ConstU16, ConstU64, Imbalance, OnUnbalanced},
+	weights::Weight,
 };
 use frame_system as system;
 use frame_system::EnsureRoot;
@@ -17,9 +21,11 @@ pub use sp_core::{
 use sp_runtime::{
 	testing::{Header, TestXt},
 	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
 };
+use std::collections::BTreeMap;
 use xcm::prelude::Here;
-use xcm::v3::{Fungibility, MultiAssets};
+use xcm::v3::{Fungibility, MultiAssets, MultiLocation};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -96,15 +102,116 @@ parameter_types! {
 	pub const Reward: u128 = 1_000_000_000_000;
 	pub const MaxCallPayloadLength: u16 = 300;
 	pub const EnforceBurningTokens: bool = false;
+	pub static StakeWeightedConsensus: bool = false;
+	/// Amounts bonded by participants in stake-weighted mode
+	pub static Bonds: BTreeMap<AccountId32, u128> = BTreeMap::new();
+	/// Keys allowed to participate, `None` lets every key in
+	pub static RegisteredKeys: Option<Vec<primitives::shared::Public>> = None;
+	/// Total amount handed to the slash handler
+	pub static SlashedFunds: u128 = 0;
+	pub const CorrectRevealScore: u32 = 10;
+	pub const FaultPenalty: u32 = 20;
+	pub const LateRevealPenalty: u32 = 5;
+	pub const ReputationDecay: Perbill = Perbill::from_percent(50);
+	pub const ReputationDecayPeriod: u64 = 100;
+	pub static MinimumReputation: Option<i64> = None;
+	/// Settle results as numbers rather than by exact match
+	pub static NumericResults: bool = false;
+	pub const NumericMedian: NumericStrategy = NumericStrategy::Median;
+	/// Weight that settling rounds may take up in a block
+	pub static MaxRoundSettlementWeight: Weight = Weight::MAX;
+	/// Whether results can be sent over the bridge
+	pub static BridgeAvailable: bool = true;
+	/// Number of messages sent over the bridge, which is also the nonce of the last one
+	pub static SentMessageCount: u64 = 0;
+	/// Metadata ids requested on-chain, `None` lets every id be committed to
+	pub static OpenRequests: Option<Vec<MetadataId>> = None;
+	/// Requests settled by the pallet along with their winners
+	pub static SettledRequests: Vec<(MetadataId, Vec<(AccountId32, u128)>)> = Vec::new();
+	/// Locations that requested metadata from other chains
+	pub static RequestLocations: BTreeMap<MetadataId, MultiLocation> = BTreeMap::new();
+	/// Where the last result sent over the bridge went, `None` for the default destination
+	pub static LastDestination: Option<MultiLocation> = None;
+}
+
+pub type MedianAggregator = NumericAggregator<NumericMedian, ConstU128<1>>;
+
+pub struct MockAggregator;
+impl Aggregator<H256> for MockAggregator {
+	fn aggregate(votes: &[(H256, u128)], threshold: Perbill) -> Option<H256> {
+		if NumericResults::get() {
+			MedianAggregator::aggregate(votes, threshold)
+		} else {
+			<ExactMatch as Aggregator<H256>>::aggregate(votes, threshold)
+		}
+	}
+
+	fn agrees(answer: &H256, result: &H256) -> bool {
+		if NumericResults::get() {
+			MedianAggregator::agrees(answer, result)
+		} else {
+			<ExactMatch as Aggregator<H256>>::agrees(answer, result)
+		}
+	}
+}
+
+pub struct MockSlashHandler;
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Test>> for MockSlashHandler {
+	fn on_nonzero_unbalanced(amount: pallet_balances::NegativeImbalance<Test>) {
+		SlashedFunds::mutate(|total| *total += amount.peek());
+	}
+}
+
+pub struct MockRegistry;
+impl logic_provider::ParticipantRegistry<primitives::shared::Public> for MockRegistry {
+	fn is_active(key: &primitives::shared::Public) -> bool {
+		RegisteredKeys::get().map_or(true, |keys| keys.contains(key))
+	}
+}
+
+pub struct MockRequests;
+impl logic_provider::MetadataRequests<AccountId32> for MockRequests {
+	fn is_open(metadata_id: MetadataId) -> bool {
+		OpenRequests::get().map_or(true, |open| open.contains(&metadata_id))
+	}
+
+	fn open_requests() -> Vec<MetadataId> {
+		OpenRequests::get().unwrap_or_default()
+	}
+
+	fn on_settled(metadata_id: MetadataId, winners: &[(AccountId32, u128)]) {
+		OpenRequests::mutate(|open| {
+			if let Some(open) = open {
+				open.retain(|id| *id != metadata_id)
+			}
+		});
+		SettledRequests::mutate(|settled| settled.push((metadata_id, winners.to_vec())));
+	}
+
+	fn reply_to(metadata_id: MetadataId) -> Option<MultiLocation> {
+		RequestLocations::get().get(&metadata_id).copied()
+	}
+}
+
+pub struct MockBondedAmount;
+impl logic_provider::BondedAmount<AccountId32, u128> for MockBondedAmount {
+	fn bonded_amount(who: &AccountId32) -> u128 {
+		Bonds::get().get(who).copied().unwrap_or_default()
+	}
 }
 
 pub struct MockBridging;
 impl<Test: crate::Config> TemplateBridgedXcm<Test> for MockBridging {
 	fn send_transact(
 		_origin: system::pallet_prelude::OriginFor<Test>,
-		_proof: Vec<u8>,
-		_delivery_and_dispatch_fee: u64,
+		_result: CrossChainResult<H256>,
+		destination: Option<MultiLocation>,
 	) -> Result<([u8; 32], xcm::v3::MultiAssets), xcm::v3::SendError> {
+		if !BridgeAvailable::get() {
+			return Err(xcm::v3::SendError::Unroutable)
+		}
+		SentMessageCount::mutate(|count| *count += 1);
+		LastDestination::set(destination);
 
 		// returning success result for testing purpose
 		let fee = MultiAssets::from((Here, Fungibility::Fungible(1_000_000_u128)));
@@ -112,6 +219,10 @@ impl<Test: crate::Config> TemplateBridgedXcm<Test> for MockBridging {
 			([0u8, 0u8, 0u8, 0u8], 1u64).using_encoded(sp_io::hashing::blake2_256);
 		Ok((hash, fee))
 	}
+
+	fn last_sent_nonce() -> Option<u64> {
+		Some(SentMessageCount::get())
+	}
 }
 
 impl logic_provider::Config for Test {
@@ -120,21 +231,46 @@ impl logic_provider::Config for Test {
 	type EnforceBurningTokens = EnforceBurningTokens;
 	type Reward = Reward;
 	type FundsToLock = FundsToLock;
+	type StakeWeightedConsensus = StakeWeightedConsensus;
+	type BondedAmount = MockBondedAmount;
+	type ParticipantRegistry = MockRegistry;
+	type MetadataRequests = MockRequests;
+	type Aggregator = MockAggregator;
+	type CorrectRevealScore = CorrectRevealScore;
+	type FaultPenalty = FaultPenalty;
+	type LateRevealPenalty = LateRevealPenalty;
+	type ReputationDecay = ReputationDecay;
+	type ReputationDecayPeriod = ReputationDecayPeriod;
+	type MinimumReputation = MinimumReputation;
+	type MaxQueuedRounds = ConstU32<4>;
+	type MaxRoundSettlementWeight = MaxRoundSettlementWeight;
+	type MaxDeliveryAttempts = ConstU32<3>;
+	type DeliveryRetryDelay = ConstU32<2>;
+	type MaxDeliveryRetriesPerBlock = ConstU32<2>;
+	type DeliveryTimeout = ConstU32<5>;
 
 	type ForceOrigin = EnsureRoot<AccountId32>;
 	type WeightInfo = logic_provider::weights::SubstrateWeight<Self>;
 	type LocalCurrency = Balances;
+	type SlashHandler = MockSlashHandler;
 	type Bridging = MockBridging;
 }
 
 parameter_types! {
-	pub const RevealWindowLength:u8 = 3;
+	pub const OpenPhaseLength: u32 = 20;
+	pub const CommitPhaseLength: u32 = 3;
+	pub const RevealWindowLength: u32 = 3;
 }
 
 impl pallet_commitments::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type OpenPhaseLength = OpenPhaseLength;
+	type CommitPhaseLength = CommitPhaseLength;
 	type RevealWindowLength = RevealWindowLength;
 	type MaxParticipants = MaxParticipants;
+	type MaxPhaseChangesPerBlock = ConstU32<16>;
 	type Hash = H256;
+	type OnRoundExpired = LogicProvider;
 }
 
 fn get_test_keys(len: usize) -> Vec<primitives::shared::Pair> {
@@ -1,26 +1,47 @@
 use crate::{
-	mock::*, Config, Error, Majority, MajorityType, MetadataId, Pallet, RoundState,
-	RoundStates,
+	mock::*, Aggregator, CommitQuorum, Config, DeliveryStatus, Error, Event, ExactMatch, Majority,
+	MetadataId, NumericAggregator, NumericStrategy, Pallet, ParticipantReputation, PendingSlashes,
+	ProcessedHashes, Reputation, RevealAgreement, RoundInfo, RoundState, RoundStates, Salt,
+	SlashReason, WeightInfo,
 };
 use frame_support::{
-	assert_err, assert_ok,
+	assert_err, assert_noop, assert_ok,
 	dispatch::RawOrigin,
 	pallet_prelude::*,
-	traits::{Currency, ExistenceRequirement, Len, WithdrawReasons},
+	parameter_types,
+	traits::{ConstU128, Currency, ExistenceRequirement, Len, OnRuntimeUpgrade, WithdrawReasons},
 };
 
-use pallet_commitments::Commitment;
-use primitives::shared::{LogicProviderCall, MapToCall};
+use pallet_commitments::{Commitment, COMMITMENT_DOMAIN_TAG};
+use primitives::shared::{
+	numeric_answer, LogicProviderCall, MapToCall, ParticipantSummary, RoundStatus, RoundSummary,
+};
 use sp_core::Pair;
 use sp_io::hashing::blake2_256;
+use sp_runtime::Perbill;
+use xcm::v3::{Junction::GlobalConsensus, Junctions::X1, MultiLocation, NetworkId};
 
 fn get_hashes() -> (sp_core::H256, sp_core::H256) {
 	(H256::from_low_u64_be(42_u64), H256::from_low_u64_be(43_u64))
 }
 
-pub fn create_commit_hash(original_data: H256, random_seed: u8) -> H256 {
-	let mut combined = original_data.encode();
-	combined.push(random_seed);
+// Run the hooks that settle the rounds whose reveal window is over by the current block.
+fn settle_finished_rounds() {
+	LogicProvider::on_initialize(System::block_number());
+	LogicProvider::on_finalize(System::block_number());
+}
+
+pub fn create_commit_hash(
+	key: &primitives::shared::Pair,
+	metadata_id: MetadataId,
+	original_data: H256,
+	salt: Salt,
+) -> H256 {
+	let mut combined = COMMITMENT_DOMAIN_TAG.to_vec();
+	combined.extend_from_slice(&get_account_from_public(key.public()).encode());
+	combined.extend_from_slice(&metadata_id.encode());
+	combined.extend_from_slice(&original_data.encode());
+	combined.extend_from_slice(&salt);
 	let hash = blake2_256(&combined);
 	H256(hash)
 }
@@ -34,8 +55,8 @@ fn hash_gets_added_to_storage_on_commit() {
 		let test_key = &test_keys[0];
 
 		let (test_hash, ..) = get_hashes();
-		let random_seed = 1;
-		let commit_hash = create_commit_hash(test_hash, random_seed);
+		let salt = [1; 32];
+		let commit_hash = create_commit_hash(test_key, metadata_id, test_hash, salt);
 
 		let account = get_account_from_public(test_key.public());
 		let free_balance = <Test as Config>::LocalCurrency::free_balance(&account);
@@ -106,7 +127,8 @@ fn commit_processing_result_hash_err_if_consensus_has_completed() {
 	test_externalities.execute_with(|| {
 		// a bit more than 2/3 of the participants
 		for i in 0..172 {
-			let commit_hash = create_commit_hash(test_hash_1, i);
+			let commit_hash =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_1, [i; 32]);
 			let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash,
@@ -122,7 +144,8 @@ fn commit_processing_result_hash_err_if_consensus_has_completed() {
 			));
 		}
 		for i in 172..255 {
-			let commit_hash_2 = create_commit_hash(test_hash_2, i);
+			let commit_hash_2 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_2, [i; 32]);
 			let commit_call2 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_2,
@@ -147,7 +170,7 @@ fn commit_processing_result_hash_err_if_consensus_has_completed() {
 			let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_1,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature = test_keys[i as usize].sign(&reveal_call);
@@ -163,7 +186,7 @@ fn commit_processing_result_hash_err_if_consensus_has_completed() {
 			let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_2,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature = test_keys[i as usize].sign(&reveal_call);
@@ -207,7 +230,8 @@ fn commit_processing_result_hash_and_check_tokens_if_consensus_has_completed() {
 
 		// a bit more than 2/3 of the participants
 		for i in 0..172 {
-			let commit_hash_1 = create_commit_hash(test_hash_1, i);
+			let commit_hash_1 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_1, [i; 32]);
 			let commit_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_1,
@@ -226,7 +250,8 @@ fn commit_processing_result_hash_and_check_tokens_if_consensus_has_completed() {
 			));
 		}
 		for i in 172..255 {
-			let commit_hash_2 = create_commit_hash(test_hash_2, i);
+			let commit_hash_2 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_2, [i; 32]);
 
 			let commit_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
@@ -257,7 +282,7 @@ fn commit_processing_result_hash_and_check_tokens_if_consensus_has_completed() {
 			let reveal_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_1,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_1 = test_keys[i as usize].sign(&reveal_call_1);
@@ -272,7 +297,7 @@ fn commit_processing_result_hash_and_check_tokens_if_consensus_has_completed() {
 			let reveal_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_2,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_2 = test_keys[i as usize].sign(&reveal_call_2);
@@ -289,14 +314,41 @@ fn commit_processing_result_hash_and_check_tokens_if_consensus_has_completed() {
 		for (account, free_balance) in winning_accounts {
 			let new_balance_after_reward = <Test as Config>::LocalCurrency::free_balance(&account);
 			assert!(new_balance_after_reward >= free_balance);
+			assert_eq!(
+				Pallet::<Test>::reputation(&account),
+				ParticipantReputation {
+					correct_reveals: 1,
+					score: CorrectRevealScore::get() as i64,
+					..Default::default()
+				}
+			);
 		}
 
 		// check balance of nodes who submitted wrong result
+		let slashed = non_winning_accounts.len() as u128 * FundsToLock::get();
 		for (account, free_balance) in non_winning_accounts {
 			let new_balance = <Test as Config>::LocalCurrency::free_balance(&account);
-			// after burning tokens
+			// after slashing tokens
 			assert_eq!(new_balance, free_balance - FundsToLock::get());
+			System::assert_has_event(
+				Event::<Test>::ParticipantSlashed {
+					who: account,
+					metadata_id,
+					amount: FundsToLock::get(),
+					reason: SlashReason::IncorrectResult,
+				}
+				.into(),
+			);
+			assert_eq!(
+				Pallet::<Test>::reputation(&account),
+				ParticipantReputation {
+					wrong_reveals: 1,
+					score: -(FaultPenalty::get() as i64),
+					..Default::default()
+				}
+			);
 		}
+		assert_eq!(SlashedFunds::get(), slashed);
 	})
 }
 
@@ -321,7 +373,8 @@ fn consensus_is_completed_if_majority_supply_same_hash() {
 	test_externalities.execute_with(|| {
 		// a bit more than 2/3 of the participants
 		for i in 0..172 {
-			let commit_hash_1 = create_commit_hash(test_hash_1, i);
+			let commit_hash_1 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_1, [i; 32]);
 			let commit_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_1,
@@ -337,7 +390,8 @@ fn consensus_is_completed_if_majority_supply_same_hash() {
 			));
 		}
 		for i in 172..255 {
-			let commit_hash_2 = create_commit_hash(test_hash_2, i);
+			let commit_hash_2 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_2, [i; 32]);
 			let commit_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_2,
@@ -364,7 +418,7 @@ fn consensus_is_completed_if_majority_supply_same_hash() {
 			let reveal_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_1,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_1 = test_keys[i as usize].sign(&reveal_call_1);
@@ -379,7 +433,7 @@ fn consensus_is_completed_if_majority_supply_same_hash() {
 			let reveal_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_2,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_2 = test_keys[i as usize].sign(&reveal_call_2);
@@ -423,7 +477,8 @@ fn consensus_is_disputed_if_hash_frequency_not_enough() {
 	// since there is no clear majority, the consensus will end in a disputed state
 	test_externalities.execute_with(|| {
 		for i in 0..128 {
-			let commit_hash_1 = create_commit_hash(test_hash_1, i);
+			let commit_hash_1 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_1, [i; 32]);
 			let commit_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_1,
@@ -439,7 +494,8 @@ fn consensus_is_disputed_if_hash_frequency_not_enough() {
 			));
 		}
 		for i in 128..255 {
-			let commit_hash_2 = create_commit_hash(test_hash_2, i);
+			let commit_hash_2 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_2, [i; 32]);
 			let commit_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_2,
@@ -466,7 +522,7 @@ fn consensus_is_disputed_if_hash_frequency_not_enough() {
 			let reveal_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_1,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_1 = test_keys[i as usize].sign(&reveal_call_1);
@@ -482,7 +538,7 @@ fn consensus_is_disputed_if_hash_frequency_not_enough() {
 			let reveal_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_2,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_2 = test_keys[i as usize].sign(&reveal_call_2);
@@ -497,9 +553,7 @@ fn consensus_is_disputed_if_hash_frequency_not_enough() {
 
 		// updating system block for reward issuence
 		System::set_block_number(reveal_window_block + RevealWindowLength::get() as u64);
-		<Pallet<Test> as Hooks<<Test as frame_system::Config>::BlockNumber>>::on_finalize(
-			System::block_number(),
-		);
+		settle_finished_rounds();
 		assert_eq!(
 			Pallet::<Test>::get_round_state(metadata_id),
 			Some(RoundState::Disputed)
@@ -517,7 +571,12 @@ fn consensus_is_disputed_if_hash_frequency_not_enough() {
 		// Test the >50% case
 		RoundStates::<Test>::remove(metadata_id);
 
-		Pallet::<Test>::set_majority_type(RawOrigin::Root.into(), Majority::OneHalf).unwrap();
+		Pallet::<Test>::set_majority_type(
+			RawOrigin::Root.into(),
+			Majority::TwoThirds,
+			Majority::OneHalf,
+		)
+		.unwrap();
 
 		assert_eq!(
 			Pallet::<Test>::issue_rewards(RawOrigin::None.into(), metadata_id),
@@ -551,7 +610,8 @@ fn reward_issuance_on_finalization() {
 
 		// a bit more than 2/3 of the participants
 		for i in 0..172 {
-			let commit_hash_1 = create_commit_hash(test_hash_1, i);
+			let commit_hash_1 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_1, [i; 32]);
 			let commit_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_1,
@@ -582,7 +642,7 @@ fn reward_issuance_on_finalization() {
 			let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_1,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature = test_keys[i as usize].sign(&reveal_call);
@@ -596,9 +656,7 @@ fn reward_issuance_on_finalization() {
 
 		// updating system block for reward issuence
 		System::set_block_number(reveal_window_block + RevealWindowLength::get() as u64);
-		<Pallet<Test> as Hooks<<Test as frame_system::Config>::BlockNumber>>::on_finalize(
-			System::block_number(),
-		);
+		settle_finished_rounds();
 
 		assert_eq!(
 			Pallet::<Test>::get_round_state(metadata_id),
@@ -618,11 +676,192 @@ fn set_majority_type_changes_majority_type() {
 	let (mut test_externalities, _) = new_test_ext();
 
 	test_externalities.execute_with(|| {
-		let prev_majority_type = MajorityType::<Test>::get();
+		let prev_majority_type = RevealAgreement::<Test>::get();
+
+		let commit_quorum = Majority::Custom(Perbill::from_percent(80));
+		Pallet::<Test>::set_majority_type(
+			RawOrigin::Root.into(),
+			commit_quorum.clone(),
+			Majority::OneHalf,
+		)
+		.unwrap();
+
+		assert_ne!(RevealAgreement::<Test>::get(), prev_majority_type);
+		assert_eq!(CommitQuorum::<Test>::get(), commit_quorum);
+		System::assert_last_event(
+			Event::<Test>::MajorityTypeSet { commit_quorum, reveal_agreement: Majority::OneHalf }
+				.into(),
+		);
+
+		// A threshold of 100% can't be exceeded, and less than half of the votes must not
+		// settle a result
+		for (commit_quorum, reveal_agreement) in [
+			(Majority::Custom(Perbill::one()), Majority::OneHalf),
+			(Majority::OneHalf, Majority::Custom(Perbill::one())),
+			(Majority::OneHalf, Majority::Custom(Perbill::from_percent(49))),
+		] {
+			assert_noop!(
+				Pallet::<Test>::set_majority_type(
+					RawOrigin::Root.into(),
+					commit_quorum,
+					reveal_agreement
+				),
+				Error::<Test>::InvalidMajority
+			);
+		}
+		// A low commit quorum only schedules the reveal window earlier
+		assert_ok!(Pallet::<Test>::set_majority_type(
+			RawOrigin::Root.into(),
+			Majority::Custom(Perbill::from_percent(10)),
+			Majority::TwoThirds,
+		));
+	})
+}
+
+#[test]
+fn commit_quorum_schedules_the_reveal_window() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	let metadata_id: MetadataId = 0;
+	let (test_hash, _) = get_hashes();
+
+	test_externalities.execute_with(|| {
+		assert_ok!(Pallet::<Test>::set_majority_type(
+			RawOrigin::Root.into(),
+			Majority::Custom(Perbill::from_percent(1)),
+			Majority::TwoThirds,
+		));
+		// 1% of 255 participants is exceeded by the third commitment
+		for i in 0..3 {
+			assert!(matches!(
+				pallet_commitments::Pallet::<Test>::round_phase(metadata_id),
+				None | Some(pallet_commitments::RoundPhase::Open { .. })
+			));
+			let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+				metadata_id,
+				hash: create_commit_hash(&test_keys[i], metadata_id, test_hash, [i as u8; 32]),
+			})
+			.encode();
+			let signature = test_keys[i].sign(&commit_call);
+			assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+				RawOrigin::None.into(),
+				commit_call,
+				signature,
+				test_keys[i].public(),
+			));
+		}
+		assert_eq!(
+			pallet_commitments::Pallet::<Test>::round_phase(metadata_id),
+			Some(pallet_commitments::RoundPhase::Committing {
+				ends_at: System::block_number() + CommitPhaseLength::get() as u64
+			})
+		);
+	})
+}
+
+#[test]
+fn settlement_is_scheduled_for_the_end_of_the_reveal_window() {
+	let (mut test_externalities, _test_accounts) = new_test_ext();
+	test_externalities.execute_with(|| {
+		let now = System::block_number();
+		let ends_at = now + 5;
+		for metadata_id in 1..=2 {
+			pallet_commitments::Rounds::<Test>::insert(
+				metadata_id,
+				pallet_commitments::RoundPhase::Revealing { starts_at: now, ends_at },
+			);
+			Pallet::<Test>::schedule_settlement(metadata_id, ends_at);
+		}
+		// A round settled in the meantime is skipped
+		pallet_commitments::Rounds::<Test>::remove(2);
+
+		// Nothing is queued before the reveal window ends
+		settle_finished_rounds();
+		assert!(Pallet::<Test>::settlement_queue().is_empty());
+		assert_eq!(Pallet::<Test>::rounds_ending_at(ends_at).into_inner(), vec![1, 2]);
+
+		System::set_block_number(ends_at);
+		settle_finished_rounds();
+		assert_eq!(Pallet::<Test>::get_round_state(1), Some(RoundState::Disputed));
+		assert_eq!(Pallet::<Test>::get_round_state(2), None);
+		assert!(Pallet::<Test>::rounds_ending_at(ends_at).is_empty());
+		assert!(Pallet::<Test>::settlement_queue().is_empty());
+	})
+}
+
+#[test]
+fn round_whose_window_already_ended_is_settled_on_the_next_block() {
+	let (mut test_externalities, _test_accounts) = new_test_ext();
+	test_externalities.execute_with(|| {
+		System::set_block_number(10);
+		let now = System::block_number();
+		// The window of the first round ended before the current block, the one of the second
+		// round ends in the current block, whose `on_initialize` already ran
+		for (metadata_id, ends_at) in [(1, now - 2), (2, now)] {
+			pallet_commitments::Rounds::<Test>::insert(
+				metadata_id,
+				pallet_commitments::RoundPhase::Revealing { starts_at: now - 5, ends_at },
+			);
+			Pallet::<Test>::schedule_settlement(metadata_id, ends_at);
+		}
+		assert!(Pallet::<Test>::rounds_ending_at(now - 2).is_empty());
+		assert!(Pallet::<Test>::rounds_ending_at(now).is_empty());
+		assert_eq!(Pallet::<Test>::rounds_ending_at(now + 1).into_inner(), vec![1, 2]);
+
+		System::set_block_number(now + 1);
+		settle_finished_rounds();
+		assert_eq!(Pallet::<Test>::get_round_state(1), Some(RoundState::Disputed));
+		assert_eq!(Pallet::<Test>::get_round_state(2), Some(RoundState::Disputed));
+		assert!(Pallet::<Test>::settlement_queue().is_empty());
+	})
+}
+
+#[test]
+fn v2_migration_schedules_rounds_past_their_open_phase() {
+	let (mut test_externalities, _test_accounts) = new_test_ext();
+	test_externalities.execute_with(|| {
+		StorageVersion::new(1).put::<Pallet<Test>>();
+		let now = System::block_number();
+		pallet_commitments::Rounds::<Test>::insert(
+			1,
+			pallet_commitments::RoundPhase::Open { expires_at: now + 10 },
+		);
+		pallet_commitments::Rounds::<Test>::insert(
+			2,
+			pallet_commitments::RoundPhase::Committing { ends_at: now + 2 },
+		);
+		// A reveal window that ended before the upgrade is settled in the block after the upgrade
+		pallet_commitments::Rounds::<Test>::insert(
+			3,
+			pallet_commitments::RoundPhase::Revealing { starts_at: 0, ends_at: 0 },
+		);
+
+		crate::migrations::v2::ScheduleSettlements::<Test>::on_runtime_upgrade();
+
+		let reveal_end = now + 2 + RevealWindowLength::get() as u64;
+		assert_eq!(Pallet::<Test>::rounds_ending_at(reveal_end).into_inner(), vec![2]);
+		assert_eq!(Pallet::<Test>::rounds_ending_at(now + 1).into_inner(), vec![3]);
+		assert_eq!(Pallet::<Test>::on_chain_storage_version(), 2);
+
+		// Running it again is a no-op
+		crate::migrations::v2::ScheduleSettlements::<Test>::on_runtime_upgrade();
+		assert_eq!(Pallet::<Test>::rounds_ending_at(now + 1).into_inner(), vec![3]);
+	})
+}
+
+#[test]
+fn migration_carries_majority_type_over_to_both_thresholds() {
+	let (mut test_externalities, _) = new_test_ext();
+
+	test_externalities.execute_with(|| {
+		StorageVersion::new(0).put::<Pallet<Test>>();
+		crate::migrations::v1::MajorityType::<Test>::put(Majority::OneHalf);
 
-		Pallet::<Test>::set_majority_type(RawOrigin::Root.into(), Majority::OneHalf).unwrap();
+		crate::migrations::v1::SplitMajorityType::<Test>::on_runtime_upgrade();
 
-		assert_ne!(MajorityType::<Test>::get(), prev_majority_type);
+		assert_eq!(CommitQuorum::<Test>::get(), Majority::OneHalf);
+		assert_eq!(RevealAgreement::<Test>::get(), Majority::OneHalf);
+		assert!(!crate::migrations::v1::MajorityType::<Test>::exists());
+		assert_eq!(Pallet::<Test>::on_chain_storage_version(), 1);
 	})
 }
 
@@ -634,7 +873,8 @@ fn resolve_metadata_dispute_works() {
 
 	test_externalities.execute_with(|| {
 		for i in 0..128 {
-			let commit_hash_1 = create_commit_hash(test_hash_1, i);
+			let commit_hash_1 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_1, [i; 32]);
 			let commit_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_1,
@@ -649,7 +889,8 @@ fn resolve_metadata_dispute_works() {
 			));
 		}
 		for i in 128..255 {
-			let commit_hash_2 = create_commit_hash(test_hash_2, i);
+			let commit_hash_2 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_2, [i; 32]);
 			let commit_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_2,
@@ -676,7 +917,7 @@ fn resolve_metadata_dispute_works() {
 			let reveal_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_1,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_1 = test_keys[i as usize].sign(&reveal_call_1);
@@ -692,7 +933,7 @@ fn resolve_metadata_dispute_works() {
 			let reveal_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_2,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_2 = test_keys[i as usize].sign(&reveal_call_2);
@@ -707,9 +948,7 @@ fn resolve_metadata_dispute_works() {
 
 		// updating system block for reward issuence
 		System::set_block_number(reveal_window_block + RevealWindowLength::get() as u64);
-		<Pallet<Test> as Hooks<<Test as frame_system::Config>::BlockNumber>>::on_finalize(
-			System::block_number(),
-		);
+		settle_finished_rounds();
 		assert_eq!(
 			Pallet::<Test>::get_round_state(metadata_id),
 			Some(RoundState::Disputed)
@@ -737,7 +976,12 @@ fn commit_processing_result_hash_if_node_has_insufficient_tokens() {
 
 	test_externalities.execute_with(|| {
 		let account_with_insufficient_tokens = primitives::shared::Pair::generate().0;
-		let commit_hash_1 = create_commit_hash(test_hash_1, 0);
+		let commit_hash_1 = create_commit_hash(
+			&account_with_insufficient_tokens,
+			metadata_id,
+			test_hash_1,
+			[0; 32],
+		);
 		let commit_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 			metadata_id,
 			hash: commit_hash_1,
@@ -764,7 +1008,7 @@ fn commit_processing_result_hash_err_if_node_has_submitted_twice() {
 	let (test_hash, ..) = get_hashes();
 
 	test_externalities.execute_with(|| {
-		let commit_hash = create_commit_hash(test_hash, 0);
+		let commit_hash = create_commit_hash(&test_keys[0], metadata_id, test_hash, [0; 32]);
 		let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 			metadata_id,
 			hash: commit_hash,
@@ -797,7 +1041,8 @@ fn commit_processing_result_hash_err_if_exceeds_participants_limit() {
 
 	test_externalities.execute_with(|| {
 		for i in 0..255 {
-			let commit_hash_1 = create_commit_hash(test_hash_1, i);
+			let commit_hash_1 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_1, [i; 32]);
 			let commit_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_1,
@@ -812,7 +1057,8 @@ fn commit_processing_result_hash_err_if_exceeds_participants_limit() {
 				test_keys[i as usize].public(),
 			));
 		}
-		let commit_hash_past_threshold = create_commit_hash(test_hash_1, 255);
+		let commit_hash_past_threshold =
+			create_commit_hash(&test_keys[255], metadata_id, test_hash_1, [255; 32]);
 		let commit_call_past_threshold =
 			MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
@@ -845,7 +1091,8 @@ fn commit_processing_result_hash_with_burn_fund_cases() {
 
 		// a bit more than 2/3 of the participants
 		for i in 0..172 {
-			let commit_hash_1 = create_commit_hash(test_hash_1, i);
+			let commit_hash_1 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_1, [i; 32]);
 			let commit_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_1,
@@ -864,7 +1111,8 @@ fn commit_processing_result_hash_with_burn_fund_cases() {
 			));
 		}
 		for i in 172..255 {
-			let commit_hash_2 = create_commit_hash(test_hash_2, i);
+			let commit_hash_2 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_2, [i; 32]);
 			let commit_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_2,
@@ -894,7 +1142,7 @@ fn commit_processing_result_hash_with_burn_fund_cases() {
 			let reveal_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_1,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_1 = test_keys[i as usize].sign(&reveal_call_1);
@@ -910,7 +1158,7 @@ fn commit_processing_result_hash_with_burn_fund_cases() {
 			let reveal_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_2,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_2 = test_keys[i as usize].sign(&reveal_call_2);
@@ -927,7 +1175,7 @@ fn commit_processing_result_hash_with_burn_fund_cases() {
 			let reveal_call_invalid = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_1,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature = test_keys[i as usize].sign(&reveal_call_invalid);
@@ -980,7 +1228,8 @@ fn process_hash_with_configurable_burn_fund_case() {
 		let mut non_winners_without_burning = Vec::new();
 
 		for i in 0..200 {
-			let commit_hash_1 = create_commit_hash(test_hash_1, i);
+			let commit_hash_1 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_1, [i; 32]);
 			let commit_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_1,
@@ -1005,7 +1254,8 @@ fn process_hash_with_configurable_burn_fund_case() {
 		}
 
 		for i in 200..255 {
-			let commit_hash_2 = create_commit_hash(test_hash_2, i);
+			let commit_hash_2 =
+				create_commit_hash(&test_keys[i as usize], metadata_id, test_hash_2, [i; 32]);
 			let commit_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
 				metadata_id,
 				hash: commit_hash_2,
@@ -1035,7 +1285,7 @@ fn process_hash_with_configurable_burn_fund_case() {
 			let reveal_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_1,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_1 = test_keys[i as usize].sign(&reveal_call_1);
@@ -1051,7 +1301,7 @@ fn process_hash_with_configurable_burn_fund_case() {
 			let reveal_call_2 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_2,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_2 = test_keys[i as usize].sign(&reveal_call_2);
@@ -1071,7 +1321,7 @@ fn process_hash_with_configurable_burn_fund_case() {
 			let reveal_call_1 = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
 				metadata_id,
 				reveal_hash: test_hash_1,
-				random_seed: i,
+				salt: [i; 32],
 			})
 			.encode();
 			let signature_1 = test_keys[i as usize].sign(&reveal_call_1);
@@ -1111,3 +1361,1071 @@ fn process_hash_with_configurable_burn_fund_case() {
 		}
 	})
 }
+
+#[test]
+fn stake_weighted_consensus_favours_stake_over_headcount() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	let metadata_id: MetadataId = 0;
+	let (test_hash_1, test_hash_2) = get_hashes();
+
+	test_externalities.execute_with(|| {
+		StakeWeightedConsensus::set(true);
+		let accounts = test_keys
+			.iter()
+			.take(7)
+			.map(|key| get_account_from_public(key.public()))
+			.collect::<Vec<_>>();
+		// two heavy participants outweigh the five light ones
+		Bonds::mutate(|bonds| {
+			bonds.insert(accounts[0].clone(), 300 * FundsToLock::get());
+			bonds.insert(accounts[1].clone(), 100 * FundsToLock::get());
+		});
+		let free_balances = accounts
+			.iter()
+			.map(|account| <Test as Config>::LocalCurrency::free_balance(account))
+			.collect::<Vec<_>>();
+
+		for i in 0..7 {
+			let hash = if i < 2 { test_hash_1 } else { test_hash_2 };
+			let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+				metadata_id,
+				hash: create_commit_hash(&test_keys[i as usize], metadata_id, hash, [i; 32]),
+			})
+			.encode();
+			let signature = test_keys[i as usize].sign(&commit_call);
+			assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+				RawOrigin::None.into(),
+				commit_call,
+				signature,
+				test_keys[i as usize].public(),
+			));
+		}
+		assert_eq!(
+			Pallet::<Test>::participant_stakes(metadata_id).get(&accounts[0]),
+			Some(&(300 * FundsToLock::get()))
+		);
+
+		pallet_commitments::Rounds::<Test>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: System::block_number(),
+				ends_at: System::block_number() + RevealWindowLength::get() as u64,
+			},
+		);
+		for i in 0..7 {
+			let hash = if i < 2 { test_hash_1 } else { test_hash_2 };
+			let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
+				metadata_id,
+				reveal_hash: hash,
+				salt: [i; 32],
+			})
+			.encode();
+			let signature = test_keys[i as usize].sign(&reveal_call);
+			assert_ok!(Pallet::<Test>::reveal_processing_result_hash(
+				RawOrigin::None.into(),
+				reveal_call,
+				signature,
+				test_keys[i as usize].public(),
+			));
+		}
+
+		assert_ok!(Pallet::<Test>::issue_rewards(RawOrigin::None.into(), metadata_id));
+		assert_eq!(ProcessedHashes::<Test>::get(metadata_id), Some(test_hash_1));
+
+		// rewards are split in proportion to the winners' stakes
+		let reward = Reward::get();
+		assert_eq!(
+			<Test as Config>::LocalCurrency::free_balance(&accounts[0]),
+			free_balances[0] + reward * 3 / 4
+		);
+		assert_eq!(
+			<Test as Config>::LocalCurrency::free_balance(&accounts[1]),
+			free_balances[1] + reward / 4
+		);
+		// light participants lose what they have at stake
+		for i in 2..7 {
+			assert_eq!(
+				<Test as Config>::LocalCurrency::free_balance(&accounts[i]),
+				free_balances[i] - FundsToLock::get()
+			);
+		}
+		assert!(Pallet::<Test>::get_participant_stakes(metadata_id).is_empty());
+	})
+}
+
+#[test]
+fn unregistered_participants_cannot_commit() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	test_externalities.execute_with(|| {
+		let metadata_id: MetadataId = 0;
+		let (registered, unregistered) = (&test_keys[0], &test_keys[1]);
+		RegisteredKeys::set(Some(vec![registered.public()]));
+
+		let (test_hash, ..) = get_hashes();
+		let call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+			metadata_id,
+			hash: create_commit_hash(unregistered, metadata_id, test_hash, [1; 32]),
+		})
+		.encode();
+
+		let unregistered_call = crate::Call::<Test>::commit_processing_result_hash {
+			payload: call.clone(),
+			signature: unregistered.sign(&call),
+			public: unregistered.public(),
+		};
+		assert_eq!(
+			<Pallet<Test> as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&unregistered_call
+			),
+			InvalidTransaction::BadSigner.into()
+		);
+		assert_err!(
+			Pallet::<Test>::commit_processing_result_hash(
+				RawOrigin::None.into(),
+				call.clone(),
+				unregistered.sign(&call),
+				unregistered.public(),
+			),
+			Error::<Test>::NotRegisteredParticipant
+		);
+
+		let registered_call = crate::Call::<Test>::commit_processing_result_hash {
+			payload: call.clone(),
+			signature: registered.sign(&call),
+			public: registered.public(),
+		};
+		assert!(<Pallet<Test> as ValidateUnsigned>::validate_unsigned(
+			TransactionSource::External,
+			&registered_call
+		)
+		.is_ok());
+		assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+			RawOrigin::None.into(),
+			call.clone(),
+			registered.sign(&call),
+			registered.public(),
+		));
+	});
+}
+
+#[test]
+fn expired_round_releases_locked_funds() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	test_externalities.execute_with(|| {
+		let metadata_id: MetadataId = 0;
+		let test_key = &test_keys[0];
+		let account = get_account_from_public(test_key.public());
+		let free_balance = <Test as Config>::LocalCurrency::free_balance(&account);
+
+		let (test_hash, ..) = get_hashes();
+		let call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+			metadata_id,
+			hash: create_commit_hash(test_key, metadata_id, test_hash, [1; 32]),
+		})
+		.encode();
+		assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+			RawOrigin::None.into(),
+			call.clone(),
+			test_key.sign(&call),
+			test_key.public(),
+		));
+		assert!(<Test as Config>::LocalCurrency::ensure_can_withdraw(
+			&account,
+			free_balance,
+			WithdrawReasons::all(),
+			0
+		)
+		.is_err());
+
+		// A single commitment never reaches the quorum
+		let expires_at = System::block_number() + OpenPhaseLength::get() as u64;
+		System::set_block_number(expires_at);
+		Commitments::on_initialize(expires_at);
+
+		assert_eq!(pallet_commitments::Pallet::<Test>::get_round(metadata_id), None);
+		assert!(pallet_commitments::Pallet::<Test>::get_commitments(metadata_id).is_empty());
+		assert!(Pallet::<Test>::get_commitment_blocks(metadata_id).is_empty());
+		assert!(Pallet::<Test>::get_participant_stakes(metadata_id).is_empty());
+		assert_ok!(<Test as Config>::LocalCurrency::ensure_can_withdraw(
+			&account,
+			free_balance,
+			WithdrawReasons::all(),
+			0
+		));
+		System::assert_last_event(
+			pallet_commitments::Event::<Test>::RoundPhaseChanged {
+				commit_key: metadata_id,
+				phase: pallet_commitments::RoundPhase::Expired,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn incorrect_reveal_is_slashed_and_the_slash_persists() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	test_externalities.execute_with(|| {
+		let metadata_id: MetadataId = 0;
+		let test_key = &test_keys[0];
+		let account = get_account_from_public(test_key.public());
+		let free_balance = <Test as Config>::LocalCurrency::free_balance(&account);
+
+		let (test_hash, wrong_hash) = get_hashes();
+		let call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+			metadata_id,
+			hash: create_commit_hash(test_key, metadata_id, test_hash, [1; 32]),
+		})
+		.encode();
+		assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+			RawOrigin::None.into(),
+			call.clone(),
+			test_key.sign(&call),
+			test_key.public(),
+		));
+		let stake = Pallet::<Test>::participant_stakes(metadata_id)[&account];
+
+		pallet_commitments::Rounds::<Test>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: System::block_number(),
+				ends_at: System::block_number() + RevealWindowLength::get() as u64,
+			},
+		);
+		let call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
+			metadata_id,
+			reveal_hash: wrong_hash,
+			salt: [1; 32],
+		})
+		.encode();
+		// The call succeeds, so that the slash isn't rolled back
+		assert_ok!(Pallet::<Test>::reveal_processing_result_hash(
+			RawOrigin::None.into(),
+			call.clone(),
+			test_key.sign(&call),
+			test_key.public(),
+		));
+
+		System::assert_last_event(
+			Event::<Test>::ParticipantSlashed {
+				who: account.clone(),
+				metadata_id,
+				amount: stake,
+				reason: SlashReason::IncorrectReveal,
+			}
+			.into(),
+		);
+		assert_eq!(Balances::free_balance(&account), free_balance - stake);
+		assert_eq!(SlashedFunds::get(), stake);
+		assert_ok!(<Test as Config>::LocalCurrency::ensure_can_withdraw(
+			&account,
+			free_balance - stake,
+			WithdrawReasons::all(),
+			0
+		));
+		assert!(pallet_commitments::Pallet::<Test>::get_commitments(metadata_id).is_empty());
+		assert!(Pallet::<Test>::get_commitment_blocks(metadata_id).is_empty());
+		assert!(Pallet::<Test>::get_participant_stakes(metadata_id).is_empty());
+
+		// Without a commitment left, the participant can't reveal again
+		assert_noop!(
+			Pallet::<Test>::reveal_processing_result_hash(
+				RawOrigin::None.into(),
+				call.clone(),
+				test_key.sign(&call),
+				test_key.public(),
+			),
+			Error::<Test>::NoCommitmentFound
+		);
+	});
+}
+
+#[test]
+fn failed_slash_is_recorded_and_can_be_retried() {
+	let (mut test_externalities, _test_keys) = new_test_ext();
+	test_externalities.execute_with(|| {
+		let metadata_id: MetadataId = 0;
+		let account = get_account_from_public(primitives::shared::Pair::generate().0.public());
+		let caller = RawOrigin::Signed(account.clone());
+		let amount = FundsToLock::get();
+		Balances::make_free_balance_be(&account, amount / 2);
+
+		Pallet::<Test>::slash(&account, metadata_id, amount, SlashReason::MissedReveal);
+		System::assert_last_event(
+			Event::<Test>::SlashDeferred {
+				who: account.clone(),
+				metadata_id,
+				amount,
+				reason: SlashReason::MissedReveal,
+			}
+			.into(),
+		);
+		assert_eq!(
+			PendingSlashes::<Test>::get(&account, metadata_id),
+			Some((amount, SlashReason::MissedReveal))
+		);
+		assert_eq!(Balances::free_balance(&account), amount / 2);
+		assert_eq!(SlashedFunds::get(), 0);
+
+		// Still not enough funds
+		assert_err!(
+			Pallet::<Test>::retry_slash(caller.clone().into(), account.clone(), metadata_id),
+			Error::<Test>::InsufficientBalance
+		);
+
+		Balances::make_free_balance_be(&account, amount * 2);
+		assert_ok!(Pallet::<Test>::retry_slash(
+			caller.clone().into(),
+			account.clone(),
+			metadata_id
+		));
+		System::assert_last_event(
+			Event::<Test>::ParticipantSlashed {
+				who: account.clone(),
+				metadata_id,
+				amount,
+				reason: SlashReason::MissedReveal,
+			}
+			.into(),
+		);
+		assert_eq!(PendingSlashes::<Test>::get(&account, metadata_id), None);
+		assert_eq!(Balances::free_balance(&account), amount);
+		assert_eq!(SlashedFunds::get(), amount);
+		assert_err!(
+			Pallet::<Test>::retry_slash(caller.into(), account, metadata_id),
+			Error::<Test>::NoPendingSlash
+		);
+	})
+}
+
+#[test]
+fn reputation_decays_and_gates_participation() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	test_externalities.execute_with(|| {
+		let metadata_id: MetadataId = 0;
+		let test_key = &test_keys[0];
+		let account = get_account_from_public(test_key.public());
+		MinimumReputation::set(Some(-30));
+		Reputation::<Test>::insert(
+			&account,
+			ParticipantReputation {
+				missing_reveals: 2,
+				score: -40,
+				last_decayed_at: 1,
+				..Default::default()
+			},
+		);
+
+		let (test_hash, ..) = get_hashes();
+		let call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+			metadata_id,
+			hash: create_commit_hash(test_key, metadata_id, test_hash, [1; 32]),
+		})
+		.encode();
+		let commit_call = crate::Call::<Test>::commit_processing_result_hash {
+			payload: call.clone(),
+			signature: test_key.sign(&call),
+			public: test_key.public(),
+		};
+		assert_eq!(
+			<Pallet<Test> as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&commit_call
+			),
+			InvalidTransaction::BadSigner.into()
+		);
+		assert_err!(
+			Pallet::<Test>::commit_processing_result_hash(
+				RawOrigin::None.into(),
+				call.clone(),
+				test_key.sign(&call),
+				test_key.public(),
+			),
+			Error::<Test>::ReputationTooLow
+		);
+
+		// Half of the score fades away after each decay period
+		System::set_block_number(1 + ReputationDecayPeriod::get() - 1);
+		assert_eq!(Pallet::<Test>::reputation(&account).score, -40);
+		System::set_block_number(1 + ReputationDecayPeriod::get());
+		assert_eq!(
+			Pallet::<Test>::reputation(&account),
+			ParticipantReputation {
+				missing_reveals: 2,
+				score: -20,
+				last_decayed_at: 1 + ReputationDecayPeriod::get(),
+				..Default::default()
+			}
+		);
+		assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+			RawOrigin::None.into(),
+			call.clone(),
+			test_key.sign(&call),
+			test_key.public(),
+		));
+
+		System::set_block_number(1 + 3 * ReputationDecayPeriod::get());
+		assert_eq!(Pallet::<Test>::reputation(&account).score, -5);
+	})
+}
+
+#[test]
+fn round_summary_reports_progress_of_a_round() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	let metadata_id: MetadataId = 0;
+	let (test_hash_1, test_hash_2) = get_hashes();
+
+	test_externalities.execute_with(|| {
+		assert_eq!(Pallet::<Test>::round_summary(metadata_id), None);
+
+		let accounts = test_keys
+			.iter()
+			.take(3)
+			.map(|key| get_account_from_public(key.public()))
+			.collect::<Vec<_>>();
+		let committed_at = System::block_number();
+		for i in 0..3 {
+			let hash = if i < 2 { test_hash_1 } else { test_hash_2 };
+			let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+				metadata_id,
+				hash: create_commit_hash(&test_keys[i as usize], metadata_id, hash, [i; 32]),
+			})
+			.encode();
+			let signature = test_keys[i as usize].sign(&commit_call);
+			assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+				RawOrigin::None.into(),
+				commit_call,
+				signature,
+				test_keys[i as usize].public(),
+			));
+		}
+
+		let summary = Pallet::<Test>::round_summary(metadata_id).unwrap();
+		assert_eq!(
+			summary.status,
+			RoundStatus::Open { expires_at: committed_at + OpenPhaseLength::get() as u64 }
+		);
+		assert_eq!(
+			summary.participants,
+			accounts
+				.iter()
+				.map(|account| ParticipantSummary {
+					account: account.clone(),
+					committed_at,
+					stake: FundsToLock::get(),
+					revealed: None,
+					revealed_in_window: false,
+				})
+				.collect::<Vec<_>>()
+		);
+		assert_eq!(summary.leading_hash, None);
+		assert_eq!(summary.result, None);
+
+		let starts_at = System::block_number();
+		let ends_at = starts_at + RevealWindowLength::get() as u64;
+		pallet_commitments::Rounds::<Test>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing { starts_at, ends_at },
+		);
+		for i in 0..3 {
+			let hash = if i < 2 { test_hash_1 } else { test_hash_2 };
+			let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
+				metadata_id,
+				reveal_hash: hash,
+				salt: [i; 32],
+			})
+			.encode();
+			let signature = test_keys[i as usize].sign(&reveal_call);
+			assert_ok!(Pallet::<Test>::reveal_processing_result_hash(
+				RawOrigin::None.into(),
+				reveal_call,
+				signature,
+				test_keys[i as usize].public(),
+			));
+		}
+
+		let summary = Pallet::<Test>::round_summary(metadata_id).unwrap();
+		assert_eq!(summary.status, RoundStatus::Revealing { starts_at, ends_at });
+		assert_eq!(summary.participants[0].revealed, Some(test_hash_1));
+		assert_eq!(summary.participants[2].revealed, Some(test_hash_2));
+		assert!(summary.participants.iter().all(|participant| participant.revealed_in_window));
+		assert_eq!(summary.leading_hash, Some((test_hash_1, Perbill::from_rational(2u32, 3u32))));
+		assert_eq!(summary.result, None);
+
+		assert_ok!(Pallet::<Test>::issue_rewards(RawOrigin::None.into(), metadata_id));
+		assert_eq!(
+			Pallet::<Test>::round_summary(metadata_id),
+			Some(RoundSummary {
+				metadata_id,
+				status: RoundStatus::Completed,
+				participants: Vec::new(),
+				leading_hash: None,
+				result: Some(test_hash_1),
+			})
+		);
+	})
+}
+
+#[test]
+fn has_revealed_only_reports_reveals_accepted_on_chain() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	let metadata_id: MetadataId = 0;
+	let (test_hash, wrong_hash) = get_hashes();
+
+	test_externalities.execute_with(|| {
+		for i in 0..2 {
+			let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+				metadata_id,
+				hash: create_commit_hash(&test_keys[i as usize], metadata_id, test_hash, [i; 32]),
+			})
+			.encode();
+			assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+				RawOrigin::None.into(),
+				commit_call.clone(),
+				test_keys[i as usize].sign(&commit_call),
+				test_keys[i as usize].public(),
+			));
+		}
+		assert!(!Pallet::<Test>::has_revealed(metadata_id, test_keys[0].public()));
+
+		pallet_commitments::Rounds::<Test>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: System::block_number(),
+				ends_at: System::block_number() + RevealWindowLength::get() as u64,
+			},
+		);
+		// The second participant reveals a hash that doesn't match its commitment
+		for (i, reveal_hash) in [(0u8, test_hash), (1, wrong_hash)] {
+			let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
+				metadata_id,
+				reveal_hash,
+				salt: [i; 32],
+			})
+			.encode();
+			assert_ok!(Pallet::<Test>::reveal_processing_result_hash(
+				RawOrigin::None.into(),
+				reveal_call.clone(),
+				test_keys[i as usize].sign(&reveal_call),
+				test_keys[i as usize].public(),
+			));
+		}
+
+		assert!(Pallet::<Test>::has_revealed(metadata_id, test_keys[0].public()));
+		assert!(!Pallet::<Test>::has_revealed(metadata_id, test_keys[1].public()));
+		assert!(!Pallet::<Test>::has_revealed(metadata_id, test_keys[2].public()));
+		assert!(!Pallet::<Test>::has_revealed(metadata_id + 1, test_keys[0].public()));
+	})
+}
+
+#[test]
+fn reveal_in_the_block_that_settles_the_round_is_still_reported() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	let metadata_id: MetadataId = 0;
+	let (test_hash, _) = get_hashes();
+
+	test_externalities.execute_with(|| {
+		for i in 0..3 {
+			let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+				metadata_id,
+				hash: create_commit_hash(&test_keys[i as usize], metadata_id, test_hash, [i; 32]),
+			})
+			.encode();
+			assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+				RawOrigin::None.into(),
+				commit_call.clone(),
+				test_keys[i as usize].sign(&commit_call),
+				test_keys[i as usize].public(),
+			));
+		}
+		let ends_at = System::block_number() + 1;
+		pallet_commitments::Rounds::<Test>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: System::block_number(),
+				ends_at,
+			},
+		);
+		Pallet::<Test>::schedule_settlement(metadata_id, ends_at);
+
+		// The first two participants reveal in the last block of the window, which settles the
+		// round once its extrinsics are applied
+		System::set_block_number(ends_at);
+		LogicProvider::on_initialize(ends_at);
+		for i in 0..2 {
+			let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
+				metadata_id,
+				reveal_hash: test_hash,
+				salt: [i; 32],
+			})
+			.encode();
+			assert_ok!(Pallet::<Test>::reveal_processing_result_hash(
+				RawOrigin::None.into(),
+				reveal_call.clone(),
+				test_keys[i as usize].sign(&reveal_call),
+				test_keys[i as usize].public(),
+			));
+		}
+		LogicProvider::on_finalize(ends_at);
+
+		assert_eq!(Pallet::<Test>::get_round_state(metadata_id), Some(RoundState::Completed));
+		assert!(pallet_commitments::Pallet::<Test>::get_commitments(metadata_id).is_empty());
+		assert!(Pallet::<Test>::has_revealed(metadata_id, test_keys[0].public()));
+		assert!(Pallet::<Test>::has_revealed(metadata_id, test_keys[1].public()));
+		assert!(!Pallet::<Test>::has_revealed(metadata_id, test_keys[2].public()));
+	})
+}
+
+#[test]
+fn exact_match_settles_on_the_majority_answer() {
+	let (test_hash_1, test_hash_2) = get_hashes();
+	let votes = [(test_hash_1, 2), (test_hash_2, 1)];
+	assert_eq!(ExactMatch::aggregate(&votes, Perbill::from_percent(66)), Some(test_hash_1));
+	assert_eq!(ExactMatch::aggregate(&votes, Perbill::from_percent(67)), None);
+	let tie = [(test_hash_1, 1), (test_hash_2, 1)];
+	assert_eq!(ExactMatch::aggregate(&tie, Perbill::from_percent(50)), None);
+	assert_eq!(ExactMatch::aggregate(&[] as &[(H256, u128)], Perbill::zero()), None);
+	assert!(ExactMatch::agrees(&test_hash_1, &test_hash_1));
+	assert!(!ExactMatch::agrees(&test_hash_2, &test_hash_1));
+}
+
+#[test]
+fn numeric_aggregator_settles_within_tolerance() {
+	parameter_types! {
+		pub const TrimQuarter: NumericStrategy =
+			NumericStrategy::TrimmedMean(Perbill::from_percent(25));
+	}
+	let votes = |values: &[u128]| {
+		values.iter().map(|value| (numeric_answer(*value), 1)).collect::<Vec<_>>()
+	};
+	let not_a_number = H256::repeat_byte(1);
+
+	// the median is backed by the answers within one of it
+	let median_votes = [votes(&[100, 8, 6, 7]), vec![(not_a_number, 1)]].concat();
+	assert_eq!(
+		MedianAggregator::aggregate(&median_votes, Perbill::from_percent(50)),
+		Some(numeric_answer(7))
+	);
+	assert_eq!(MedianAggregator::aggregate(&median_votes, Perbill::from_percent(60)), None);
+	assert!(MedianAggregator::agrees(&numeric_answer(8), &numeric_answer(7)));
+	assert!(!MedianAggregator::agrees(&numeric_answer(100), &numeric_answer(7)));
+	assert!(!MedianAggregator::agrees(&not_a_number, &numeric_answer(7)));
+
+	// a quarter of the weight is dropped at either end before taking the mean
+	type TrimmedMean<Tolerance> = NumericAggregator<TrimQuarter, Tolerance>;
+	let mean_votes = votes(&[6, 8, 10, 100]);
+	assert_eq!(
+		TrimmedMean::<ConstU128<3>>::aggregate(&mean_votes, Perbill::from_percent(50)),
+		Some(numeric_answer(9))
+	);
+	assert_eq!(
+		TrimmedMean::<ConstU128<1>>::aggregate(&mean_votes, Perbill::from_percent(50)),
+		None
+	);
+}
+
+#[test]
+fn numeric_round_rewards_answers_close_to_the_median() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	let metadata_id: MetadataId = 0;
+	let answers = [6u128, 7, 8, 100].map(numeric_answer);
+
+	test_externalities.execute_with(|| {
+		NumericResults::set(true);
+		let accounts = test_keys
+			.iter()
+			.take(4)
+			.map(|key| get_account_from_public(key.public()))
+			.collect::<Vec<_>>();
+		let free_balances = accounts
+			.iter()
+			.map(|account| <Test as Config>::LocalCurrency::free_balance(account))
+			.collect::<Vec<_>>();
+
+		for i in 0..4 {
+			let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+				metadata_id,
+				hash: create_commit_hash(&test_keys[i], metadata_id, answers[i], [i as u8; 32]),
+			})
+			.encode();
+			let signature = test_keys[i].sign(&commit_call);
+			assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+				RawOrigin::None.into(),
+				commit_call,
+				signature,
+				test_keys[i].public(),
+			));
+		}
+
+		pallet_commitments::Rounds::<Test>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: System::block_number(),
+				ends_at: System::block_number() + RevealWindowLength::get() as u64,
+			},
+		);
+		for i in 0..4 {
+			let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
+				metadata_id,
+				reveal_hash: answers[i],
+				salt: [i as u8; 32],
+			})
+			.encode();
+			let signature = test_keys[i].sign(&reveal_call);
+			assert_ok!(Pallet::<Test>::reveal_processing_result_hash(
+				RawOrigin::None.into(),
+				reveal_call,
+				signature,
+				test_keys[i].public(),
+			));
+		}
+
+		assert_ok!(Pallet::<Test>::issue_rewards(RawOrigin::None.into(), metadata_id));
+		assert_eq!(ProcessedHashes::<Test>::get(metadata_id), Some(numeric_answer(7)));
+
+		for i in 0..3 {
+			assert_eq!(
+				<Test as Config>::LocalCurrency::free_balance(&accounts[i]),
+				free_balances[i] + Reward::get() / 3
+			);
+		}
+		assert_eq!(
+			<Test as Config>::LocalCurrency::free_balance(&accounts[3]),
+			free_balances[3] - FundsToLock::get()
+		);
+	})
+}
+
+#[test]
+fn settlement_is_spread_over_blocks_within_the_weight_budget() {
+	let (mut test_externalities, _test_accounts) = new_test_ext();
+	test_externalities.execute_with(|| {
+		// The rounds are scheduled the block before their reveal window ends
+		let now = System::block_number() + 1;
+		for metadata_id in 1..=6 {
+			pallet_commitments::Rounds::<Test>::insert(
+				metadata_id,
+				pallet_commitments::RoundPhase::Revealing { starts_at: now, ends_at: now },
+			);
+			Pallet::<Test>::schedule_settlement(metadata_id, now);
+		}
+		System::set_block_number(now);
+		// Enough to settle two rounds without submissions per block
+		MaxRoundSettlementWeight::set(
+			<Test as Config>::WeightInfo::settle_round(0).saturating_mul(2),
+		);
+
+		// Only four rounds fit in the queue, the first two of them are settled right away
+		settle_finished_rounds();
+		assert_eq!(Pallet::<Test>::settlement_queue().len(), 2);
+		assert_eq!(pallet_commitments::Rounds::<Test>::iter_keys().count(), 4);
+		assert!(Pallet::<Test>::rounds_ending_at(now).is_empty());
+		assert_eq!(Pallet::<Test>::rounds_ending_at(now + 1).into_inner(), vec![5, 6]);
+
+		// The rounds left out are queued behind the remaining ones
+		System::set_block_number(now + 1);
+		settle_finished_rounds();
+		assert_eq!(Pallet::<Test>::settlement_queue().len(), 2);
+		assert_eq!(pallet_commitments::Rounds::<Test>::iter_keys().count(), 2);
+
+		// A round heavier than the budget is still settled on its own
+		MaxRoundSettlementWeight::set(Weight::zero());
+		System::set_block_number(now + 2);
+		settle_finished_rounds();
+		assert_eq!(Pallet::<Test>::settlement_queue().len(), 1);
+
+		System::set_block_number(now + 3);
+		settle_finished_rounds();
+		assert!(Pallet::<Test>::settlement_queue().is_empty());
+		assert_eq!(pallet_commitments::Rounds::<Test>::iter_keys().count(), 0);
+		for metadata_id in 1..=6 {
+			assert_eq!(Pallet::<Test>::get_round_state(metadata_id), Some(RoundState::Disputed));
+		}
+	})
+}
+
+// Commit and reveal `answer` for `metadata_id` with the first three keys, then settle the round.
+fn settle_unanimous_round(
+	test_keys: &[primitives::shared::Pair],
+	metadata_id: MetadataId,
+	answer: H256,
+) {
+	for (i, key) in test_keys.iter().take(3).enumerate() {
+		let commit_call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+			metadata_id,
+			hash: create_commit_hash(key, metadata_id, answer, [i as u8; 32]),
+		})
+		.encode();
+		let signature = key.sign(&commit_call);
+		assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+			RawOrigin::None.into(),
+			commit_call,
+			signature,
+			key.public(),
+		));
+	}
+
+	pallet_commitments::Rounds::<Test>::insert(
+		metadata_id,
+		pallet_commitments::RoundPhase::Revealing {
+			starts_at: System::block_number(),
+			ends_at: System::block_number() + RevealWindowLength::get() as u64,
+		},
+	);
+	for (i, key) in test_keys.iter().take(3).enumerate() {
+		let reveal_call = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
+			metadata_id,
+			reveal_hash: answer,
+			salt: [i as u8; 32],
+		})
+		.encode();
+		let signature = key.sign(&reveal_call);
+		assert_ok!(Pallet::<Test>::reveal_processing_result_hash(
+			RawOrigin::None.into(),
+			reveal_call,
+			signature,
+			key.public(),
+		));
+	}
+
+	assert_ok!(Pallet::<Test>::issue_rewards(RawOrigin::None.into(), metadata_id));
+}
+
+#[test]
+fn sent_result_is_delivered_once_the_bridge_confirms_it() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	let metadata_id: MetadataId = 1;
+	let (test_hash, ..) = get_hashes();
+
+	test_externalities.execute_with(|| {
+		settle_unanimous_round(&test_keys, metadata_id, test_hash);
+		let entry = Pallet::<Test>::outbound_result(metadata_id).unwrap();
+		assert_eq!(entry.payload.result_hash, test_hash);
+		assert_eq!(
+			entry.payload.round_info,
+			RoundInfo { settled_at: 1, participants: 3, agreeing: 3 }
+		);
+		assert_eq!(entry.payload.nonce, 0);
+		assert_eq!(entry.attempts, 1);
+		assert!(matches!(entry.status, DeliveryStatus::Sent(_)));
+		assert_eq!(Pallet::<Test>::next_result_nonce(), 1);
+
+		// Confirmations of other messages are ignored
+		Pallet::<Test>::confirm_delivery(2..=4);
+		assert!(matches!(
+			Pallet::<Test>::outbound_result(metadata_id).unwrap().status,
+			DeliveryStatus::Sent(_)
+		));
+
+		Pallet::<Test>::confirm_delivery(1..=4);
+		assert_eq!(
+			Pallet::<Test>::outbound_result(metadata_id).unwrap().status,
+			DeliveryStatus::Delivered
+		);
+		System::assert_last_event(Event::<Test>::ResultDelivered { metadata_id }.into());
+	})
+}
+
+#[test]
+fn failed_result_send_is_retried_with_backoff() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	let (test_hash, ..) = get_hashes();
+
+	test_externalities.execute_with(|| {
+		let now = System::block_number();
+		BridgeAvailable::set(false);
+		settle_unanimous_round(&test_keys, 1, test_hash);
+		assert_eq!(Pallet::<Test>::outbound_result(1).unwrap().status, DeliveryStatus::Pending);
+		System::assert_last_event(
+			Event::<Test>::ResultSendFailed { metadata_id: 1, attempts: 1, retry_at: now + 2 }
+				.into(),
+		);
+
+		System::set_block_number(now + 1);
+		LogicProvider::on_initialize(now + 1);
+		settle_unanimous_round(&test_keys, 2, test_hash);
+		// Nothing is sent again before the delay is over
+		assert_eq!(Pallet::<Test>::outbound_result(1).unwrap().attempts, 1);
+
+		// The delay doubles after every failed attempt
+		System::set_block_number(now + 2);
+		LogicProvider::on_initialize(now + 2);
+		System::assert_last_event(
+			Event::<Test>::ResultSendFailed { metadata_id: 1, attempts: 2, retry_at: now + 6 }
+				.into(),
+		);
+		System::set_block_number(now + 3);
+		LogicProvider::on_initialize(now + 3);
+		assert_eq!(Pallet::<Test>::outbound_result(2).unwrap().attempts, 2);
+
+		// The first result runs out of attempts
+		System::set_block_number(now + 6);
+		LogicProvider::on_initialize(now + 6);
+		assert_eq!(Pallet::<Test>::outbound_result(1).unwrap().status, DeliveryStatus::Failed);
+		System::assert_last_event(Event::<Test>::ResultDeliveryFailed { metadata_id: 1 }.into());
+		// Failed sends don't use up nonces
+		assert_eq!(Pallet::<Test>::next_result_nonce(), 0);
+
+		// The second one makes it once the bridge is back
+		BridgeAvailable::set(true);
+		System::set_block_number(now + 7);
+		LogicProvider::on_initialize(now + 7);
+		let entry = Pallet::<Test>::outbound_result(2).unwrap();
+		assert_eq!(entry.attempts, 3);
+		assert!(matches!(entry.status, DeliveryStatus::Sent(_)));
+		assert_eq!(entry.payload.nonce, 0);
+		assert_eq!(Pallet::<Test>::next_result_nonce(), 1);
+	})
+}
+
+#[test]
+fn unconfirmed_result_is_sent_again_after_the_delivery_timeout() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	let (test_hash, ..) = get_hashes();
+
+	test_externalities.execute_with(|| {
+		let now = System::block_number();
+		settle_unanimous_round(&test_keys, 1, test_hash);
+		assert_eq!(Pallet::<Test>::outbound_result(1).unwrap().payload.nonce, 0);
+
+		// Nothing is sent again before the timeout
+		System::set_block_number(now + 4);
+		LogicProvider::on_initialize(now + 4);
+		assert_eq!(Pallet::<Test>::outbound_result(1).unwrap().attempts, 1);
+
+		// The result is sent again under a new nonce
+		System::set_block_number(now + 5);
+		LogicProvider::on_initialize(now + 5);
+		System::assert_has_event(
+			Event::<Test>::ResultDeliveryTimedOut { metadata_id: 1, attempts: 1 }.into(),
+		);
+		let entry = Pallet::<Test>::outbound_result(1).unwrap();
+		assert_eq!(entry.attempts, 2);
+		assert_eq!(entry.payload.nonce, 1);
+		assert!(matches!(entry.status, DeliveryStatus::Sent(_)));
+
+		// It is given up on once it runs out of attempts
+		System::set_block_number(now + 10);
+		LogicProvider::on_initialize(now + 10);
+		assert_eq!(Pallet::<Test>::outbound_result(1).unwrap().attempts, 3);
+		System::set_block_number(now + 15);
+		LogicProvider::on_initialize(now + 15);
+		assert_eq!(Pallet::<Test>::outbound_result(1).unwrap().status, DeliveryStatus::Failed);
+		System::assert_last_event(Event::<Test>::ResultDeliveryFailed { metadata_id: 1 }.into());
+		assert_eq!(Pallet::<Test>::next_result_nonce(), 3);
+
+		// A late confirmation of any of the messages still counts
+		Pallet::<Test>::confirm_delivery(1..=1);
+		assert_eq!(Pallet::<Test>::outbound_result(1).unwrap().status, DeliveryStatus::Delivered);
+	})
+}
+
+#[test]
+fn commits_require_an_open_request() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	test_externalities.execute_with(|| {
+		OpenRequests::set(Some(vec![0]));
+		let (test_hash, _) = get_hashes();
+
+		let call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+			metadata_id: 1,
+			hash: create_commit_hash(&test_keys[0], 1, test_hash, [0; 32]),
+		})
+		.encode();
+		assert_noop!(
+			Pallet::<Test>::commit_processing_result_hash(
+				RawOrigin::None.into(),
+				call.clone(),
+				test_keys[0].sign(&call),
+				test_keys[0].public(),
+			),
+			Error::<Test>::RequestNotOpen
+		);
+
+		// The winners of a settled round are handed to the request queue
+		settle_unanimous_round(&test_keys, 0, test_hash);
+		let settled = SettledRequests::get();
+		assert_eq!(settled.len(), 1);
+		assert_eq!(settled[0].0, 0);
+		let mut winners: Vec<_> = settled[0].1.iter().map(|(account, _)| account.clone()).collect();
+		let mut expected: Vec<_> = test_keys
+			.iter()
+			.take(3)
+			.map(|key| get_account_from_public(key.public()))
+			.collect();
+		winners.sort();
+		expected.sort();
+		assert_eq!(winners, expected);
+		assert!(OpenRequests::get().unwrap().is_empty());
+	})
+}
+
+#[test]
+fn only_requests_accepting_commitments_are_pending() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	test_externalities.execute_with(|| {
+		OpenRequests::set(Some(vec![0, 1, 2]));
+		let (test_hash, _) = get_hashes();
+
+		settle_unanimous_round(&test_keys, 0, test_hash);
+		assert_eq!(Pallet::<Test>::pending_requests(), vec![1, 2]);
+
+		// Rounds in their reveal window no longer accept commitments
+		pallet_commitments::Rounds::<Test>::insert(
+			1,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: System::block_number(),
+				ends_at: System::block_number() + RevealWindowLength::get() as u64,
+			},
+		);
+		assert_eq!(Pallet::<Test>::pending_requests(), vec![2]);
+
+		// Rounds that never reach a quorum can start over once they expire
+		let call = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+			metadata_id: 2,
+			hash: create_commit_hash(&test_keys[0], 2, test_hash, [0; 32]),
+		})
+		.encode();
+		assert_ok!(Pallet::<Test>::commit_processing_result_hash(
+			RawOrigin::None.into(),
+			call.clone(),
+			test_keys[0].sign(&call),
+			test_keys[0].public(),
+		));
+		let expires_at = System::block_number() + OpenPhaseLength::get() as u64;
+		System::set_block_number(expires_at);
+		Commitments::on_initialize(expires_at);
+		assert_eq!(Pallet::<Test>::pending_requests(), vec![2]);
+		assert!(SettledRequests::get().iter().all(|(metadata_id, _)| *metadata_id == 0));
+	})
+}
+
+#[test]
+fn results_are_sent_back_to_the_requesting_location() {
+	let (mut test_externalities, test_keys) = new_test_ext();
+	test_externalities.execute_with(|| {
+		let location = MultiLocation::new(1, X1(GlobalConsensus(NetworkId::Rococo)));
+		OpenRequests::set(Some(vec![0, 1]));
+		RequestLocations::mutate(|locations| locations.insert(1, location));
+		let (test_hash, _) = get_hashes();
+
+		settle_unanimous_round(&test_keys, 0, test_hash);
+		assert_eq!(LastDestination::get(), None);
+		assert_eq!(Pallet::<Test>::result_destination(0), None);
+
+		// The destination is kept for the results sent again
+		let now = System::block_number();
+		BridgeAvailable::set(false);
+		settle_unanimous_round(&test_keys, 1, test_hash);
+		assert_eq!(Pallet::<Test>::result_destination(1), Some(location));
+		BridgeAvailable::set(true);
+		System::set_block_number(now + 2);
+		LogicProvider::on_initialize(now + 2);
+		assert!(matches!(
+			Pallet::<Test>::outbound_result(1).unwrap().status,
+			DeliveryStatus::Sent(_)
+		));
+		assert_eq!(LastDestination::get(), Some(location));
+	})
+}
@@ -1,30 +1,12 @@
-//! Autogenerated weights for `pallet_logic_provider`
+//! Weights for `pallet_logic_provider`
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2022-05-30, STEPS: `25`, REPEAT: 25, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
-
-// Executed Command:
-// ../../../target/release/node-template
-// benchmark
-// pallet
-// --chain
-// dev
-// --execution
-// wasm
-// --wasm-execution
-// compiled
-// --pallet
-// pallet_logic_provider
-// --extrinsic
-// *
-// --steps
-// 25
-// --repeat
-// 25
-// --json-file=benchmark_raw.json
-// --output
-// ./weights_new.rs
+//! Estimated for the Millau configuration until the benchmarks are run on reference hardware:
+//! the weights generated on 2022-05-30 predate the settlement queue, the reputation and the
+//! results sent over the bridge. Regenerate them by running `./benchmark.sh` from the `src` dir on
+//! a node built with `--features=runtime-benchmarks`.
+//!
+//! Sending a result includes the bridge message it is carried in, i.e. the
+//! `BridgeRialtoParachainMessages` storage touched by `send_message`.
 
 // #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -46,26 +28,39 @@ pub trait WeightInfo {
 	fn set_majority_type() -> Weight;
 	/// the `s` parameter stands for the number of submissions
 	fn resolve_metadata_dispute(s: u32) -> Weight;
-	/// the `metadatas` parameter stands for the number of metadatas to be scheduled
-	fn on_finalize(metadatas: u32, max_participants: u32) -> Weight;
+	/// the `r` parameter stands for the number of rounds looked at for the settlement queue
+	fn on_initialize(r: u32) -> Weight;
+	/// the `s` parameter stands for the number of submissions of the settled round
+	fn settle_round(s: u32) -> Weight;
+	fn retry_slash() -> Weight;
+	fn retry_result_delivery() -> Weight;
+	/// the `n` parameter stands for the number of bridge messages confirmed
+	fn confirm_delivery(n: u32) -> Weight;
 }
 
 /// Weight functions for `logic_provider`.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: ParticipantRegistry KeyOwners (r:1 w:0)
+	// Storage: ParticipantRegistry Participants (r:1 w:0)
+	// Storage: LogicProvider Reputation (r:1 w:0)
 	// Storage: LogicProvider CommitmentBlockNumbers (r:1 w:1)
 	// Storage: Commitments Commits (r:1 w:1)
 	// Storage: LogicProvider ProcessedHashes (r:1 w:0)
-	// Storage: Commitments RevealWindow (r:1 w:0)
+	// Storage: MetadataRequests OpenRequests (r:1 w:0)
+	// Storage: Commitments Rounds (r:1 w:1)
+	// Storage: Commitments PhaseChanges (r:1 w:1)
 	// Storage: LogicProvider RoundStates (r:1 w:0)
-	// Storage: LogicProvider MajorityType (r:1 w:0)
+	// Storage: LogicProvider ParticipantStakes (r:1 w:1)
+	// Storage: LogicProvider CommitQuorum (r:1 w:0)
+	// Storage: LogicProvider RoundsEndingAt (r:1 w:1)
 	// Storage: Balances Locks (r:1 w:1)
 	fn commit_processing_result_hash() -> Weight {
 		Weight::from_parts(33_000_000, 0)
-			.saturating_add(T::DbWeight::get().reads(7))
-			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().reads(14))
+			.saturating_add(T::DbWeight::get().writes(7))
 	}
-	// Storage: Commitments RevealWindow (r:1 w:0)
+	// Storage: Commitments Rounds (r:1 w:0)
 	// Storage: Commitments Commits (r:1 w:1)
 	fn reveal_processing_result_hash() -> Weight {
 		Weight::from_parts(15_000_000, 0)
@@ -74,81 +69,170 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	}
 	// Storage: LogicProvider CommitmentBlockNumbers (r:1 w:1)
 	// Storage: Commitments Commits (r:1 w:1)
-	// Storage: LogicProvider MajorityType (r:1 w:0)
+	// Storage: LogicProvider RevealAgreement (r:1 w:0)
 	// Storage: Balances Locks (r:6 w:6)
 	// Storage: LogicProvider RoundStates (r:0 w:1)
 	// Storage: LogicProvider ProcessedHashes (r:0 w:1)
+	// Storage: LogicProvider OutboundResults (r:0 w:1)
+	// Storage: LogicProvider SentMessages (r:0 w:1)
+	// Storage: LogicProvider NextResultNonce (r:1 w:1)
+	// Storage: LogicProvider ResultDestinations (r:1 w:1)
+	// Storage: LogicProvider DeliveryRetries (r:0 w:1)
+	// Storage: LogicProvider ParticipantStakes (r:1 w:1)
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages PalletOperatingMode (r:1 w:0)
+	// Storage: BridgeRialtoParachainMessages OutboundLanes (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages OutboundMessages (r:0 w:1)
+	// Storage: LogicProvider Reputation (r:s w:s)
 	fn issue_rewards_to_some_participants(s: u32) -> Weight {
-		Weight::from_parts(0, 0)
-			// Standard Error: 28_000
+		Weight::from_parts(25_000_000, 0)
 			.saturating_add(Weight::from_parts(13_797_000, 0).saturating_mul(s as u64))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().reads((1u64).saturating_mul(s as u64)))
-			.saturating_add(T::DbWeight::get().writes(4))
-			.saturating_add(T::DbWeight::get().writes((1u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().reads((2u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes(14))
+			.saturating_add(T::DbWeight::get().writes((2u64).saturating_mul(s as u64)))
 	}
 	// Storage: LogicProvider CommitmentBlockNumbers (r:1 w:1)
 	// Storage: Commitments Commits (r:1 w:1)
-	// Storage: LogicProvider MajorityType (r:1 w:0)
+	// Storage: LogicProvider RevealAgreement (r:1 w:0)
 	// Storage: Balances Locks (r:10 w:10)
 	// Storage: LogicProvider RoundStates (r:0 w:1)
 	// Storage: LogicProvider ProcessedHashes (r:0 w:1)
+	// Storage: LogicProvider OutboundResults (r:0 w:1)
+	// Storage: LogicProvider SentMessages (r:0 w:1)
+	// Storage: LogicProvider NextResultNonce (r:1 w:1)
+	// Storage: LogicProvider ResultDestinations (r:1 w:1)
+	// Storage: LogicProvider DeliveryRetries (r:0 w:1)
+	// Storage: LogicProvider ParticipantStakes (r:1 w:1)
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages PalletOperatingMode (r:1 w:0)
+	// Storage: BridgeRialtoParachainMessages OutboundLanes (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages OutboundMessages (r:0 w:1)
+	// Storage: LogicProvider Reputation (r:s w:s)
 	fn issue_rewards_to_all_participants(s: u32) -> Weight {
-		Weight::from_parts(0, 0)
-			// Standard Error: 30_000
+		Weight::from_parts(25_000_000, 0)
 			.saturating_add(Weight::from_parts(15_022_000, 0).saturating_mul(s as u64))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().reads((1u64).saturating_mul(s as u64)))
-			.saturating_add(T::DbWeight::get().writes(4))
-			.saturating_add(T::DbWeight::get().writes((1u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().reads((2u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes(14))
+			.saturating_add(T::DbWeight::get().writes((2u64).saturating_mul(s as u64)))
 	}
-	// Storage: LogicProvider MajorityType (r:0 w:1)
+	// Storage: LogicProvider CommitQuorum (r:0 w:1)
+	// Storage: LogicProvider RevealAgreement (r:0 w:1)
 	fn set_majority_type() -> Weight {
-		Weight::from_parts(1_000_000, 0).saturating_add(T::DbWeight::get().writes(1))
+		Weight::from_parts(1_000_000, 0).saturating_add(T::DbWeight::get().writes(2))
 	}
 	// Storage: LogicProvider RoundStates (r:1 w:1)
 	// Storage: LogicProvider CommitmentBlockNumbers (r:1 w:1)
 	// Storage: Commitments Commits (r:1 w:1)
 	// Storage: Balances Locks (r:5 w:5)
+	// Storage: LogicProvider Reputation (r:s w:s)
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:1 w:1)
 	fn resolve_metadata_dispute(s: u32) -> Weight {
 		Weight::from_parts(0, 0)
-			// Standard Error: 22_000
 			.saturating_add(Weight::from_parts(12_842_000, 0).saturating_mul(s as u64))
-			.saturating_add(T::DbWeight::get().reads(3))
-			.saturating_add(T::DbWeight::get().reads((1u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().reads((2u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes(5))
+			.saturating_add(T::DbWeight::get().writes((2u64).saturating_mul(s as u64)))
+	}
+	// Storage: LogicProvider RoundsEndingAt (r:1 w:1)
+	// Storage: Commitments Rounds (r:r w:0)
+	// Storage: LogicProvider SettlementQueue (r:1 w:1)
+	// Storage: Commitments Commits (r:r w:0)
+	// Storage: LogicProvider RoundsToSettle (r:0 w:1)
+	fn on_initialize(r: u32) -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(Weight::from_parts(3_500_000, 0).saturating_mul(r as u64))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((2u64).saturating_mul(r as u64)))
 			.saturating_add(T::DbWeight::get().writes(3))
-			.saturating_add(T::DbWeight::get().writes((1u64).saturating_mul(s as u64)))
 	}
-
-	fn on_finalize(metadatas: u32, max_participants: u32) -> Weight {
-		Weight::from_parts(metadatas as u64, 0)
-			.saturating_mul(
-				Self::issue_rewards_to_some_participants(max_participants)
-					.max(Self::issue_rewards_to_all_participants(max_participants)).ref_time(),
-			)
-			.saturating_add(
-				T::DbWeight::get()
-					.writes(1)
-					.saturating_add(T::DbWeight::get().reads(1)),
-			)
+	// Storage: LogicProvider RoundsToSettle (r:1 w:1)
+	// Storage: LogicProvider SettlementQueue (r:1 w:1)
+	// Storage: LogicProvider SettledReveals (r:0 w:1)
+	// Storage: LogicProvider CommitmentBlockNumbers (r:1 w:1)
+	// Storage: Commitments Commits (r:1 w:1)
+	// Storage: LogicProvider RevealAgreement (r:1 w:0)
+	// Storage: LogicProvider ParticipantStakes (r:1 w:1)
+	// Storage: Balances Locks (r:s w:s)
+	// Storage: LogicProvider Reputation (r:s w:s)
+	// Storage: LogicProvider RoundStates (r:0 w:1)
+	// Storage: LogicProvider ProcessedHashes (r:0 w:1)
+	// Storage: LogicProvider OutboundResults (r:0 w:1)
+	// Storage: LogicProvider SentMessages (r:0 w:1)
+	// Storage: LogicProvider NextResultNonce (r:1 w:1)
+	// Storage: LogicProvider ResultDestinations (r:1 w:1)
+	// Storage: LogicProvider DeliveryRetries (r:0 w:1)
+	// Storage: Commitments Rounds (r:0 w:1)
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages PalletOperatingMode (r:1 w:0)
+	// Storage: BridgeRialtoParachainMessages OutboundLanes (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages OutboundMessages (r:0 w:1)
+	fn settle_round(s: u32) -> Weight {
+		Weight::from_parts(50_000_000, 0)
+			.saturating_add(Weight::from_parts(15_000_000, 0).saturating_mul(s as u64))
+			.saturating_add(T::DbWeight::get().reads(12))
+			.saturating_add(T::DbWeight::get().reads((2u64).saturating_mul(s as u64)))
+			.saturating_add(T::DbWeight::get().writes(18))
+			.saturating_add(T::DbWeight::get().writes((2u64).saturating_mul(s as u64)))
+	}
+	// Storage: LogicProvider PendingSlashes (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn retry_slash() -> Weight {
+		Weight::from_parts(25_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: LogicProvider DeliveryRetries (r:1 w:2)
+	// Storage: LogicProvider OutboundResults (r:1 w:1)
+	// Storage: LogicProvider SentMessages (r:0 w:1)
+	// Storage: LogicProvider NextResultNonce (r:1 w:1)
+	// Storage: LogicProvider ResultDestinations (r:1 w:0)
+	// Storage: BridgeRialtoParachainMessages PalletOperatingMode (r:1 w:0)
+	// Storage: BridgeRialtoParachainMessages OutboundLanes (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages OutboundMessages (r:0 w:1)
+	fn retry_result_delivery() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(7))
+	}
+	// Storage: LogicProvider SentMessages (r:n w:n)
+	// Storage: LogicProvider OutboundResults (r:n w:n)
+	fn confirm_delivery(n: u32) -> Weight {
+		Weight::from_parts(1_000_000, 0)
+			.saturating_add(Weight::from_parts(8_000_000, 0).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads((2u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes((2u64).saturating_mul(n as u64)))
 	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
+	// Storage: ParticipantRegistry KeyOwners (r:1 w:0)
+	// Storage: ParticipantRegistry Participants (r:1 w:0)
+	// Storage: LogicProvider Reputation (r:1 w:0)
 	// Storage: LogicProvider CommitmentBlockNumbers (r:1 w:1)
 	// Storage: Commitments Commits (r:1 w:1)
 	// Storage: LogicProvider ProcessedHashes (r:1 w:0)
-	// Storage: Commitments RevealWindow (r:1 w:0)
+	// Storage: MetadataRequests OpenRequests (r:1 w:0)
+	// Storage: Commitments Rounds (r:1 w:1)
+	// Storage: Commitments PhaseChanges (r:1 w:1)
 	// Storage: LogicProvider RoundStates (r:1 w:0)
-	// Storage: LogicProvider MajorityType (r:1 w:0)
+	// Storage: LogicProvider ParticipantStakes (r:1 w:1)
+	// Storage: LogicProvider CommitQuorum (r:1 w:0)
+	// Storage: LogicProvider RoundsEndingAt (r:1 w:1)
 	// Storage: Balances Locks (r:1 w:1)
 	fn commit_processing_result_hash() -> Weight {
 		Weight::from_parts(33_000_000, 0)
-			.saturating_add(RocksDbWeight::get().reads(7))
-			.saturating_add(RocksDbWeight::get().writes(3))
+			.saturating_add(RocksDbWeight::get().reads(14))
+			.saturating_add(RocksDbWeight::get().writes(7))
 	}
-	// Storage: Commitments RevealWindow (r:1 w:0)
+	// Storage: Commitments Rounds (r:1 w:0)
 	// Storage: Commitments Commits (r:1 w:1)
 	fn reveal_processing_result_hash() -> Weight {
 		Weight::from_parts(15_000_000, 0)
@@ -157,55 +241,144 @@ impl WeightInfo for () {
 	}
 	// Storage: LogicProvider CommitmentBlockNumbers (r:1 w:1)
 	// Storage: Commitments Commits (r:1 w:1)
-	// Storage: LogicProvider MajorityType (r:1 w:0)
+	// Storage: LogicProvider RevealAgreement (r:1 w:0)
 	// Storage: Balances Locks (r:6 w:6)
 	// Storage: LogicProvider RoundStates (r:0 w:1)
 	// Storage: LogicProvider ProcessedHashes (r:0 w:1)
+	// Storage: LogicProvider OutboundResults (r:0 w:1)
+	// Storage: LogicProvider SentMessages (r:0 w:1)
+	// Storage: LogicProvider NextResultNonce (r:1 w:1)
+	// Storage: LogicProvider ResultDestinations (r:1 w:1)
+	// Storage: LogicProvider DeliveryRetries (r:0 w:1)
+	// Storage: LogicProvider ParticipantStakes (r:1 w:1)
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages PalletOperatingMode (r:1 w:0)
+	// Storage: BridgeRialtoParachainMessages OutboundLanes (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages OutboundMessages (r:0 w:1)
+	// Storage: LogicProvider Reputation (r:s w:s)
 	fn issue_rewards_to_some_participants(s: u32) -> Weight {
-		Weight::from_parts(0, 0)
-			// Standard Error: 28_000
+		Weight::from_parts(25_000_000, 0)
 			.saturating_add(Weight::from_parts(13_797_000, 0).saturating_mul(s as u64))
-			.saturating_add(RocksDbWeight::get().reads(3))
-			.saturating_add(RocksDbWeight::get().reads((1u64).saturating_mul(s as u64)))
-			.saturating_add(RocksDbWeight::get().writes(4))
-			.saturating_add(RocksDbWeight::get().writes((1u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().reads(10))
+			.saturating_add(RocksDbWeight::get().reads((2u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().writes(14))
+			.saturating_add(RocksDbWeight::get().writes((2u64).saturating_mul(s as u64)))
 	}
 	// Storage: LogicProvider CommitmentBlockNumbers (r:1 w:1)
 	// Storage: Commitments Commits (r:1 w:1)
-	// Storage: LogicProvider MajorityType (r:1 w:0)
+	// Storage: LogicProvider RevealAgreement (r:1 w:0)
 	// Storage: Balances Locks (r:10 w:10)
 	// Storage: LogicProvider RoundStates (r:0 w:1)
 	// Storage: LogicProvider ProcessedHashes (r:0 w:1)
+	// Storage: LogicProvider OutboundResults (r:0 w:1)
+	// Storage: LogicProvider SentMessages (r:0 w:1)
+	// Storage: LogicProvider NextResultNonce (r:1 w:1)
+	// Storage: LogicProvider ResultDestinations (r:1 w:1)
+	// Storage: LogicProvider DeliveryRetries (r:0 w:1)
+	// Storage: LogicProvider ParticipantStakes (r:1 w:1)
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages PalletOperatingMode (r:1 w:0)
+	// Storage: BridgeRialtoParachainMessages OutboundLanes (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages OutboundMessages (r:0 w:1)
+	// Storage: LogicProvider Reputation (r:s w:s)
 	fn issue_rewards_to_all_participants(s: u32) -> Weight {
-		Weight::from_parts(0, 0)
-			// Standard Error: 30_000
+		Weight::from_parts(25_000_000, 0)
 			.saturating_add(Weight::from_parts(15_022_000, 0).saturating_mul(s as u64))
-			.saturating_add(RocksDbWeight::get().reads(3))
-			.saturating_add(RocksDbWeight::get().reads((1u64).saturating_mul(s as u64)))
-			.saturating_add(RocksDbWeight::get().writes(4))
-			.saturating_add(RocksDbWeight::get().writes((1u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().reads(10))
+			.saturating_add(RocksDbWeight::get().reads((2u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().writes(14))
+			.saturating_add(RocksDbWeight::get().writes((2u64).saturating_mul(s as u64)))
 	}
-	// Storage: LogicProvider MajorityType (r:0 w:1)
+	// Storage: LogicProvider CommitQuorum (r:0 w:1)
+	// Storage: LogicProvider RevealAgreement (r:0 w:1)
 	fn set_majority_type() -> Weight {
-		Weight::from_parts(1_000_000, 0).saturating_add(RocksDbWeight::get().writes(1))
+		Weight::from_parts(1_000_000, 0).saturating_add(RocksDbWeight::get().writes(2))
 	}
 	// Storage: LogicProvider RoundStates (r:1 w:1)
 	// Storage: LogicProvider CommitmentBlockNumbers (r:1 w:1)
 	// Storage: Commitments Commits (r:1 w:1)
 	// Storage: Balances Locks (r:5 w:5)
+	// Storage: LogicProvider Reputation (r:s w:s)
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:1 w:1)
 	fn resolve_metadata_dispute(s: u32) -> Weight {
 		Weight::from_parts(0, 0)
-			// Standard Error: 22_000
 			.saturating_add(Weight::from_parts(12_842_000, 0).saturating_mul(s as u64))
-			.saturating_add(RocksDbWeight::get().reads(3))
-			.saturating_add(RocksDbWeight::get().reads((1u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().reads((2u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().writes(5))
+			.saturating_add(RocksDbWeight::get().writes((2u64).saturating_mul(s as u64)))
+	}
+	// Storage: LogicProvider RoundsEndingAt (r:1 w:1)
+	// Storage: Commitments Rounds (r:r w:0)
+	// Storage: LogicProvider SettlementQueue (r:1 w:1)
+	// Storage: Commitments Commits (r:r w:0)
+	// Storage: LogicProvider RoundsToSettle (r:0 w:1)
+	fn on_initialize(r: u32) -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(Weight::from_parts(3_500_000, 0).saturating_mul(r as u64))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().reads((2u64).saturating_mul(r as u64)))
 			.saturating_add(RocksDbWeight::get().writes(3))
-			.saturating_add(RocksDbWeight::get().writes((1u64).saturating_mul(s as u64)))
 	}
-	// Storage: LogicProvider StartingBlockForCurrentRound (r:1 w:1)
-	fn on_finalize(_metadatas: u32, _max_participants: u32) -> Weight {
-		RocksDbWeight::get()
-			.writes(1)
-			.saturating_add(RocksDbWeight::get().reads(1))
+	// Storage: LogicProvider RoundsToSettle (r:1 w:1)
+	// Storage: LogicProvider SettlementQueue (r:1 w:1)
+	// Storage: LogicProvider SettledReveals (r:0 w:1)
+	// Storage: LogicProvider CommitmentBlockNumbers (r:1 w:1)
+	// Storage: Commitments Commits (r:1 w:1)
+	// Storage: LogicProvider RevealAgreement (r:1 w:0)
+	// Storage: LogicProvider ParticipantStakes (r:1 w:1)
+	// Storage: Balances Locks (r:s w:s)
+	// Storage: LogicProvider Reputation (r:s w:s)
+	// Storage: LogicProvider RoundStates (r:0 w:1)
+	// Storage: LogicProvider ProcessedHashes (r:0 w:1)
+	// Storage: LogicProvider OutboundResults (r:0 w:1)
+	// Storage: LogicProvider SentMessages (r:0 w:1)
+	// Storage: LogicProvider NextResultNonce (r:1 w:1)
+	// Storage: LogicProvider ResultDestinations (r:1 w:1)
+	// Storage: LogicProvider DeliveryRetries (r:0 w:1)
+	// Storage: Commitments Rounds (r:0 w:1)
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages PalletOperatingMode (r:1 w:0)
+	// Storage: BridgeRialtoParachainMessages OutboundLanes (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages OutboundMessages (r:0 w:1)
+	fn settle_round(s: u32) -> Weight {
+		Weight::from_parts(50_000_000, 0)
+			.saturating_add(Weight::from_parts(15_000_000, 0).saturating_mul(s as u64))
+			.saturating_add(RocksDbWeight::get().reads(12))
+			.saturating_add(RocksDbWeight::get().reads((2u64).saturating_mul(s as u64)))
+			.saturating_add(RocksDbWeight::get().writes(18))
+			.saturating_add(RocksDbWeight::get().writes((2u64).saturating_mul(s as u64)))
+	}
+	// Storage: LogicProvider PendingSlashes (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn retry_slash() -> Weight {
+		Weight::from_parts(25_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	// Storage: LogicProvider DeliveryRetries (r:1 w:2)
+	// Storage: LogicProvider OutboundResults (r:1 w:1)
+	// Storage: LogicProvider SentMessages (r:0 w:1)
+	// Storage: LogicProvider NextResultNonce (r:1 w:1)
+	// Storage: LogicProvider ResultDestinations (r:1 w:0)
+	// Storage: BridgeRialtoParachainMessages PalletOperatingMode (r:1 w:0)
+	// Storage: BridgeRialtoParachainMessages OutboundLanes (r:1 w:1)
+	// Storage: BridgeRialtoParachainMessages OutboundMessages (r:0 w:1)
+	fn retry_result_delivery() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(7))
+	}
+	// Storage: LogicProvider SentMessages (r:n w:n)
+	// Storage: LogicProvider OutboundResults (r:n w:n)
+	fn confirm_delivery(n: u32) -> Weight {
+		Weight::from_parts(1_000_000, 0)
+			.saturating_add(Weight::from_parts(8_000_000, 0).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads((2u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes((2u64).saturating_mul(n as u64)))
 	}
 }
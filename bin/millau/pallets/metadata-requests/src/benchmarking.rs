@@ -0,0 +1,96 @@
+//! Benchmarking setup for pallet-metadata-requests
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, vec, whitelisted_caller, BenchmarkError};
+use frame_support::{
+	traits::{Currency, EnsureOrigin, Get, Hooks, ReservableCurrency},
+	BoundedVec,
+};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, Saturating};
+use xcm_executor::traits::ConvertLocation;
+
+fn funded<T: Config>(who: &T::AccountId) {
+	T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+}
+
+fn reward<T: Config>() -> BalanceOf<T> {
+	T::Currency::minimum_balance().saturating_mul(1_000u32.into())
+}
+
+/// Open `count` requests of `requester` expiring at `deadline`.
+fn open_requests<T: Config>(requester: &T::AccountId, count: u32, deadline: T::BlockNumber) {
+	let mut open = Vec::new();
+	for metadata_id in 0..count as MetadataId {
+		T::Currency::reserve(requester, reward::<T>()).unwrap();
+		Requests::<T>::insert(
+			metadata_id,
+			MetadataRequest {
+				id: metadata_id,
+				requester: requester.clone(),
+				source: Vec::new(),
+				deadline,
+				reward: reward::<T>(),
+				reply_to: None,
+			},
+		);
+		open.push((metadata_id, deadline));
+	}
+	OpenRequests::<T>::put(BoundedVec::truncate_from(open));
+	NextMetadataId::<T>::put(count as MetadataId);
+}
+
+benchmarks! {
+	request {
+		let origin = T::RequestOrigin::try_successful_origin()
+			.map_err(|_| BenchmarkError::Weightless)?;
+		let requester = T::RequestOrigin::ensure_origin(origin.clone())
+			.map_err(|_| "the successful origin is not a request origin")?;
+		funded::<T>(&requester);
+		let deadline = T::BlockNumber::max_value();
+		open_requests::<T>(&requester, T::MaxOpenRequests::get().saturating_sub(1), deadline);
+		let source = vec![0; T::MaxSourceLength::get() as usize];
+	}: _<T::RuntimeOrigin>(origin, source, deadline, reward::<T>())
+	verify {
+		assert_eq!(OpenRequests::<T>::get().len() as u32, T::MaxOpenRequests::get());
+	}
+
+	cancel_request {
+		let requester: T::AccountId = whitelisted_caller();
+		funded::<T>(&requester);
+		open_requests::<T>(&requester, T::MaxOpenRequests::get(), T::BlockNumber::max_value());
+		let metadata_id = T::MaxOpenRequests::get().saturating_sub(1) as MetadataId;
+	}: _(RawOrigin::Root, metadata_id)
+	verify {
+		assert!(!Requests::<T>::contains_key(metadata_id));
+	}
+
+	on_initialize {
+		let e in 0 .. T::MaxOpenRequests::get();
+		let requester: T::AccountId = whitelisted_caller();
+		funded::<T>(&requester);
+		let now = <frame_system::Pallet<T>>::block_number();
+		open_requests::<T>(&requester, e, now);
+	}: { Pallet::<T>::on_initialize(now) }
+	verify {
+		assert!(OpenRequests::<T>::get().is_empty());
+	}
+
+	request_from_location {
+		let origin = T::CrossChainOrigin::try_successful_origin()
+			.map_err(|_| BenchmarkError::Weightless)?;
+		let location = T::CrossChainOrigin::ensure_origin(origin.clone())
+			.map_err(|_| "the successful origin is not a cross chain origin")?;
+		let requester = T::SovereignAccountOf::convert_location(&location)
+			.ok_or(BenchmarkError::Weightless)?;
+		funded::<T>(&requester);
+		let deadline = T::BlockNumber::max_value();
+		open_requests::<T>(&requester, T::MaxOpenRequests::get().saturating_sub(1), deadline);
+		let source = vec![0; T::MaxSourceLength::get() as usize];
+	}: _<T::RuntimeOrigin>(origin, source, T::MinimumLifetime::get(), reward::<T>())
+	verify {
+		let metadata_id = T::MaxOpenRequests::get().saturating_sub(1) as MetadataId;
+		assert_eq!(Requests::<T>::get(metadata_id).unwrap().reply_to, Some(location));
+	}
+}
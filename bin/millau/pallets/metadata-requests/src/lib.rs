@@ -0,0 +1,416 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Queue of the metadata the logic providers are asked to process. A requester describes where
+/// the metadata comes from, sets a deadline and escrows a reward; the reward goes to the
+/// participants who settle the round, or back to the requester if the deadline passes first.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::*;
+
+use primitives::shared::MetadataId;
+use sp_std::vec::Vec;
+use xcm::latest::MultiLocation;
+
+/// What the logic provider needs to know about the requested metadata.
+pub trait MetadataRequests<AccountId> {
+	/// Whether `metadata_id` was requested and the request is still open.
+	fn is_open(metadata_id: MetadataId) -> bool;
+
+	/// The ids of the open requests, oldest first.
+	fn open_requests() -> Vec<MetadataId>;
+
+	/// The round of `metadata_id` was settled in favour of `winners`. Closes the request and
+	/// splits its reward between the winners in proportion to their weights.
+	fn on_settled(metadata_id: MetadataId, winners: &[(AccountId, u128)]);
+
+	/// The location that requested `metadata_id` from another chain, which its result is sent
+	/// back to.
+	fn reply_to(metadata_id: MetadataId) -> Option<MultiLocation>;
+
+	/// Open a request for `metadata_id`, so that benchmarks can commit to it.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn open(_metadata_id: MetadataId) {}
+}
+
+/// No request queue: every metadata id may be committed to, and nothing is escrowed.
+impl<AccountId> MetadataRequests<AccountId> for () {
+	fn is_open(_metadata_id: MetadataId) -> bool {
+		true
+	}
+
+	fn open_requests() -> Vec<MetadataId> {
+		Vec::new()
+	}
+
+	fn on_settled(_metadata_id: MetadataId, _winners: &[(AccountId, u128)]) {}
+
+	fn reply_to(_metadata_id: MetadataId) -> Option<MultiLocation> {
+		None
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		dispatch::DispatchResult,
+		pallet_prelude::*,
+		traits::{BalanceStatus, Currency, ReservableCurrency},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::{
+		traits::{Saturating, Zero},
+		Perbill,
+	};
+	use xcm_executor::traits::ConvertLocation;
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Currency the rewards are escrowed in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Who may request metadata to be processed, resolving to the account the reward is
+		/// escrowed from, e.g. `EnsureSigned`.
+		type RequestOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+		/// Locations of other chains that may request metadata over XCM, e.g. `EnsureXcm`. The
+		/// results of their requests are sent back to them.
+		type CrossChainOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = MultiLocation>;
+		/// The account of a requesting location on this chain, which the reward of its requests
+		/// is escrowed from.
+		type SovereignAccountOf: ConvertLocation<Self::AccountId>;
+		/// May cancel requests, refunding their reward.
+		type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Maximum number of requests open at the same time.
+		#[pallet::constant]
+		type MaxOpenRequests: Get<u32>;
+		/// Maximum length of the source descriptor of a request.
+		#[pallet::constant]
+		type MaxSourceLength: Get<u32>;
+		/// Minimum number of blocks between a request and its deadline, so that a round has the
+		/// time to run.
+		#[pallet::constant]
+		type MinimumLifetime: Get<Self::BlockNumber>;
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+	pub struct MetadataRequest<AccountId, Balance, BlockNumber> {
+		pub id: MetadataId,
+		/// Account the reward is escrowed from, and refunded to.
+		pub requester: AccountId,
+		/// Where the participants get the metadata from, e.g. a URL. Opaque to the chain.
+		pub source: Vec<u8>,
+		/// The request expires, and its reward is refunded, at this block unless the round was
+		/// settled before.
+		pub deadline: BlockNumber,
+		/// Amount reserved from the requester, paid to the winners of the round.
+		pub reward: Balance,
+		/// The location that requested the metadata over XCM, which the result is sent back to.
+		/// `None` for requests made on this chain.
+		pub reply_to: Option<MultiLocation>,
+	}
+
+	pub type MetadataRequestOf<T> = MetadataRequest<
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+		<T as frame_system::Config>::BlockNumber,
+	>;
+
+	/// The id given to the next request. Ids are handed out in order, so that every participant
+	/// commits to the same ones.
+	#[pallet::storage]
+	#[pallet::getter(fn next_metadata_id)]
+	pub type NextMetadataId<T: Config> = StorageValue<_, MetadataId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_request)]
+	pub type Requests<T: Config> =
+		StorageMap<_, Blake2_128Concat, MetadataId, MetadataRequestOf<T>, OptionQuery>;
+
+	/// The open requests along with their deadline, oldest first.
+	#[pallet::storage]
+	pub type OpenRequests<T: Config> = StorageValue<
+		_,
+		BoundedVec<(MetadataId, T::BlockNumber), T::MaxOpenRequests>,
+		ValueQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// `requester` asked for `metadata_id` to be processed by `deadline`, escrowing `reward`.
+		Requested {
+			metadata_id: MetadataId,
+			requester: T::AccountId,
+			deadline: T::BlockNumber,
+			reward: BalanceOf<T>,
+		},
+		/// The round of `metadata_id` was settled and its reward paid out.
+		RequestFulfilled { metadata_id: MetadataId },
+		/// The deadline of `metadata_id` passed; `refund` went back to the requester.
+		RequestExpired { metadata_id: MetadataId, refund: BalanceOf<T> },
+		/// `metadata_id` was cancelled; `refund` went back to the requester.
+		RequestCancelled { metadata_id: MetadataId, refund: BalanceOf<T> },
+	}
+
+	#[pallet::error]
+	#[derive(PartialEq)]
+	pub enum Error<T> {
+		/// `MaxOpenRequests` requests are already open.
+		TooManyOpenRequests,
+		/// The source descriptor is longer than `MaxSourceLength`.
+		SourceTooLong,
+		/// The deadline is less than `MinimumLifetime` blocks away.
+		DeadlineTooSoon,
+		/// The requester does not have enough free balance to escrow the reward.
+		InsufficientBalance,
+		/// There is no open request for the metadata.
+		UnknownRequest,
+		/// The requesting location has no account on this chain to escrow the reward from.
+		NoSovereignAccount,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// Refund the requests whose deadline is reached.
+		fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+			let mut expired = Vec::new();
+			OpenRequests::<T>::mutate(|open| {
+				open.retain(|(metadata_id, deadline)| {
+					let is_expired = *deadline <= now;
+					if is_expired {
+						expired.push(*metadata_id);
+					}
+					!is_expired
+				})
+			});
+
+			for &metadata_id in expired.iter() {
+				if let Some(refund) = Self::refund(metadata_id) {
+					Self::deposit_event(Event::RequestExpired { metadata_id, refund });
+				}
+			}
+			<T as Config>::WeightInfo::on_initialize(expired.len() as u32)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Request the metadata described by `source` to be processed by `deadline`, escrowing
+		/// `reward` for the participants who settle its round. The request is given the next id
+		/// in line.
+		///
+		/// # Errors
+		/// Returns `SourceTooLong` if `source` is longer than `MaxSourceLength`, `DeadlineTooSoon`
+		/// if `deadline` is less than `MinimumLifetime` blocks away, `TooManyOpenRequests` if the
+		/// queue is full and `InsufficientBalance` if the reward can't be reserved.
+		#[pallet::weight(<T as Config>::WeightInfo::request())]
+		pub fn request(
+			origin: OriginFor<T>,
+			source: Vec<u8>,
+			deadline: T::BlockNumber,
+			reward: BalanceOf<T>,
+		) -> DispatchResult {
+			let requester = T::RequestOrigin::ensure_origin(origin)?;
+			Self::do_request(requester, None, source, deadline, reward)
+		}
+
+		/// Cancel the request for `metadata_id` and refund its reward. A round already opened for
+		/// it no longer accepts commitments.
+		/// Origin must have permissions of the [`ForceOrigin`][Config::ForceOrigin].
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_request())]
+		pub fn cancel_request(origin: OriginFor<T>, metadata_id: MetadataId) -> DispatchResult {
+			let _ = T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(Self::close(metadata_id), Error::<T>::UnknownRequest);
+			let refund = Self::refund(metadata_id).unwrap_or_else(Zero::zero);
+
+			Self::deposit_event(Event::RequestCancelled { metadata_id, refund });
+			Ok(())
+		}
+
+		/// Request the metadata described by `source` on behalf of another chain, to be processed
+		/// within `lifetime` blocks. Works like [`Pallet::request`], except that the reward is
+		/// escrowed from the sovereign account of the requesting location, and that the result
+		/// is sent back to that location.
+		///
+		/// # Errors
+		/// Returns `NoSovereignAccount` if the location has no account on this chain, and the
+		/// errors of `request` otherwise.
+		#[pallet::weight(<T as Config>::WeightInfo::request_from_location())]
+		pub fn request_from_location(
+			origin: OriginFor<T>,
+			source: Vec<u8>,
+			lifetime: T::BlockNumber,
+			reward: BalanceOf<T>,
+		) -> DispatchResult {
+			let location = T::CrossChainOrigin::ensure_origin(origin)?;
+			let requester = T::SovereignAccountOf::convert_location(&location)
+				.ok_or(Error::<T>::NoSovereignAccount)?;
+			let deadline = <frame_system::Pallet<T>>::block_number().saturating_add(lifetime);
+			Self::do_request(requester, Some(location), source, deadline, reward)
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Open a request under the next id, escrowing `reward` from `requester`.
+		fn do_request(
+			requester: T::AccountId,
+			reply_to: Option<MultiLocation>,
+			source: Vec<u8>,
+			deadline: T::BlockNumber,
+			reward: BalanceOf<T>,
+		) -> DispatchResult {
+			ensure!(
+				source.len() <= T::MaxSourceLength::get() as usize,
+				Error::<T>::SourceTooLong
+			);
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(
+				deadline >= now.saturating_add(T::MinimumLifetime::get()),
+				Error::<T>::DeadlineTooSoon
+			);
+			let metadata_id = NextMetadataId::<T>::get();
+			OpenRequests::<T>::try_append((metadata_id, deadline))
+				.map_err(|_| Error::<T>::TooManyOpenRequests)?;
+			T::Currency::reserve(&requester, reward).map_err(|_| Error::<T>::InsufficientBalance)?;
+
+			Requests::<T>::insert(
+				metadata_id,
+				MetadataRequest {
+					id: metadata_id,
+					requester: requester.clone(),
+					source,
+					deadline,
+					reward,
+					reply_to,
+				},
+			);
+			NextMetadataId::<T>::put(metadata_id.saturating_add(1));
+
+			Self::deposit_event(Event::Requested { metadata_id, requester, deadline, reward });
+			Ok(())
+		}
+
+		/// Take `metadata_id` off the open requests. Returns whether it was open.
+		fn close(metadata_id: MetadataId) -> bool {
+			OpenRequests::<T>::mutate(|open| {
+				let count = open.len();
+				open.retain(|(id, _)| *id != metadata_id);
+				open.len() < count
+			})
+		}
+
+		/// Remove the request for `metadata_id` and hand its reward back to the requester.
+		/// Returns the amount refunded.
+		fn refund(metadata_id: MetadataId) -> Option<BalanceOf<T>> {
+			let request = Requests::<T>::take(metadata_id)?;
+			let remaining = T::Currency::unreserve(&request.requester, request.reward);
+			Some(request.reward.saturating_sub(remaining))
+		}
+
+		/// Pay the reward of `request` out to `winners`, in proportion to their weights. The last
+		/// winner also gets what rounding leaves over.
+		fn pay_out(request: &MetadataRequestOf<T>, winners: &[(T::AccountId, u128)]) {
+			let total =
+				winners.iter().fold(0u128, |total, (_, weight)| total.saturating_add(*weight));
+			if total == 0 {
+				T::Currency::unreserve(&request.requester, request.reward);
+				return
+			}
+			let mut left = request.reward;
+			for (index, (winner, weight)) in winners.iter().enumerate() {
+				let amount = if index + 1 == winners.len() {
+					left
+				} else {
+					Perbill::from_rational(*weight, total) * request.reward
+				};
+				let amount = amount.min(left);
+				match T::Currency::repatriate_reserved(
+					&request.requester,
+					winner,
+					amount,
+					BalanceStatus::Free,
+				) {
+					Ok(missing) => left = left.saturating_sub(amount.saturating_sub(missing)),
+					Err(error) => log::warn!(
+						target: "runtime::metadata-requests",
+						"Could not pay {:?} out to {:?}: {:?}",
+						amount,
+						winner,
+						error
+					),
+				}
+			}
+		}
+	}
+
+	impl<T: Config> MetadataRequests<T::AccountId> for Pallet<T> {
+		fn is_open(metadata_id: MetadataId) -> bool {
+			OpenRequests::<T>::get().iter().any(|(id, _)| *id == metadata_id)
+		}
+
+		fn open_requests() -> Vec<MetadataId> {
+			OpenRequests::<T>::get().into_iter().map(|(metadata_id, _)| metadata_id).collect()
+		}
+
+		fn on_settled(metadata_id: MetadataId, winners: &[(T::AccountId, u128)]) {
+			if !Self::close(metadata_id) {
+				return
+			}
+			if let Some(request) = Requests::<T>::take(metadata_id) {
+				Self::pay_out(&request, winners);
+				Self::deposit_event(Event::RequestFulfilled { metadata_id });
+			}
+		}
+
+		fn reply_to(metadata_id: MetadataId) -> Option<MultiLocation> {
+			Requests::<T>::get(metadata_id).and_then(|request| request.reply_to)
+		}
+
+		#[cfg(feature = "runtime-benchmarks")]
+		fn open(metadata_id: MetadataId) {
+			use sp_runtime::traits::Bounded;
+
+			let requester: T::AccountId = frame_benchmarking::account("requester", 0, 0);
+			let reward = T::Currency::minimum_balance().saturating_mul(1_000u32.into());
+			let funds = BalanceOf::<T>::max_value() / 2u32.into();
+			T::Currency::make_free_balance_be(&requester, funds);
+			T::Currency::reserve(&requester, reward).expect("the requester was just funded; qed");
+			let deadline = T::BlockNumber::max_value();
+			OpenRequests::<T>::mutate(|open| {
+				open.retain(|(id, _)| *id != metadata_id);
+				let _ = open.try_push((metadata_id, deadline));
+			});
+			Requests::<T>::insert(
+				metadata_id,
+				MetadataRequest {
+					id: metadata_id,
+					requester,
+					source: Vec::new(),
+					deadline,
+					reward,
+					reply_to: None,
+				},
+			);
+		}
+	}
+}
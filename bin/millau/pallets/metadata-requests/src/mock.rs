@@ -0,0 +1,153 @@
+use crate as pallet_metadata_requests;
+
+use frame_support::{
+	pallet_prelude::ConstU32,
+	parameter_types,
+	traits::{ConstU128, ConstU16, ConstU64, EnsureOrigin},
+};
+use frame_system::{self as system, ensure_signed, EnsureRoot, EnsureSigned};
+use pallet_balances::AccountData;
+
+use sp_core::{crypto::AccountId32, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+use xcm::latest::prelude::*;
+use xcm_executor::traits::ConvertLocation;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		Balances: pallet_balances,
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		MetadataRequests: pallet_metadata_requests,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = AccountData<u128>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	/// The type for recording an account's balance.
+	type Balance = u128;
+	type DustRemoval = ();
+	/// The ubiquitous event type.
+	type RuntimeEvent = RuntimeEvent;
+	type ExistentialDeposit = ConstU128<0>;
+	type AccountStore = System;
+	type WeightInfo = pallet_balances::weights::SubstrateWeight<Test>;
+	type MaxLocks = ConstU32<50>;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+}
+
+parameter_types! {
+	pub const MaxOpenRequests: u32 = 3;
+	pub const MaxSourceLength: u32 = 32;
+	pub const MinimumLifetime: u64 = 10;
+}
+
+/// Signing as this account stands for a request sent over XCM by `remote_location()`.
+pub const REMOTE_ORIGIN: u8 = 42;
+/// Signing as this account stands for a request sent over XCM by a location without an account
+/// on this chain.
+pub const UNKNOWN_REMOTE_ORIGIN: u8 = 43;
+/// The account of `remote_location()` on this chain.
+pub const REMOTE_SOVEREIGN: u8 = 7;
+
+pub fn remote_location() -> MultiLocation {
+	MultiLocation::new(1, X2(GlobalConsensus(NetworkId::Rococo), PalletInstance(42)))
+}
+
+pub struct EnsureRemoteLocation;
+impl EnsureOrigin<RuntimeOrigin> for EnsureRemoteLocation {
+	type Success = MultiLocation;
+
+	fn try_origin(o: RuntimeOrigin) -> Result<MultiLocation, RuntimeOrigin> {
+		match ensure_signed(o.clone()) {
+			Ok(who) if who == account(REMOTE_ORIGIN) => Ok(remote_location()),
+			Ok(who) if who == account(UNKNOWN_REMOTE_ORIGIN) =>
+				Ok(MultiLocation::new(1, X1(GlobalConsensus(NetworkId::Kusama)))),
+			_ => Err(o),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<RuntimeOrigin, ()> {
+		Ok(RuntimeOrigin::signed(account(REMOTE_ORIGIN)))
+	}
+}
+
+pub struct RemoteSovereignAccount;
+impl ConvertLocation<AccountId32> for RemoteSovereignAccount {
+	fn convert_location(location: &MultiLocation) -> Option<AccountId32> {
+		(*location == remote_location()).then(|| account(REMOTE_SOVEREIGN))
+	}
+}
+
+impl pallet_metadata_requests::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type RequestOrigin = EnsureSigned<AccountId32>;
+	type CrossChainOrigin = EnsureRemoteLocation;
+	type SovereignAccountOf = RemoteSovereignAccount;
+	type ForceOrigin = EnsureRoot<AccountId32>;
+	type MaxOpenRequests = MaxOpenRequests;
+	type MaxSourceLength = MaxSourceLength;
+	type MinimumLifetime = MinimumLifetime;
+	type WeightInfo = ();
+}
+
+pub const INITIAL_BALANCE: u128 = 10_000;
+
+pub fn account(id: u8) -> AccountId32 {
+	AccountId32::new([id; 32])
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: (0_u8..8_u8).map(|id| (account(id), INITIAL_BALANCE)).collect(),
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+	});
+	ext
+}
@@ -0,0 +1,237 @@
+use crate::{
+	mock::*, Error, Event, MetadataRequest, MetadataRequests as _, OpenRequests, Requests,
+};
+
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchError, traits::Hooks};
+
+const REWARD: u128 = 1_000;
+
+fn request(id: u8, deadline: u64) {
+	assert_ok!(MetadataRequests::request(
+		RuntimeOrigin::signed(account(id)),
+		b"http://localhost:8000/metadatas".to_vec(),
+		deadline,
+		REWARD,
+	));
+}
+
+#[test]
+fn request_escrows_reward_and_hands_out_consecutive_ids() {
+	new_test_ext().execute_with(|| {
+		let deadline = System::block_number() + MinimumLifetime::get();
+		request(1, deadline);
+		request(2, deadline + 1);
+
+		assert_eq!(
+			MetadataRequests::get_request(0),
+			Some(MetadataRequest {
+				id: 0,
+				requester: account(1),
+				source: b"http://localhost:8000/metadatas".to_vec(),
+				deadline,
+				reward: REWARD,
+				reply_to: None,
+			})
+		);
+		assert_eq!(MetadataRequests::get_request(1).unwrap().requester, account(2));
+		assert_eq!(MetadataRequests::next_metadata_id(), 2);
+		assert_eq!(MetadataRequests::open_requests(), vec![0, 1]);
+		assert!(MetadataRequests::is_open(1));
+		assert!(!MetadataRequests::is_open(2));
+		assert_eq!(Balances::reserved_balance(account(1)), REWARD);
+		System::assert_last_event(
+			Event::<Test>::Requested {
+				metadata_id: 1,
+				requester: account(2),
+				deadline: deadline + 1,
+				reward: REWARD,
+			}
+			.into(),
+		);
+	})
+}
+
+#[test]
+fn request_rejects_invalid_requests() {
+	new_test_ext().execute_with(|| {
+		let deadline = System::block_number() + MinimumLifetime::get();
+		assert_noop!(
+			MetadataRequests::request(RuntimeOrigin::none(), Vec::new(), deadline, REWARD),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			MetadataRequests::request(
+				RuntimeOrigin::signed(account(1)),
+				vec![0; MaxSourceLength::get() as usize + 1],
+				deadline,
+				REWARD
+			),
+			Error::<Test>::SourceTooLong
+		);
+		assert_noop!(
+			MetadataRequests::request(
+				RuntimeOrigin::signed(account(1)),
+				Vec::new(),
+				deadline - 1,
+				REWARD
+			),
+			Error::<Test>::DeadlineTooSoon
+		);
+		assert_noop!(
+			MetadataRequests::request(
+				RuntimeOrigin::signed(account(1)),
+				Vec::new(),
+				deadline,
+				INITIAL_BALANCE + 1
+			),
+			Error::<Test>::InsufficientBalance
+		);
+
+		for id in 0..MaxOpenRequests::get() as u8 {
+			request(id, deadline);
+		}
+		assert_noop!(
+			MetadataRequests::request(
+				RuntimeOrigin::signed(account(1)),
+				Vec::new(),
+				deadline,
+				REWARD
+			),
+			Error::<Test>::TooManyOpenRequests
+		);
+	})
+}
+
+#[test]
+fn settled_requests_pay_the_winners_out() {
+	new_test_ext().execute_with(|| {
+		let deadline = System::block_number() + MinimumLifetime::get();
+		request(1, deadline);
+		request(1, deadline);
+
+		// Rewards are split in proportion to the weights
+		MetadataRequests::on_settled(0, &[(account(2), 1), (account(3), 3)]);
+		assert_eq!(Balances::free_balance(account(2)), INITIAL_BALANCE + REWARD / 4);
+		assert_eq!(Balances::free_balance(account(3)), INITIAL_BALANCE + REWARD * 3 / 4);
+		assert_eq!(MetadataRequests::get_request(0), None);
+		assert!(!MetadataRequests::is_open(0));
+		System::assert_last_event(Event::<Test>::RequestFulfilled { metadata_id: 0 }.into());
+
+		// What the split leaves over goes to the last winner
+		MetadataRequests::on_settled(1, &[(account(4), 1), (account(5), 1), (account(6), 1)]);
+		assert_eq!(Balances::free_balance(account(4)), INITIAL_BALANCE + 333);
+		assert_eq!(Balances::free_balance(account(5)), INITIAL_BALANCE + 333);
+		assert_eq!(Balances::free_balance(account(6)), INITIAL_BALANCE + 334);
+
+		assert_eq!(Balances::reserved_balance(account(1)), 0);
+		assert_eq!(Balances::free_balance(account(1)), INITIAL_BALANCE - 2 * REWARD);
+
+		// Closed requests are not paid out twice
+		MetadataRequests::on_settled(1, &[(account(4), 1)]);
+		assert_eq!(Balances::free_balance(account(4)), INITIAL_BALANCE + 333);
+	})
+}
+
+#[test]
+fn expired_requests_are_refunded() {
+	new_test_ext().execute_with(|| {
+		let deadline = System::block_number() + MinimumLifetime::get();
+		request(1, deadline);
+		request(2, deadline + 1);
+
+		System::set_block_number(deadline);
+		MetadataRequests::on_initialize(deadline);
+
+		assert_eq!(OpenRequests::<Test>::get().into_inner(), vec![(1, deadline + 1)]);
+		assert!(!Requests::<Test>::contains_key(0));
+		assert_eq!(Balances::reserved_balance(account(1)), 0);
+		assert_eq!(Balances::free_balance(account(1)), INITIAL_BALANCE);
+		assert_eq!(Balances::reserved_balance(account(2)), REWARD);
+		System::assert_last_event(
+			Event::<Test>::RequestExpired { metadata_id: 0, refund: REWARD }.into(),
+		);
+
+		// Settling the round afterwards pays nothing out
+		MetadataRequests::on_settled(0, &[(account(3), 1)]);
+		assert_eq!(Balances::free_balance(account(3)), INITIAL_BALANCE);
+	})
+}
+
+#[test]
+fn cancel_request_refunds_the_requester() {
+	new_test_ext().execute_with(|| {
+		request(1, System::block_number() + MinimumLifetime::get());
+
+		assert_noop!(
+			MetadataRequests::cancel_request(RuntimeOrigin::signed(account(1)), 0),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(MetadataRequests::cancel_request(RuntimeOrigin::root(), 0));
+		assert!(MetadataRequests::open_requests().is_empty());
+		assert_eq!(Balances::free_balance(account(1)), INITIAL_BALANCE);
+		System::assert_last_event(
+			Event::<Test>::RequestCancelled { metadata_id: 0, refund: REWARD }.into(),
+		);
+
+		assert_noop!(
+			MetadataRequests::cancel_request(RuntimeOrigin::root(), 0),
+			Error::<Test>::UnknownRequest
+		);
+	})
+}
+
+#[test]
+fn other_chains_request_through_their_sovereign_account() {
+	new_test_ext().execute_with(|| {
+		let lifetime = MinimumLifetime::get();
+		assert_noop!(
+			MetadataRequests::request_from_location(
+				RuntimeOrigin::signed(account(1)),
+				Vec::new(),
+				lifetime,
+				REWARD
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			MetadataRequests::request_from_location(
+				RuntimeOrigin::signed(account(UNKNOWN_REMOTE_ORIGIN)),
+				Vec::new(),
+				lifetime,
+				REWARD
+			),
+			Error::<Test>::NoSovereignAccount
+		);
+		assert_noop!(
+			MetadataRequests::request_from_location(
+				RuntimeOrigin::signed(account(REMOTE_ORIGIN)),
+				Vec::new(),
+				lifetime - 1,
+				REWARD
+			),
+			Error::<Test>::DeadlineTooSoon
+		);
+
+		assert_ok!(MetadataRequests::request_from_location(
+			RuntimeOrigin::signed(account(REMOTE_ORIGIN)),
+			b"http://localhost:8000/metadatas".to_vec(),
+			lifetime,
+			REWARD,
+		));
+		let request = MetadataRequests::get_request(0).unwrap();
+		assert_eq!(request.requester, account(REMOTE_SOVEREIGN));
+		assert_eq!(request.deadline, System::block_number() + lifetime);
+		assert_eq!(MetadataRequests::reply_to(0), Some(remote_location()));
+		assert_eq!(Balances::reserved_balance(account(REMOTE_SOVEREIGN)), REWARD);
+
+		// Requests made on this chain have nowhere to be sent back to
+		request(1, System::block_number() + lifetime);
+		assert_eq!(MetadataRequests::reply_to(1), None);
+
+		// The sovereign account pays out like any other requester
+		MetadataRequests::on_settled(0, &[(account(2), 1)]);
+		assert_eq!(Balances::free_balance(account(2)), INITIAL_BALANCE + REWARD);
+		assert_eq!(Balances::reserved_balance(account(REMOTE_SOVEREIGN)), 0);
+		assert_eq!(MetadataRequests::reply_to(0), None);
+	})
+}
@@ -0,0 +1,109 @@
+//! Weights for `pallet_metadata_requests`
+//!
+//! Estimated from the storage accesses of each call until the numbers are regenerated with the
+//! benchmark CLI (see `pallet_logic_provider`'s `benchmark.sh`, with
+//! `--pallet pallet_metadata_requests`).
+
+// #![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+pub trait WeightInfo {
+	fn request() -> Weight;
+	fn cancel_request() -> Weight;
+	/// the `e` parameter stands for the number of requests expiring in the block
+	fn on_initialize(e: u32) -> Weight;
+	fn request_from_location() -> Weight;
+}
+
+/// Weight functions for `metadata_requests`.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests NextMetadataId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:0 w:1)
+	fn request() -> Weight {
+		Weight::from_parts(32_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn cancel_request() -> Weight {
+		Weight::from_parts(27_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:e w:e)
+	// Storage: System Account (r:e w:e)
+	fn on_initialize(e: u32) -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			// Standard Error: 9_000
+			.saturating_add(Weight::from_parts(21_000_000, 0).saturating_mul(e as u64))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((2u64).saturating_mul(e as u64)))
+			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().writes((2u64).saturating_mul(e as u64)))
+	}
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests NextMetadataId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:0 w:1)
+	fn request_from_location() -> Weight {
+		Weight::from_parts(36_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests NextMetadataId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:0 w:1)
+	fn request() -> Weight {
+		Weight::from_parts(32_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn cancel_request() -> Weight {
+		Weight::from_parts(27_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:e w:e)
+	// Storage: System Account (r:e w:e)
+	fn on_initialize(e: u32) -> Weight {
+		Weight::from_parts(4_000_000, 0)
+			// Standard Error: 9_000
+			.saturating_add(Weight::from_parts(21_000_000, 0).saturating_mul(e as u64))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().reads((2u64).saturating_mul(e as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1))
+			.saturating_add(RocksDbWeight::get().writes((2u64).saturating_mul(e as u64)))
+	}
+	// Storage: MetadataRequests OpenRequests (r:1 w:1)
+	// Storage: MetadataRequests NextMetadataId (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: MetadataRequests Requests (r:0 w:1)
+	fn request_from_location() -> Weight {
+		Weight::from_parts(36_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+}
@@ -0,0 +1,107 @@
+//! Benchmarking setup for pallet-participant-registry
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Registry;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::traits::{Currency, Get};
+use frame_system::RawOrigin;
+use primitives::shared::{registration_payload, Public, Signature};
+use sp_runtime::{
+	traits::{Bounded, Saturating},
+	RuntimeAppPublic,
+};
+
+fn funded_caller<T: Config>() -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	caller
+}
+
+fn fill_registry<T: Config>() {
+	// Nothing iterates over the registry, so only the membership limit check depends on its size.
+	// The registry is still filled for real, so that `ParticipantCount` matches its contents.
+	for index in 0..T::MaxRegisteredParticipants::get().saturating_sub(1) {
+		<Registry<T> as ParticipantRegistry<Public>>::activate(&other_public(index));
+	}
+	assert_eq!(
+		ParticipantCount::<T>::get(),
+		T::MaxRegisteredParticipants::get().saturating_sub(1)
+	);
+}
+
+// A key held in the keystore, so that it can sign the proof of its ownership
+fn signing_key() -> Public {
+	Public::generate_pair(None)
+}
+
+fn key_proof<T: Config>(public: &Public, operator: &T::AccountId) -> Signature {
+	public
+		.sign(&registration_payload(operator))
+		.expect("the key was generated in the keystore; qed")
+}
+
+// Keys of the other participants, which never sign anything
+fn other_public(index: u32) -> Public {
+	let mut raw = [u8::MAX; 32];
+	raw[..4].copy_from_slice(&index.to_le_bytes());
+	raw[4] = 0;
+	sp_core::sr25519::Public::from_raw(raw).into()
+}
+
+fn registered_caller<T: Config>() -> (T::AccountId, Public) {
+	let caller = funded_caller::<T>();
+	let public = signing_key();
+	Registry::<T>::register(
+		RawOrigin::Signed(caller.clone()).into(),
+		public.clone(),
+		key_proof::<T>(&public, &caller),
+		T::MinimumBond::get(),
+	)
+	.unwrap();
+	(caller, public)
+}
+
+benchmarks! {
+	register {
+		fill_registry::<T>();
+		let caller = funded_caller::<T>();
+		let public = signing_key();
+		let signature = key_proof::<T>(&public, &caller);
+		let bond = T::MinimumBond::get();
+	}: _(RawOrigin::Signed(caller.clone()), public, signature, bond)
+	verify {
+		assert!(Participants::<T>::contains_key(&caller));
+		assert_eq!(ParticipantCount::<T>::get(), T::MaxRegisteredParticipants::get());
+	}
+
+	bond_extra {
+		let (caller, _) = registered_caller::<T>();
+		let amount = T::MinimumBond::get();
+	}: _(RawOrigin::Signed(caller.clone()), amount)
+	verify {
+		assert_eq!(
+			Participants::<T>::get(&caller).unwrap().bond,
+			T::MinimumBond::get().saturating_add(amount)
+		);
+	}
+
+	unbond {
+		let (caller, public) = registered_caller::<T>();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!<Registry<T> as ParticipantRegistry<Public>>::is_active(&public));
+	}
+
+	withdraw_unbonded {
+		let (caller, _) = registered_caller::<T>();
+		Registry::<T>::unbond(RawOrigin::Signed(caller.clone()).into()).unwrap();
+		let unlocks_at = <frame_system::Pallet<T>>::block_number()
+			.saturating_add(T::UnbondingPeriod::get());
+		<frame_system::Pallet<T>>::set_block_number(unlocks_at);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!Participants::<T>::contains_key(&caller));
+	}
+}
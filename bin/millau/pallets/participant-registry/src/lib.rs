@@ -0,0 +1,292 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Registry of the operators that are allowed to run the offchain plugin and take part in
+/// logic-provider rounds. Operators bond funds and declare the `Public` key their plugin signs
+/// with, proving they control it; the key stays active until the operator starts unbonding.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::*;
+
+/// Answers whether a key may currently take part in logic-provider rounds.
+pub trait ParticipantRegistry<Key> {
+	fn is_active(key: &Key) -> bool;
+
+	/// Make `key` an active participant, so that benchmarks can exercise the gated calls.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn activate(_key: &Key) {}
+}
+
+/// Open participation: every key is considered active.
+impl<Key> ParticipantRegistry<Key> for () {
+	fn is_active(_key: &Key) -> bool {
+		true
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		dispatch::DispatchResult,
+		fail,
+		pallet_prelude::*,
+		traits::{Currency, ReservableCurrency},
+	};
+	use frame_system::pallet_prelude::*;
+	use primitives::shared::{registration_payload, Public, Signature};
+	use sp_runtime::{
+		traits::{Saturating, Zero},
+		RuntimeAppPublic,
+	};
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Currency the bonds are reserved in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Minimum amount an operator has to bond in order to register.
+		#[pallet::constant]
+		type MinimumBond: Get<BalanceOf<Self>>;
+		/// Number of blocks between `unbond` and the moment the bond can be withdrawn.
+		#[pallet::constant]
+		type UnbondingPeriod: Get<Self::BlockNumber>;
+		/// Maximum number of operators registered at the same time, unbonding ones included.
+		#[pallet::constant]
+		type MaxRegisteredParticipants: Get<u32>;
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	pub enum ParticipantStatus<BlockNumber> {
+		/// The participant may commit to logic-provider rounds.
+		Active,
+		/// The participant is leaving; the bond can be withdrawn from `unlocks_at` on.
+		Unbonding { unlocks_at: BlockNumber },
+	}
+
+	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	pub struct ParticipantInfo<Balance, BlockNumber> {
+		/// Key the operator's offchain plugin signs its submissions with.
+		pub public: Public,
+		/// Amount currently reserved from the operator's account.
+		pub bond: Balance,
+		pub status: ParticipantStatus<BlockNumber>,
+	}
+
+	pub type ParticipantInfoOf<T> =
+		ParticipantInfo<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_participant)]
+	pub type Participants<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, ParticipantInfoOf<T>, OptionQuery>;
+
+	/// Reverse lookup from a declared key to the operator that registered it.
+	#[pallet::storage]
+	#[pallet::getter(fn get_key_owner)]
+	pub type KeyOwners<T: Config> =
+		StorageMap<_, Blake2_128Concat, Public, T::AccountId, OptionQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn participant_count)]
+	pub type ParticipantCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An operator registered `public` and bonded `bond`.
+		Registered { who: T::AccountId, public: Public, bond: BalanceOf<T> },
+		/// An operator increased its bond by `amount`.
+		Bonded { who: T::AccountId, amount: BalanceOf<T> },
+		/// An operator left the active set; its bond is released at `unlocks_at`.
+		Unbonding { who: T::AccountId, unlocks_at: T::BlockNumber },
+		/// An operator withdrew its bond and was removed from the registry.
+		Withdrawn { who: T::AccountId, amount: BalanceOf<T> },
+	}
+
+	#[pallet::error]
+	#[derive(PartialEq)]
+	pub enum Error<T> {
+		/// The account already registered an operator key.
+		AlreadyRegistered,
+		/// The key was already declared by another operator.
+		KeyAlreadyInUse,
+		/// The signature does not prove that the caller controls the declared key.
+		InvalidKeyProof,
+		/// The bond is below `MinimumBond`.
+		BondTooLow,
+		/// `MaxRegisteredParticipants` has been reached.
+		TooManyParticipants,
+		/// The account does not have enough free balance to reserve the bond.
+		InsufficientBalance,
+		/// The account is not registered.
+		NotRegistered,
+		/// The operator already started unbonding.
+		AlreadyUnbonding,
+		/// The operator did not call `unbond` yet.
+		NotUnbonding,
+		/// The unbonding period is not over yet.
+		UnbondingPeriodNotOver,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register the caller as an operator signing with `public`, reserving `bond`.
+		/// `signature` is the signature of `public` over
+		/// [`registration_payload`](primitives::shared::registration_payload) of the caller,
+		/// proving that the caller controls the key.
+		///
+		/// # Errors
+		/// Returns `AlreadyRegistered` or `KeyAlreadyInUse` if either the account or the key are
+		/// already known, `InvalidKeyProof` if `signature` is not valid, `BondTooLow` if `bond`
+		/// is below `MinimumBond` and `TooManyParticipants` if the registry is full.
+		#[pallet::weight(<T as Config>::WeightInfo::register())]
+		pub fn register(
+			origin: OriginFor<T>,
+			public: Public,
+			signature: Signature,
+			bond: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Participants::<T>::contains_key(&who), Error::<T>::AlreadyRegistered);
+			ensure!(!KeyOwners::<T>::contains_key(&public), Error::<T>::KeyAlreadyInUse);
+			ensure!(
+				public.verify(&registration_payload(&who), &signature),
+				Error::<T>::InvalidKeyProof
+			);
+			ensure!(bond >= T::MinimumBond::get(), Error::<T>::BondTooLow);
+			let count = ParticipantCount::<T>::get();
+			ensure!(count < T::MaxRegisteredParticipants::get(), Error::<T>::TooManyParticipants);
+
+			T::Currency::reserve(&who, bond).map_err(|_| Error::<T>::InsufficientBalance)?;
+
+			Participants::<T>::insert(
+				&who,
+				ParticipantInfo { public: public.clone(), bond, status: ParticipantStatus::Active },
+			);
+			KeyOwners::<T>::insert(&public, &who);
+			ParticipantCount::<T>::put(count + 1);
+
+			Self::deposit_event(Event::Registered { who, public, bond });
+			Ok(())
+		}
+
+		/// Reserve `amount` on top of the caller's current bond.
+		#[pallet::weight(<T as Config>::WeightInfo::bond_extra())]
+		pub fn bond_extra(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Participants::<T>::try_mutate(&who, |maybe_info| -> DispatchResult {
+				let info = maybe_info.as_mut().ok_or(Error::<T>::NotRegistered)?;
+				ensure!(info.status == ParticipantStatus::Active, Error::<T>::AlreadyUnbonding);
+				T::Currency::reserve(&who, amount).map_err(|_| Error::<T>::InsufficientBalance)?;
+				info.bond = info.bond.saturating_add(amount);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Bonded { who, amount });
+			Ok(())
+		}
+
+		/// Leave the active set. The caller's key is no longer accepted by the logic provider and
+		/// the bond can be withdrawn once `UnbondingPeriod` blocks have passed.
+		#[pallet::weight(<T as Config>::WeightInfo::unbond())]
+		pub fn unbond(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let unlocks_at =
+				<frame_system::Pallet<T>>::block_number().saturating_add(T::UnbondingPeriod::get());
+			Participants::<T>::try_mutate(&who, |maybe_info| -> DispatchResult {
+				let info = maybe_info.as_mut().ok_or(Error::<T>::NotRegistered)?;
+				ensure!(info.status == ParticipantStatus::Active, Error::<T>::AlreadyUnbonding);
+				info.status = ParticipantStatus::Unbonding { unlocks_at };
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Unbonding { who, unlocks_at });
+			Ok(())
+		}
+
+		/// Release the caller's bond and remove it from the registry once the unbonding period
+		/// is over.
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw_unbonded())]
+		pub fn withdraw_unbonded(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let info = Participants::<T>::get(&who).ok_or(Error::<T>::NotRegistered)?;
+			match info.status {
+				ParticipantStatus::Unbonding { unlocks_at } => ensure!(
+					<frame_system::Pallet<T>>::block_number() >= unlocks_at,
+					Error::<T>::UnbondingPeriodNotOver
+				),
+				ParticipantStatus::Active => fail!(Error::<T>::NotUnbonding),
+			}
+
+			let remaining = T::Currency::unreserve(&who, info.bond);
+			if !remaining.is_zero() {
+				log::warn!(
+					target: "runtime::participant-registry",
+					"Could not unreserve {:?} of the bond of {:?}",
+					remaining,
+					who
+				);
+			}
+			Participants::<T>::remove(&who);
+			KeyOwners::<T>::remove(&info.public);
+			ParticipantCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+
+			Self::deposit_event(Event::Withdrawn { who, amount: info.bond });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The operator owning `public`, if that operator is active.
+		pub fn active_owner(public: &Public) -> Option<T::AccountId> {
+			let who = KeyOwners::<T>::get(public)?;
+			let info = Participants::<T>::get(&who)?;
+			(info.status == ParticipantStatus::Active).then_some(who)
+		}
+	}
+
+	impl<T: Config> ParticipantRegistry<Public> for Pallet<T> {
+		fn is_active(key: &Public) -> bool {
+			Self::active_owner(key).is_some()
+		}
+
+		#[cfg(feature = "runtime-benchmarks")]
+		fn activate(key: &Public) {
+			use sp_runtime::traits::TrailingZeroInput;
+
+			let who = T::AccountId::decode(&mut TrailingZeroInput::new(&key.encode()[..]))
+				.expect("infinite length input; no invalid inputs for type; qed");
+			if !Participants::<T>::contains_key(&who) {
+				ParticipantCount::<T>::mutate(|count| *count = count.saturating_add(1));
+			}
+			Participants::<T>::insert(
+				&who,
+				ParticipantInfo {
+					public: key.clone(),
+					bond: Zero::zero(),
+					status: ParticipantStatus::Active,
+				},
+			);
+			KeyOwners::<T>::insert(key, who);
+		}
+	}
+}
@@ -0,0 +1,123 @@
+use crate as pallet_participant_registry;
+
+use frame_support::{
+	pallet_prelude::ConstU32,
+	parameter_types,
+	traits::{ConstU128, ConstU16, ConstU64},
+};
+use frame_system as system;
+use pallet_balances::AccountData;
+
+use primitives::shared::{registration_payload, Pair, Public, Signature};
+use sp_core::{crypto::AccountId32, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		Balances: pallet_balances,
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		ParticipantRegistry: pallet_participant_registry,
+	}
+);
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = AccountData<u128>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+	/// The type for recording an account's balance.
+	type Balance = u128;
+	type DustRemoval = ();
+	/// The ubiquitous event type.
+	type RuntimeEvent = RuntimeEvent;
+	type ExistentialDeposit = ConstU128<0>;
+	type AccountStore = System;
+	type WeightInfo = pallet_balances::weights::SubstrateWeight<Test>;
+	type MaxLocks = ConstU32<50>;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+}
+
+parameter_types! {
+	pub const MinimumBond: u128 = 1_000;
+	pub const UnbondingPeriod: u64 = 10;
+	pub const MaxRegisteredParticipants: u32 = 3;
+}
+
+impl pallet_participant_registry::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type MinimumBond = MinimumBond;
+	type UnbondingPeriod = UnbondingPeriod;
+	type MaxRegisteredParticipants = MaxRegisteredParticipants;
+	type WeightInfo = ();
+}
+
+pub const INITIAL_BALANCE: u128 = 10_000;
+
+pub fn account(id: u8) -> AccountId32 {
+	AccountId32::new([id; 32])
+}
+
+pub fn pair(id: u8) -> Pair {
+	<Pair as sp_core::Pair>::from_seed(&[id; 32])
+}
+
+pub fn public(id: u8) -> Public {
+	sp_core::Pair::public(&pair(id))
+}
+
+/// The signature of the key of `signer` proving that `operator` controls it.
+pub fn key_proof(signer: u8, operator: u8) -> Signature {
+	sp_core::Pair::sign(&pair(signer), &registration_payload(&account(operator)))
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: (0_u8..8_u8).map(|id| (account(id), INITIAL_BALANCE)).collect(),
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+	});
+	ext
+}
@@ -0,0 +1,217 @@
+use crate::{
+	mock::*, Error, Event, ParticipantCount, ParticipantInfo, ParticipantRegistry as _,
+	ParticipantStatus, Participants,
+};
+
+use frame_support::{assert_noop, assert_ok};
+
+fn register(id: u8, bond: u128) {
+	assert_ok!(ParticipantRegistry::register(
+		RuntimeOrigin::signed(account(id)),
+		public(id),
+		key_proof(id, id),
+		key_proof(id, id),
+		bond
+	));
+}
+
+#[test]
+fn register_reserves_bond_and_activates_key() {
+	new_test_ext().execute_with(|| {
+		register(1, MinimumBond::get());
+
+		assert_eq!(
+			ParticipantRegistry::get_participant(account(1)),
+			Some(ParticipantInfo {
+				public: public(1),
+				bond: MinimumBond::get(),
+				status: ParticipantStatus::Active,
+			})
+		);
+		assert_eq!(ParticipantRegistry::get_key_owner(public(1)), Some(account(1)));
+		assert_eq!(Balances::reserved_balance(account(1)), MinimumBond::get());
+		assert_eq!(ParticipantCount::<Test>::get(), 1);
+		assert!(ParticipantRegistry::is_active(&public(1)));
+		assert!(!ParticipantRegistry::is_active(&public(2)));
+		System::assert_last_event(
+			Event::<Test>::Registered {
+				who: account(1),
+				public: public(1),
+				bond: MinimumBond::get(),
+			}
+			.into(),
+		);
+	})
+}
+
+#[test]
+fn register_rejects_invalid_requests() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ParticipantRegistry::register(
+				RuntimeOrigin::signed(account(1)),
+				public(1),
+				key_proof(1, 1),
+				MinimumBond::get() - 1
+			),
+			Error::<Test>::BondTooLow
+		);
+		assert_noop!(
+			ParticipantRegistry::register(
+				RuntimeOrigin::signed(account(1)),
+				public(1),
+				key_proof(1, 1),
+				INITIAL_BALANCE + 1
+			),
+			Error::<Test>::InsufficientBalance
+		);
+
+		register(1, MinimumBond::get());
+		assert_noop!(
+			ParticipantRegistry::register(
+				RuntimeOrigin::signed(account(1)),
+				public(2),
+				key_proof(2, 1),
+				MinimumBond::get()
+			),
+			Error::<Test>::AlreadyRegistered
+		);
+		assert_noop!(
+			ParticipantRegistry::register(
+				RuntimeOrigin::signed(account(2)),
+				public(1),
+				key_proof(1, 2),
+				MinimumBond::get()
+			),
+			Error::<Test>::KeyAlreadyInUse
+		);
+	})
+}
+
+#[test]
+fn register_requires_proof_of_key_ownership() {
+	new_test_ext().execute_with(|| {
+		// Signed by another key than the declared one
+		assert_noop!(
+			ParticipantRegistry::register(
+				RuntimeOrigin::signed(account(2)),
+				public(1),
+				key_proof(2, 2),
+				MinimumBond::get()
+			),
+			Error::<Test>::InvalidKeyProof
+		);
+		// A proof made for another operator can't be replayed
+		assert_noop!(
+			ParticipantRegistry::register(
+				RuntimeOrigin::signed(account(2)),
+				public(1),
+				key_proof(1, 1),
+				MinimumBond::get()
+			),
+			Error::<Test>::InvalidKeyProof
+		);
+
+		// Once registered, the key can't be claimed again, not even with a valid proof
+		register(1, MinimumBond::get());
+		assert_noop!(
+			ParticipantRegistry::register(
+				RuntimeOrigin::signed(account(2)),
+				public(1),
+				key_proof(1, 2),
+				MinimumBond::get()
+			),
+			Error::<Test>::KeyAlreadyInUse
+		);
+		assert_eq!(ParticipantRegistry::get_key_owner(public(1)), Some(account(1)));
+	})
+}
+
+#[test]
+fn register_respects_membership_limit() {
+	new_test_ext().execute_with(|| {
+		for id in 0..MaxRegisteredParticipants::get() as u8 {
+			register(id, MinimumBond::get());
+		}
+		let next = MaxRegisteredParticipants::get() as u8;
+		assert_noop!(
+			ParticipantRegistry::register(
+				RuntimeOrigin::signed(account(next)),
+				public(next),
+				key_proof(next, next),
+				MinimumBond::get()
+			),
+			Error::<Test>::TooManyParticipants
+		);
+
+		// Unbonding participants still count until they withdraw.
+		assert_ok!(ParticipantRegistry::unbond(RuntimeOrigin::signed(account(0))));
+		assert_noop!(
+			ParticipantRegistry::register(
+				RuntimeOrigin::signed(account(next)),
+				public(next),
+				key_proof(next, next),
+				MinimumBond::get()
+			),
+			Error::<Test>::TooManyParticipants
+		);
+
+		System::set_block_number(1 + UnbondingPeriod::get());
+		assert_ok!(ParticipantRegistry::withdraw_unbonded(RuntimeOrigin::signed(account(0))));
+		register(next, MinimumBond::get());
+	})
+}
+
+#[test]
+fn bond_extra_increases_bond() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ParticipantRegistry::bond_extra(RuntimeOrigin::signed(account(1)), 1),
+			Error::<Test>::NotRegistered
+		);
+
+		register(1, MinimumBond::get());
+		assert_ok!(ParticipantRegistry::bond_extra(RuntimeOrigin::signed(account(1)), 500));
+
+		assert_eq!(Participants::<Test>::get(account(1)).unwrap().bond, MinimumBond::get() + 500);
+		assert_eq!(Balances::reserved_balance(account(1)), MinimumBond::get() + 500);
+	})
+}
+
+#[test]
+fn unbonding_deactivates_key_and_releases_bond_after_period() {
+	new_test_ext().execute_with(|| {
+		register(1, MinimumBond::get());
+		assert_noop!(
+			ParticipantRegistry::withdraw_unbonded(RuntimeOrigin::signed(account(1))),
+			Error::<Test>::NotUnbonding
+		);
+
+		assert_ok!(ParticipantRegistry::unbond(RuntimeOrigin::signed(account(1))));
+		let unlocks_at = 1 + UnbondingPeriod::get();
+		System::assert_last_event(Event::<Test>::Unbonding { who: account(1), unlocks_at }.into());
+		assert!(!ParticipantRegistry::is_active(&public(1)));
+		assert_noop!(
+			ParticipantRegistry::unbond(RuntimeOrigin::signed(account(1))),
+			Error::<Test>::AlreadyUnbonding
+		);
+		assert_noop!(
+			ParticipantRegistry::bond_extra(RuntimeOrigin::signed(account(1)), 1),
+			Error::<Test>::AlreadyUnbonding
+		);
+
+		System::set_block_number(unlocks_at - 1);
+		assert_noop!(
+			ParticipantRegistry::withdraw_unbonded(RuntimeOrigin::signed(account(1))),
+			Error::<Test>::UnbondingPeriodNotOver
+		);
+
+		System::set_block_number(unlocks_at);
+		assert_ok!(ParticipantRegistry::withdraw_unbonded(RuntimeOrigin::signed(account(1))));
+		assert_eq!(Balances::reserved_balance(account(1)), 0);
+		assert_eq!(Balances::free_balance(account(1)), INITIAL_BALANCE);
+		assert_eq!(ParticipantRegistry::get_participant(account(1)), None);
+		assert_eq!(ParticipantRegistry::get_key_owner(public(1)), None);
+		assert_eq!(ParticipantCount::<Test>::get(), 0);
+	})
+}
@@ -0,0 +1,94 @@
+//! Weights for `pallet_participant_registry`
+//!
+//! Estimated from the storage accesses of each call until the numbers are regenerated with the
+//! benchmark CLI (see `pallet_logic_provider`'s `benchmark.sh`, with
+//! `--pallet pallet_participant_registry`).
+
+// #![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+pub trait WeightInfo {
+	fn register() -> Weight;
+	fn bond_extra() -> Weight;
+	fn unbond() -> Weight;
+	fn withdraw_unbonded() -> Weight;
+}
+
+/// Weight functions for `participant_registry`.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: ParticipantRegistry Participants (r:1 w:1)
+	// Storage: ParticipantRegistry KeyOwners (r:1 w:1)
+	// Storage: ParticipantRegistry ParticipantCount (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn register() -> Weight {
+		Weight::from_parts(75_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	// Storage: ParticipantRegistry Participants (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn bond_extra() -> Weight {
+		Weight::from_parts(22_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: ParticipantRegistry Participants (r:1 w:1)
+	fn unbond() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: ParticipantRegistry Participants (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ParticipantRegistry KeyOwners (r:0 w:1)
+	// Storage: ParticipantRegistry ParticipantCount (r:1 w:1)
+	fn withdraw_unbonded() -> Weight {
+		Weight::from_parts(28_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: ParticipantRegistry Participants (r:1 w:1)
+	// Storage: ParticipantRegistry KeyOwners (r:1 w:1)
+	// Storage: ParticipantRegistry ParticipantCount (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn register() -> Weight {
+		Weight::from_parts(75_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	// Storage: ParticipantRegistry Participants (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn bond_extra() -> Weight {
+		Weight::from_parts(22_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	// Storage: ParticipantRegistry Participants (r:1 w:1)
+	fn unbond() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: ParticipantRegistry Participants (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: ParticipantRegistry KeyOwners (r:0 w:1)
+	// Storage: ParticipantRegistry ParticipantCount (r:1 w:1)
+	fn withdraw_unbonded() -> Weight {
+		Weight::from_parts(28_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+}
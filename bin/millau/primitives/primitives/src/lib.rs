@@ -1,32 +1,78 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use core::time::Duration;
 use sp_application_crypto::{sr25519, KeyTypeId};
 
 // This file's contents are shared across runtime and client.
 // Therefore, changes to types in this file will almost certainly necessitate
 // a full node upgrade.
 
-/// This constant defines how often we send processed
-/// metadata hashes & update the offchain config.
-pub const PROCESSING_INTERVAL: Duration = Duration::from_secs(13);
-
 pub const PUBLIC_KEY_TYPE_ID: KeyTypeId = KeyTypeId(*b"pubK");
 sp_application_crypto::app_crypto!(sr25519, PUBLIC_KEY_TYPE_ID);
 // Types shared across runtime and client
 pub mod shared {
-	use codec::{Decode, Encode};
+	use codec::{Decode, Encode, MaxEncodedLen};
 	use scale_info::TypeInfo;
+	use serde::{Deserialize, Serialize};
 	use sp_application_crypto::{sr25519, KeyTypeId};
+	use sp_core::sp_std::vec::Vec;
 	use sp_runtime::Perbill;
 
 	pub type Hash = sp_core::H256;
 	pub type MetadataId = u64;
 	pub type BlockNumber = u64;
+	/// Secret mixed into a commitment so that the committed answer can't be guessed from its hash.
+	pub type Salt = [u8; 32];
+
+	/// Prefix of every commitment preimage, so that a commitment can't be mistaken for a hash
+	/// produced in any other context.
+	pub const COMMITMENT_DOMAIN_TAG: &[u8] = b"pallet-commitments/commit/v1";
+
+	/// The preimage hashed into the commitment of `committer` to `answer` in the round of
+	/// `commit_key`: `COMMITMENT_DOMAIN_TAG ++ committer ++ commit_key ++ answer ++ salt`, each
+	/// SCALE-encoded but the salt. Commitments are its blake2-256 hash.
+	pub fn commitment_preimage<AccountId: Encode, Answer: Encode>(
+		committer: &AccountId,
+		commit_key: MetadataId,
+		answer: &Answer,
+		salt: &Salt,
+	) -> Vec<u8> {
+		let mut preimage = COMMITMENT_DOMAIN_TAG.to_vec();
+		committer.encode_to(&mut preimage);
+		commit_key.encode_to(&mut preimage);
+		answer.encode_to(&mut preimage);
+		preimage.extend_from_slice(salt);
+		preimage
+	}
+
+	/// Prefix of the message an operator's key signs to be registered, so that the proof of key
+	/// ownership can't be mistaken for a signature produced in any other context.
+	pub const REGISTRATION_DOMAIN_TAG: &[u8] = b"participant-registry/register/v1";
+
+	/// The message the key registered by `operator` signs to prove the operator controls it:
+	/// `REGISTRATION_DOMAIN_TAG ++ operator`, the operator SCALE-encoded.
+	pub fn registration_payload<AccountId: Encode>(operator: &AccountId) -> Vec<u8> {
+		let mut payload = REGISTRATION_DOMAIN_TAG.to_vec();
+		operator.encode_to(&mut payload);
+		payload
+	}
 
 	pub const PUBLIC_KEY_TYPE_ID: KeyTypeId = KeyTypeId(*b"pubK");
 	sp_application_crypto::app_crypto!(sr25519, PUBLIC_KEY_TYPE_ID);
 
+	/// Reveal `value` as the answer to a metadata whose results are numeric: the number is
+	/// SCALE-encoded into the leading bytes of the revealed hash, the rest is left zeroed.
+	pub fn numeric_answer(value: u128) -> Hash {
+		let mut answer = Hash::zero();
+		value.using_encoded(|encoded| answer.0[..encoded.len()].copy_from_slice(encoded));
+		answer
+	}
+
+	/// The number revealed as `answer`, if it was built by [`numeric_answer`].
+	pub fn decode_numeric_answer(mut answer: &[u8]) -> Option<u128> {
+		let value = u128::decode(&mut answer).ok()?;
+		answer.iter().all(|byte| *byte == 0).then_some(value)
+	}
+
 	#[derive(Encode, Decode, Clone, Debug, PartialEq, TypeInfo)]
 	pub enum LogicProviderCall {
 		CommitHash {
@@ -35,7 +81,7 @@ pub mod shared {
 		},
 		RevealHash {
 			reveal_hash: Hash,
-			random_seed: u8,
+			salt: Salt,
 			metadata_id: MetadataId,
 			// public: Public,
 		},
@@ -45,7 +91,74 @@ pub mod shared {
 	pub struct OffchainCommitmentInfo {
 		pub reveal_hash: Hash,
 		pub commit_hash: Hash,
-		pub random_seed: u8,
+		pub salt: Salt,
+	}
+
+	/// How a participant behaved across the logic-provider rounds it took part in.
+	#[derive(Encode, Decode, MaxEncodedLen, Clone, Debug, Default, PartialEq, Eq, TypeInfo)]
+	pub struct ParticipantReputation {
+		/// Rounds in which the participant revealed the agreed result within the reveal window.
+		pub correct_reveals: u32,
+		/// Rounds in which the participant revealed a result other than the agreed one.
+		pub wrong_reveals: u32,
+		/// Rounds in which the participant did not reveal its answer at all.
+		pub missing_reveals: u32,
+		/// Rounds in which the participant revealed the agreed result after the reveal window.
+		pub late_reveals: u32,
+		/// Score built up from the outcomes above. It decays towards zero over time, so that
+		/// recent rounds weigh more than old ones.
+		pub score: i64,
+		/// Block up to which `score` has been decayed.
+		pub last_decayed_at: BlockNumber,
+	}
+
+	/// The stage a logic-provider round is in.
+	#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, Serialize, Deserialize)]
+	#[serde(rename_all = "camelCase")]
+	pub enum RoundStatus {
+		/// Accepting commitments until a quorum is reached, or the round expires at `expires_at`.
+		Open { expires_at: BlockNumber },
+		/// A quorum was reached; the reveal window opens at `ends_at`.
+		Committing { ends_at: BlockNumber },
+		/// Commitments can be revealed from `starts_at` up to `ends_at`.
+		Revealing { starts_at: BlockNumber, ends_at: BlockNumber },
+		/// The round settled on a result and its participants were rewarded.
+		Completed,
+		/// No result reached the required majority; the round awaits manual resolution.
+		Disputed,
+		/// The result was forced after a dispute.
+		ManuallyResolved,
+	}
+
+	/// What a single participant did in a logic-provider round.
+	#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, Serialize, Deserialize)]
+	#[serde(rename_all = "camelCase")]
+	pub struct ParticipantSummary<AccountId, Balance, Hash> {
+		pub account: AccountId,
+		/// Block the participant committed in.
+		pub committed_at: BlockNumber,
+		/// Amount the participant has at stake in the round.
+		pub stake: Balance,
+		/// The revealed answer, if the participant revealed already.
+		pub revealed: Option<Hash>,
+		/// Whether the answer was revealed within the reveal window.
+		pub revealed_in_window: bool,
+	}
+
+	/// State of a logic-provider round, as exposed to clients.
+	#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo, Serialize, Deserialize)]
+	#[serde(rename_all = "camelCase")]
+	pub struct RoundSummary<AccountId, Balance, Hash> {
+		pub metadata_id: MetadataId,
+		pub status: RoundStatus,
+		/// Participants of a round that has not been settled yet. Emptied once rewards were
+		/// issued.
+		pub participants: Vec<ParticipantSummary<AccountId, Balance, Hash>>,
+		/// The answer with the largest share of the votes revealed in time so far, along with
+		/// that share.
+		pub leading_hash: Option<(Hash, Perbill)>,
+		/// The result the round settled on.
+		pub result: Option<Hash>,
 	}
 
 	#[derive(Encode, Decode, Clone, Debug, PartialEq, TypeInfo)]
@@ -60,4 +173,6 @@ pub mod shared {
 // Information shared with the client only
 pub mod client {
 	pub const TRACKED_STORAGE_KEYS: &[u8] = b"tracked_keys";
+	/// Prefix of the offchain storage keys holding the reveal state of every tracked metadata id.
+	pub const REVEAL_STATE_PREFIX: &[u8] = b"reveal_state";
 }
@@ -15,27 +15,59 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 #![cfg_attr(not(feature = "std"), no_std)]
-use codec::{Decode, Encode};
-use primitives::shared::{BlockNumber, MetadataId, Public, Signature};
+use codec::{Codec, Decode, Encode};
+use primitives::shared::{
+	BlockNumber, Hash, MetadataId, ParticipantReputation, Public, RoundSummary, Signature,
+};
 use sp_core::sp_std::vec::Vec;
 
 #[derive(Encode, Decode, PartialEq, Debug)]
 pub enum Error {
 	AccountConversion,
+	/// The call handed to the runtime could not be decoded.
+	InvalidCall,
+	/// The transaction pool did not accept the extrinsic built from the call.
+	SubmissionFailed,
 }
 
 sp_api::decl_runtime_apis! {
 	pub trait ConstructExtrinsicApi {
+		/// Submit `mapped_call`, signed with `public`, to the transaction pool. Returns the hash of
+		/// the submitted extrinsic.
 		fn submit_unchecked_extrinsic(
 			mapped_call: Vec<u8>,
 			signature: Signature,
 			public: Public,
-		) -> Result<(), ()>;
+		) -> Result<Hash, Error>;
 	}
 
 	pub trait StorageQueryApi {
 		fn get_reveal_window(
 			metadata_id: MetadataId,
 		) -> Option<(BlockNumber, BlockNumber)>;
+
+		/// The metadata requested for processing whose round still accepts commitments, oldest
+		/// first.
+		fn get_pending_requests() -> Vec<MetadataId>;
+
+		/// Whether the reveal of the participant signing with `public` for `metadata_id` is on
+		/// chain, i.e. a reveal extrinsic of theirs was dispatched successfully.
+		fn has_revealed(metadata_id: MetadataId, public: Public) -> bool;
+	}
+
+	pub trait ReputationApi {
+		/// The reputation of the participant signing with `public`, decayed up to the current
+		/// block.
+		fn get_reputation(public: Public) -> ParticipantReputation;
+	}
+
+	pub trait LogicProviderApi<AccountId, Balance>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The state of the logic-provider round for `metadata_id`, or `None` if there is no
+		/// such round or it expired.
+		fn round_summary(metadata_id: MetadataId) -> Option<RoundSummary<AccountId, Balance, Hash>>;
 	}
 }
@@ -33,14 +33,11 @@ pub mod rialto_parachain_messages;
 pub mod weights;
 pub mod xcm_config;
 
-use crate::xcm_config::XcmRouter;
-
 use bp_parachains::SingleParaStoredHeaderDataBuilder;
 #[cfg(feature = "runtime-benchmarks")]
 use bp_relayers::{RewardsAccountOwner, RewardsAccountParams};
-use bp_rialto_parachain::RIALTO_PARACHAIN_ID;
 use bp_runtime::HeaderId;
-use frame_system::{pallet_prelude::OriginFor, EnsureRoot};
+use frame_system::{pallet_prelude::OriginFor, EnsureRoot, EnsureSigned};
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
@@ -57,7 +54,6 @@ use sp_runtime::{
 	ApplyExtrinsicResult, FixedPointNumber, Perquintill,
 };
 
-use bridge_runtime_common::CustomNetworkId;
 use xcm::latest::prelude::*;
 
 use sp_std::prelude::*;
@@ -486,10 +482,12 @@ impl pallet_bridge_messages::Config<WithRialtoParachainMessagesInstance> for Run
 	type InboundPayload = bridge_runtime_common::messages_xcm_extension::XcmAsPlainPayload;
 
 	type DeliveryPayments = ();
-	type DeliveryConfirmationPayments = pallet_bridge_relayers::DeliveryConfirmationPaymentsAdapter<
-		Runtime,
-		WithRialtoParachainMessagesInstance,
-		frame_support::traits::ConstU64<100_000>,
+	type DeliveryConfirmationPayments = crate::rialto_parachain_messages::ConfirmResultDelivery<
+		pallet_bridge_relayers::DeliveryConfirmationPaymentsAdapter<
+			Runtime,
+			WithRialtoParachainMessagesInstance,
+			frame_support::traits::ConstU64<100_000>,
+		>,
 	>;
 
 	type MessageDispatch = crate::rialto_parachain_messages::FromRialtoParachainMessageDispatch;
@@ -543,47 +541,107 @@ parameter_types! {
 	pub const Reward: u64 = 1_000_000_000_000_000;
 	pub const FundsToLock: u64 = 500_000_000_000;
 	pub const EnforceBurningTokens: bool = false;
+	/// Count one vote per participant rather than weighing votes by the bonded stake.
+	pub const StakeWeightedConsensus: bool = false;
 	/// The number of blocks during which the participants are still
 	/// able to provide solutions **after** enough
 	/// submissions are gathered.
 	pub const BlocksPerRound: u8 = 1;
 	pub const MaxCallPayloadLength: u16 =  325;
+	pub const CorrectRevealScore: u32 = 10;
+	pub const FaultPenalty: u32 = 20;
+	pub const LateRevealPenalty: u32 = 5;
+	/// Scores halve every day, so that participants can recover from past faults.
+	pub const ReputationDecay: Perbill = Perbill::from_percent(50);
+	pub const ReputationDecayPeriod: u64 = bp_millau::DAYS as u64;
+	/// Keep participants out once they failed several rounds in a row.
+	pub const MinimumReputation: Option<i64> = Some(-100);
+	pub const MaxQueuedRounds: u32 = 64;
+	/// Leave most of the block to extrinsics, rounds that do not fit are settled in later blocks.
+	pub MaxRoundSettlementWeight: Weight =
+		Perbill::from_percent(25) * bp_millau::BlockWeights::get().max_block;
+	/// Results that could not be sent are retried after 10, 20, 40 and 80 blocks.
+	pub const MaxDeliveryAttempts: u32 = 5;
+	pub const DeliveryRetryDelay: u32 = 10;
+	pub const MaxDeliveryRetriesPerBlock: u32 = 16;
+	/// Results whose delivery the bridge did not confirm within an hour are sent again.
+	pub const DeliveryTimeout: u32 = bp_millau::HOURS as u32;
 }
 
 impl pallet_logic_provider::TemplateBridgedXcm<Runtime> for BridgeRialtoMessages {
 	fn send_transact(
 		_origin: OriginFor<Runtime>,
-		proof: Vec<u8>,
-		_delivery_and_dispatch_fee: u64,
+		result: pallet_logic_provider::CrossChainResult,
+		destination: Option<MultiLocation>,
 	) -> Result<([u8; 32], xcm::v3::MultiAssets), xcm::v3::SendError> {
 		use codec::Encode;
-		const EXTRINSIC_INDEX: u8 = 0;
-		// Construct the call. First, prepend the pallet index and the extrinsic index,
-		// then add encoded params to that byte vector.
-		let encoded_hardcoded_call: Vec<u8> =
-			[vec![TARGET_X_CHAIN_PALLET_INDEX, EXTRINSIC_INDEX], proof.encode()].concat();
-
-		// Send Transact call - tell other chain to dispatch *some* call. Is accepted and delivered
-		// to the other chain.
-		let xcm: Xcm<()> = vec![Instruction::Transact {
-			origin_kind: OriginKind::Xcm,
-			// max weight of call
-			require_weight_at_most: Weight::from_parts(100000, 0),
-			call: encoded_hardcoded_call.into(),
-		}]
-		.into();
+		use frame_support::weights::WeightToFee;
 
-		let dest = (
-			Parent,
-			X2(
-				GlobalConsensus(CustomNetworkId::Rialto.as_network_id()),
-				Parachain(RIALTO_PARACHAIN_ID),
-			),
+		let call = bp_rialto_parachain::Call::XChain(
+			bp_rialto_parachain::XChainCall::submit_result { result },
 		);
-		send_xcm::<XcmRouter>(dest.into(), xcm)
+		// The weight RialtoParachain charges for the call, including the pallets it notifies
+		let call_weight = bp_rialto_parachain::SUBMIT_RESULT_DISPATCH_WEIGHT;
+
+		// Send Transact call - tell other chain to dispatch the call. Is accepted and delivered
+		// to the other chain. The call is dispatched from the location of this pallet, which
+		// RialtoParachain allows to submit results.
+		let xcm: Xcm<()> = vec![
+			Instruction::DescendOrigin(X1(PalletInstance(bp_millau::LOGIC_PROVIDER_PALLET_INDEX))),
+			Instruction::Transact {
+				origin_kind: OriginKind::Xcm,
+				require_weight_at_most: call_weight,
+				call: call.encode().into(),
+			},
+		]
+		.into();
+
+		// Executing the message also takes the `UniversalOrigin` instruction prepended by the
+		// bridge.
+		let execution_weight = bp_rialto_parachain::XCM_INSTRUCTION_WEIGHT
+			.saturating_mul(xcm.len() as u64 + 1)
+			.saturating_add(call_weight);
+		let dispatch_fee = bp_rialto_parachain::WeightToFee::weight_to_fee(&execution_weight);
+
+		// Results requested from another chain go back to the chain of the requester. Either
+		// way they leave over the RialtoParachain lane, whose nonces and confirmations the
+		// logic provider tracks.
+		let dest: MultiLocation = match destination {
+			Some(requester) => chain_of(requester),
+			None => {
+				let rialto_parachain = xcm_config::RialtoParachainNetwork::get();
+				MultiLocation::new(1, X1(GlobalConsensus(rialto_parachain)))
+			},
+		};
+		let (message_hash, mut fees) =
+			send_xcm::<rialto_parachain_messages::ToRialtoParachainRouter>(dest, xcm)?;
+		fees.push((dest, dispatch_fee).into());
+		Ok((message_hash, fees))
+	}
+
+	fn last_sent_nonce() -> Option<u64> {
+		use bridge_runtime_common::messages_xcm_extension::XcmBlobHauler;
+		pallet_bridge_messages::OutboundLanes::<Runtime, WithRialtoParachainMessagesInstance>::get(
+			rialto_parachain_messages::ToRialtoParachainXcmBlobHauler::xcm_lane(),
+		)
+		.map(|lane| lane.latest_generated_nonce)
 	}
 }
 
+/// The chain a location belongs to, i.e. the location up to its `GlobalConsensus` and `Parachain`
+/// junctions.
+fn chain_of(location: MultiLocation) -> MultiLocation {
+	location
+		.interior
+		.into_iter()
+		.take_while(|junction| matches!(junction, GlobalConsensus(_) | Parachain(_)))
+		.fold(MultiLocation::new(location.parents, Here), |mut chain, junction| {
+			// Can't overflow, `chain` has fewer junctions than `location`
+			let _ = chain.push_interior(junction);
+			chain
+		})
+}
+
 // How many participants can there be within the network
 #[cfg(feature = "runtime-benchmarks")]
 parameter_types! {
@@ -605,9 +663,28 @@ impl pallet_logic_provider::Config for Runtime {
 	type EnforceBurningTokens = EnforceBurningTokens;
 	type Reward = Reward;
 	type FundsToLock = FundsToLock;
+	type StakeWeightedConsensus = StakeWeightedConsensus;
+	type BondedAmount = ();
+	type ParticipantRegistry = ParticipantRegistry;
+	type MetadataRequests = MetadataRequests;
+	type Aggregator = pallet_logic_provider::ExactMatch;
+	type CorrectRevealScore = CorrectRevealScore;
+	type FaultPenalty = FaultPenalty;
+	type LateRevealPenalty = LateRevealPenalty;
+	type ReputationDecay = ReputationDecay;
+	type ReputationDecayPeriod = ReputationDecayPeriod;
+	type MinimumReputation = MinimumReputation;
+	type MaxQueuedRounds = MaxQueuedRounds;
+	type MaxRoundSettlementWeight = MaxRoundSettlementWeight;
+	type MaxDeliveryAttempts = MaxDeliveryAttempts;
+	type DeliveryRetryDelay = DeliveryRetryDelay;
+	type MaxDeliveryRetriesPerBlock = MaxDeliveryRetriesPerBlock;
+	type DeliveryTimeout = DeliveryTimeout;
 	type ForceOrigin = EnsureRoot<AccountId>;
 	type WeightInfo = pallet_logic_provider::SubstrateWeight<Self>;
 	type LocalCurrency = Balances;
+	// There is no treasury on Millau, slashed funds are burned
+	type SlashHandler = ();
 
 	type Bridging = BridgeRialtoMessages;
 }
@@ -621,13 +698,59 @@ where
 }
 
 parameter_types! {
-	pub const RevealWindowLength:u8 = 5;
+	/// Rounds that do not gather a quorum of commitments within an hour expire.
+	pub const OpenPhaseLength: u32 = bp_millau::HOURS as u32;
+	pub const CommitPhaseLength: u32 = 5;
+	pub const RevealWindowLength: u32 = 5;
+	/// As many rounds as there can be open requests.
+	pub const MaxPhaseChangesPerBlock: u32 = 64;
 }
 
 impl pallet_commitments::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type OpenPhaseLength = OpenPhaseLength;
+	type CommitPhaseLength = CommitPhaseLength;
 	type RevealWindowLength = RevealWindowLength;
 	type MaxParticipants = MaxParticipants;
+	type MaxPhaseChangesPerBlock = MaxPhaseChangesPerBlock;
 	type Hash = H256;
+	type OnRoundExpired = LogicProvider;
+}
+
+parameter_types! {
+	/// The minimum amount an operator has to bond to take part in logic-provider rounds.
+	pub const MinimumParticipantBond: Balance = 1_000_000_000_000;
+	pub const ParticipantUnbondingPeriod: BlockNumber = bp_millau::DAYS;
+	pub const MaxRegisteredParticipants: u32 = 100;
+}
+
+impl pallet_participant_registry::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type MinimumBond = MinimumParticipantBond;
+	type UnbondingPeriod = ParticipantUnbondingPeriod;
+	type MaxRegisteredParticipants = MaxRegisteredParticipants;
+	type WeightInfo = pallet_participant_registry::SubstrateWeight<Self>;
+}
+
+parameter_types! {
+	pub const MaxOpenRequests: u32 = 64;
+	pub const MaxRequestSourceLength: u32 = 256;
+	/// Leave participants at least a few rounds' worth of blocks to settle a request.
+	pub const MinimumRequestLifetime: BlockNumber = bp_millau::HOURS;
+}
+
+impl pallet_metadata_requests::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type RequestOrigin = EnsureSigned<AccountId>;
+	type CrossChainOrigin = pallet_xcm::EnsureXcm<xcm_config::AtRialtoParachain>;
+	type SovereignAccountOf = xcm_config::RialtoParachainRequesterAccount;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type MaxOpenRequests = MaxOpenRequests;
+	type MaxSourceLength = MaxRequestSourceLength;
+	type MinimumLifetime = MinimumRequestLifetime;
+	type WeightInfo = pallet_metadata_requests::SubstrateWeight<Self>;
 }
 
 parameter_types! {
@@ -651,8 +774,6 @@ impl pallet_collective::Config<CouncilCollective> for Runtime {
 	type MaxProposalWeight = MaxProposalWeight;
 }
 
-const TARGET_X_CHAIN_PALLET_INDEX: u8 = 200;
-
 construct_runtime!(
 	pub enum Runtime {
 		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
@@ -693,9 +814,13 @@ construct_runtime!(
 		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config<T>} = 99,
 
 		// Include the custom logic from the logic-provider in the runtime.
-		LogicProvider: pallet_logic_provider::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
+		// Its index is part of the location RialtoParachain accepts results from.
+		LogicProvider: pallet_logic_provider::{Pallet, Call, Storage, Event<T>, ValidateUnsigned} = 100,
 		Council: pallet_collective::<Instance1>,
 		Commitments: pallet_commitments,
+		ParticipantRegistry: pallet_participant_registry,
+		// Its index is part of the call RialtoParachain sends metadata requests with.
+		MetadataRequests: pallet_metadata_requests = 104,
 	}
 );
 
@@ -751,6 +876,13 @@ pub type UncheckedExtrinsic =
 	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, RuntimeCall, SignedExtra>;
+/// Migrations to apply on runtime upgrade.
+pub type Migrations = (
+	pallet_commitments::migrations::v1::ExpireLegacyCommitments<Runtime>,
+	pallet_commitments::migrations::v2::ScheduleRoundPhaseChanges<Runtime>,
+	pallet_logic_provider::migrations::v1::SplitMajorityType<Runtime>,
+	pallet_logic_provider::migrations::v2::ScheduleSettlements<Runtime>,
+);
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -758,6 +890,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -777,11 +910,13 @@ impl_runtime_apis! {
 			mapped_call: Vec<u8>,
 			signature: primitives::shared::Signature,
 			public: primitives::shared::Public,
-		) -> Result<(), ()> {
-			let decoded_call = MapToCall::decode(&mut &mapped_call[..]).map_err(|_| ())?;
+		) -> Result<primitives::shared::Hash, runtime_api::Error> {
+			let decoded_call = MapToCall::decode(&mut &mapped_call[..])
+				.map_err(|_| runtime_api::Error::InvalidCall)?;
 			match decoded_call {
 				MapToCall::LogicProviderCall(_) =>
-					LogicProvider::create_extrinsic_from_external_call(mapped_call, public, signature),
+					LogicProvider::create_extrinsic_from_external_call(mapped_call, public, signature)
+						.map_err(|()| runtime_api::Error::SubmissionFailed),
 			}
 		}
 	}
@@ -792,6 +927,35 @@ impl_runtime_apis! {
 		) -> Option<(BlockNumber, BlockNumber)> {
 			Commitments::get_reveal_window(metadata_id)
 		}
+
+		fn get_pending_requests() -> Vec<primitives::shared::MetadataId> {
+			LogicProvider::pending_requests()
+		}
+
+		fn has_revealed(
+			metadata_id: primitives::shared::MetadataId,
+			public: primitives::shared::Public,
+		) -> bool {
+			LogicProvider::has_revealed(metadata_id, public)
+		}
+	}
+
+	impl runtime_api::ReputationApi<Block> for Runtime {
+		fn get_reputation(
+			public: primitives::shared::Public,
+		) -> primitives::shared::ParticipantReputation {
+			LogicProvider::to_account_id(public)
+				.map(|who| LogicProvider::reputation(&who))
+				.unwrap_or_default()
+		}
+	}
+
+	impl runtime_api::LogicProviderApi<Block, AccountId, Balance> for Runtime {
+		fn round_summary(
+			metadata_id: primitives::shared::MetadataId,
+		) -> Option<primitives::shared::RoundSummary<AccountId, Balance, primitives::shared::Hash>> {
+			LogicProvider::round_summary(metadata_id)
+		}
 	}
 
 	impl sp_api::Core<Block> for Runtime {
@@ -1279,6 +1443,7 @@ impl_runtime_apis! {
 mod tests {
 	use super::*;
 	use bp_runtime::Chain;
+	use bridge_runtime_common::CustomNetworkId;
 
 	#[test]
 	fn runtime_version() {
@@ -1299,4 +1464,67 @@ mod tests {
 		const MAX_CALL_SIZE: usize = 230; // value from polkadot-runtime tests
 		assert!(core::mem::size_of::<RuntimeCall>() <= MAX_CALL_SIZE);
 	}
+
+	#[test]
+	fn logic_provider_pallet_index_matches_bridged_chains() {
+		use frame_support::traits::PalletInfoAccess;
+		assert_eq!(LogicProvider::index(), bp_millau::LOGIC_PROVIDER_PALLET_INDEX as usize);
+		assert_eq!(
+			MetadataRequests::index(),
+			bp_millau::METADATA_REQUESTS_PALLET_INDEX as usize
+		);
+	}
+
+	#[test]
+	fn indirect_metadata_requests_calls_match_runtime_calls() {
+		use codec::Encode;
+
+		let source = b"http://localhost:8000/metadatas".to_vec();
+		let call = RuntimeCall::MetadataRequests(
+			pallet_metadata_requests::Call::request_from_location {
+				source: source.clone(),
+				lifetime: 100,
+				reward: 1_000,
+			},
+		);
+		let indirect_call = bp_millau::Call::MetadataRequests(
+			bp_millau::MetadataRequestsCall::request_from_location {
+				source,
+				lifetime: 100,
+				reward: 1_000,
+			},
+		);
+		assert_eq!(call.encode(), indirect_call.encode());
+	}
+
+	#[test]
+	fn metadata_requests_fit_in_the_weight_rialto_parachain_pays_for() {
+		use pallet_metadata_requests::WeightInfo;
+
+		let weight = pallet_metadata_requests::SubstrateWeight::<Runtime>::request_from_location();
+		assert!(weight.all_lte(bp_millau::MAX_METADATA_REQUEST_WEIGHT));
+	}
+
+	#[test]
+	fn results_are_sent_back_to_the_chain_of_the_requester() {
+		use frame_support::traits::Contains;
+
+		let rialto_parachain = GlobalConsensus(CustomNetworkId::RialtoParachain.as_network_id());
+		let requester = MultiLocation::new(
+			1,
+			X2(rialto_parachain, AccountId32 { network: None, id: [1; 32] }),
+		);
+		assert_eq!(chain_of(requester), MultiLocation::new(1, X1(rialto_parachain)));
+
+		let pallet =
+			MultiLocation::new(1, X3(rialto_parachain, Parachain(2000), PalletInstance(42)));
+		assert_eq!(chain_of(pallet), MultiLocation::new(1, X2(rialto_parachain, Parachain(2000))));
+		assert_eq!(chain_of(MultiLocation::here()), MultiLocation::here());
+
+		assert!(xcm_config::AtRialtoParachain::contains(&requester));
+		assert!(!xcm_config::AtRialtoParachain::contains(&MultiLocation::new(
+			1,
+			X1(GlobalConsensus(CustomNetworkId::Rialto.as_network_id()))
+		)));
+	}
 }
@@ -16,15 +16,19 @@
 
 //! Everything required to serve Millau <-> RialtoParachain messages.
 
-use crate::{Runtime, WithRialtoParachainMessagesInstance};
+use crate::{AccountId, LogicProvider, Runtime, WithRialtoParachainMessagesInstance};
 
-use bp_messages::LaneId;
+use bp_messages::{
+	source_chain::DeliveryConfirmationPayments, LaneId, MessageNonce, UnrewardedRelayer,
+};
 use bridge_runtime_common::messages_xcm_extension::{
 	LaneIdFromChainId, XcmBlobHauler, XcmBlobHaulerAdapter,
 };
 use frame_support::{parameter_types, weights::Weight};
 use pallet_bridge_relayers::WeightInfoExt as _;
+use pallet_logic_provider::WeightInfo as _;
 use sp_core::Get;
+use sp_std::{collections::vec_deque::VecDeque, marker::PhantomData, ops::RangeInclusive};
 use xcm_builder::HaulBlobExporter;
 
 /// Weight of 2 XCM instructions is for simple `Trap(42)` program, coming through bridge
@@ -54,6 +58,13 @@ pub type ToRialtoParachainBlobExporter = HaulBlobExporter<
 	(),
 >;
 
+/// Sends XCM messages to RialtoParachain over the lane whose confirmations
+/// [`ConfirmResultDelivery`] passes on to the logic provider.
+pub type ToRialtoParachainRouter = xcm_builder::UnpaidLocalExporter<
+	ToRialtoParachainBlobExporter,
+	crate::xcm_config::UniversalLocation,
+>;
+
 /// To-RialtoParachain XCM hauler.
 pub struct ToRialtoParachainXcmBlobHauler;
 
@@ -66,6 +77,30 @@ impl XcmBlobHauler for ToRialtoParachainXcmBlobHauler {
 	}
 }
 
+/// Rewards relayers through `Payments`, and lets the logic provider know which of the results it
+/// sent to RialtoParachain have been delivered. Its weight is part of
+/// `receive_messages_delivery_proof_overhead_from_runtime`.
+pub struct ConfirmResultDelivery<Payments>(PhantomData<Payments>);
+
+impl<Payments> DeliveryConfirmationPayments<AccountId> for ConfirmResultDelivery<Payments>
+where
+	Payments: DeliveryConfirmationPayments<AccountId>,
+{
+	type Error = Payments::Error;
+
+	fn pay_reward(
+		lane_id: LaneId,
+		messages_relayers: VecDeque<UnrewardedRelayer<AccountId>>,
+		confirmation_relayer: &AccountId,
+		received_range: &RangeInclusive<MessageNonce>,
+	) -> MessageNonce {
+		if lane_id == ToRialtoParachainXcmBlobHauler::xcm_lane() {
+			LogicProvider::confirm_delivery(received_range.clone());
+		}
+		Payments::pay_reward(lane_id, messages_relayers, confirmation_relayer, received_range)
+	}
+}
+
 impl pallet_bridge_messages::WeightInfoExt
 	for crate::weights::RialtoParachainMessagesWeightInfo<Runtime>
 {
@@ -78,7 +113,13 @@ impl pallet_bridge_messages::WeightInfoExt
 	}
 
 	fn receive_messages_delivery_proof_overhead_from_runtime() -> Weight {
+		// `ConfirmResultDelivery` looks at every message a confirmation may carry
+		let confirm_results =
+			<Runtime as pallet_logic_provider::Config>::WeightInfo::confirm_delivery(
+				bp_millau::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX as u32,
+			);
 		pallet_bridge_relayers::weights::BridgeWeight::<Runtime>::receive_messages_delivery_proof_overhead_from_runtime()
+			.saturating_add(confirm_results)
 	}
 }
 
@@ -86,10 +127,12 @@ impl pallet_bridge_messages::WeightInfoExt
 mod tests {
 	use super::*;
 	use crate::{
-		PriorityBoostPerMessage, RialtoGrandpaInstance, Runtime,
+		Balances, MetadataRequests, ParticipantRegistry, PriorityBoostPerMessage,
+		RialtoGrandpaInstance, Runtime, RuntimeOrigin, System, WithRialtoMessagesInstance,
 		WithRialtoParachainMessagesInstance,
 	};
 
+	use bp_messages::OutboundLaneData;
 	use bridge_runtime_common::{
 		assert_complete_bridge_types,
 		integrity::{
@@ -97,6 +140,138 @@ mod tests {
 			AssertChainConstants, AssertCompleteBridgeConstants,
 		},
 	};
+	use codec::Encode;
+	use frame_support::{assert_ok, traits::Currency};
+	use pallet_bridge_messages::OutboundLanes;
+	use pallet_logic_provider::DeliveryStatus;
+	use primitives::shared::{
+		commitment_preimage, registration_payload, LogicProviderCall, MapToCall, Pair,
+	};
+	use sp_core::{Pair as _, H256};
+	use sp_runtime::BuildStorage;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		sp_io::TestExternalities::new(
+			frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap(),
+		)
+	}
+
+	// Have a single participant settle a round on `answer`, which sends the result to the default
+	// destination of the logic provider
+	fn settle_round_with_default_destination(answer: H256) -> u64 {
+		let operator = Pair::from_seed(&[1; 32]);
+		let account = LogicProvider::to_account_id(operator.public()).unwrap();
+		let requester = AccountId::from([2; 32]);
+		Balances::make_free_balance_be(&account, 10_000_000_000_000);
+		Balances::make_free_balance_be(&requester, 10_000_000_000_000);
+
+		assert_ok!(ParticipantRegistry::register(
+			RuntimeOrigin::signed(account.clone()),
+			operator.public(),
+			operator.sign(&registration_payload(&account)),
+			crate::MinimumParticipantBond::get(),
+		));
+		let metadata_id = pallet_metadata_requests::NextMetadataId::<Runtime>::get();
+		assert_ok!(MetadataRequests::request(
+			RuntimeOrigin::signed(requester),
+			b"http://localhost:8000/metadatas".to_vec(),
+			System::block_number() + bp_millau::DAYS,
+			1_000,
+		));
+
+		let salt = [3; 32];
+		let hash = sp_io::hashing::blake2_256(&commitment_preimage(
+			&account,
+			metadata_id,
+			&answer,
+			&salt,
+		));
+		let commit = MapToCall::LogicProviderCall(LogicProviderCall::CommitHash {
+			metadata_id,
+			hash: hash.into(),
+		})
+		.encode();
+		assert_ok!(LogicProvider::commit_processing_result_hash(
+			RuntimeOrigin::none(),
+			commit.clone(),
+			operator.sign(&commit),
+			operator.public(),
+		));
+
+		pallet_commitments::Rounds::<Runtime>::insert(
+			metadata_id,
+			pallet_commitments::RoundPhase::Revealing {
+				starts_at: System::block_number(),
+				ends_at: System::block_number() + u64::from(crate::RevealWindowLength::get()),
+			},
+		);
+		let reveal = MapToCall::LogicProviderCall(LogicProviderCall::RevealHash {
+			metadata_id,
+			reveal_hash: answer,
+			salt,
+		})
+		.encode();
+		assert_ok!(LogicProvider::reveal_processing_result_hash(
+			RuntimeOrigin::none(),
+			reveal.clone(),
+			operator.sign(&reveal),
+			operator.public(),
+		));
+		assert_ok!(LogicProvider::issue_rewards(RuntimeOrigin::none(), metadata_id));
+
+		metadata_id
+	}
+
+	#[test]
+	fn results_sent_to_the_default_destination_are_confirmed_over_the_same_lane() {
+		new_test_ext().execute_with(|| {
+			let lane_id = ToRialtoParachainXcmBlobHauler::xcm_lane();
+			let rialto_lane_id = crate::rialto_messages::ToRialtoXcmBlobHauler::xcm_lane();
+			OutboundLanes::<Runtime, WithRialtoParachainMessagesInstance>::insert(
+				lane_id,
+				OutboundLaneData::opened(),
+			);
+			OutboundLanes::<Runtime, WithRialtoMessagesInstance>::insert(
+				rialto_lane_id,
+				OutboundLaneData::opened(),
+			);
+
+			let metadata_id = settle_round_with_default_destination(H256::repeat_byte(42));
+
+			// The result left over the RialtoParachain lane only
+			assert!(matches!(
+				LogicProvider::outbound_result(metadata_id).unwrap().status,
+				DeliveryStatus::Sent(_)
+			));
+			assert_eq!(
+				OutboundLanes::<Runtime, WithRialtoParachainMessagesInstance>::get(lane_id)
+					.unwrap()
+					.latest_generated_nonce,
+				1,
+			);
+			assert_eq!(
+				OutboundLanes::<Runtime, WithRialtoMessagesInstance>::get(rialto_lane_id)
+					.unwrap()
+					.latest_generated_nonce,
+				0,
+			);
+
+			// The bridge confirms it over the same lane
+			let relayer = AccountId::from([4; 32]);
+			<Runtime as pallet_bridge_messages::Config<
+				WithRialtoParachainMessagesInstance,
+			>>::DeliveryConfirmationPayments::pay_reward(
+				lane_id,
+				VecDeque::new(),
+				&relayer,
+				&(1..=1),
+			);
+			assert_eq!(
+				LogicProvider::outbound_result(metadata_id).unwrap().status,
+				DeliveryStatus::Delivered
+			);
+		})
+	}
 
 	#[test]
 	fn ensure_millau_message_lane_weights_are_correct() {
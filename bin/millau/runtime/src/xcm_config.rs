@@ -23,19 +23,22 @@ use super::{
 };
 use bp_millau::WeightToFee;
 use bridge_runtime_common::CustomNetworkId;
+use codec::Encode;
 use frame_support::{
 	parameter_types,
-	traits::{ConstU32, Everything, Nothing},
+	traits::{ConstU32, Contains, Everything, Nothing},
 	weights::Weight,
 };
 use frame_system::EnsureRoot;
+use pallet_xcm::XcmPassthrough;
+use sp_core::hashing::blake2_256;
 use xcm::latest::prelude::*;
 use xcm_builder::{
 	AccountId32Aliases, CurrencyAdapter as XcmCurrencyAdapter, IsConcrete, MintLocation,
 	SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation, TakeWeightCredit,
 	UsingComponents,
 };
-use xcm_executor::traits::ExportXcm;
+use xcm_executor::traits::{ConvertLocation, ExportXcm};
 
 parameter_types! {
 	/// The location of the `MLAU` token, from the context of this chain. Since this token is native to this
@@ -87,11 +90,37 @@ type LocalOriginConverter = (
 	SovereignSignedViaLocation<SovereignAccountOf, RuntimeOrigin>,
 	// The AccountId32 location type can be expressed natively as a `Signed` origin.
 	SignedAccountId32AsNative<ThisNetwork, RuntimeOrigin>,
+	// Xcm origins can be represented natively under the Xcm pallet's Xcm origin.
+	XcmPassthrough<RuntimeOrigin>,
 );
 
+/// Locations at RialtoParachain, which may request metadata from the logic providers.
+pub struct AtRialtoParachain;
+
+impl Contains<MultiLocation> for AtRialtoParachain {
+	fn contains(location: &MultiLocation) -> bool {
+		location.parents == 1 &&
+			location.first_interior() == Some(&GlobalConsensus(RialtoParachainNetwork::get()))
+	}
+}
+
+/// Derives the account that escrows the rewards of the metadata requests of a RialtoParachain
+/// location.
+///
+/// The account is a hash of the location, so no one at Millau holds its keys. It has to be funded
+/// before the location can request metadata.
+pub struct RialtoParachainRequesterAccount;
+
+impl ConvertLocation<AccountId> for RialtoParachainRequesterAccount {
+	fn convert_location(location: &MultiLocation) -> Option<AccountId> {
+		AtRialtoParachain::contains(location)
+			.then(|| ("bridged-requester", location).using_encoded(blake2_256).into())
+	}
+}
+
 parameter_types! {
 	/// The amount of weight an XCM operation takes. This is a safe overestimate.
-	pub const BaseXcmWeight: Weight = Weight::from_parts(1_000_000_000, 64 * 1024);
+	pub const BaseXcmWeight: Weight = bp_millau::XCM_INSTRUCTION_WEIGHT;
 	/// Maximum number of instructions in a single XCM fragment. A sanity check against weight
 	/// calculations getting too crazy.
 	pub const MaxInstructions: u32 = 100;
@@ -202,5 +202,9 @@ fn testnet_genesis(
 			],
 			..Default::default()
 		},
+		x_chain: rialto_parachain_runtime::XChainConfig {
+			allowed_sources: vec![rialto_parachain_runtime::MillauLogicProviderLocation::get()],
+			..Default::default()
+		},
 	}
 }
@@ -0,0 +1,15 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bin_traits::StateKey;
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	pub trait SharedStateApi<Value>
+	where
+		Value: Codec,
+	{
+		/// The latest state written under `key`, or `None` if there is no such state or it is
+		/// not a `Value`.
+		fn latest_state(key: StateKey) -> Option<Value>;
+	}
+}
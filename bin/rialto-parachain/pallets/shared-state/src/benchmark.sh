@@ -0,0 +1,8 @@
+../../../../../target/release/rialto-parachain-collator benchmark pallet --chain dev --execution wasm \
+    --wasm-execution compiled \
+    --pallet pallet_shared_state \
+    --extrinsic '*' \
+    --steps 25 \
+    --repeat 25 \
+    --json-file=benchmark_raw.json \
+    --output ./weights_new.rs
@@ -0,0 +1,27 @@
+//! Benchmarking setup for pallet-shared-state
+
+use super::*;
+use bin_traits::SharedState;
+use frame_benchmarking::instance_benchmarks;
+use frame_support::traits::Get;
+use sp_std::vec;
+
+instance_benchmarks! {
+	// Write a state of `l` bytes under a key whose history is already full of states of the
+	// maximal length, so that the oldest one has to be dropped.
+	write_state {
+		let l in 1 .. T::MaxStateLength::get();
+		let key = 1;
+		for _ in 0..T::MaxHistoryLength::get() {
+			Pallet::<T, I>::write(key, vec![0; T::MaxStateLength::get() as usize])
+				.map_err(|_| "Failed to fill the history")?;
+		}
+		let state = vec![1; l as usize];
+	}: {
+		Pallet::<T, I>::write(key, state.clone()).map_err(|_| "Failed to write the state")?;
+	}
+	verify {
+		assert_eq!(Pallet::<T, I>::read(key), Some(state));
+		assert_eq!(States::<T, I>::get(key).len() as u32, T::MaxHistoryLength::get());
+	}
+}
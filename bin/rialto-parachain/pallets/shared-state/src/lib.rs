@@ -5,38 +5,95 @@
 /// <https://docs.substrate.io/v3/runtime/frame>
 pub use pallet::*;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
+pub mod weights;
+pub use weights::*;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use bin_traits::{SharedState, SharedStateError, Vec};
+	use bin_traits::{SharedState, SharedStateError, StateKey, Vec};
 	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
 	pub trait Config<I: 'static = ()>: frame_system::Config {
+		type RuntimeEvent: From<Event<Self, I>>
+			+ IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		#[pallet::constant]
 		type MaxStateLength: Get<u32>;
+		/// How many states are kept for each key, the latest one included. Once there are that
+		/// many, writing a new state drops the oldest one.
+		#[pallet::constant]
+		type MaxHistoryLength: Get<u32>;
+		type WeightInfo: WeightInfo;
 	}
 
+	/// A state, as written by the solo chains.
+	pub type StateOf<T, I> = BoundedVec<u8, <T as Config<I>>::MaxStateLength>;
+
+	/// The states kept for a key along with the block they were written in, oldest first.
+	pub type HistoryOf<T, I> = BoundedVec<
+		(BlockNumberFor<T>, StateOf<T, I>),
+		<T as Config<I>>::MaxHistoryLength,
+	>;
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T, I = ()>(_);
 
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// A new state was written under `key`.
+		StateUpdated { key: StateKey },
+	}
+
 	impl<T: Config<I>, I: 'static> SharedState<I> for Pallet<T, I> {
-		fn read() -> Vec<u8> {
-			State::<T, I>::get().into_inner()
+		fn read(key: StateKey) -> Option<Vec<u8>> {
+			States::<T, I>::get(key).last().map(|(_, state)| state.clone().into_inner())
 		}
 
-		fn write(state: Vec<u8>) -> Result<(), SharedStateError> {
-			State::<T, I>::put(
-				BoundedVec::try_from(state).map_err(|_| SharedStateError::InputTooLong)?,
-			);
+		fn write(key: StateKey, state: Vec<u8>) -> Result<(), SharedStateError> {
+			let state =
+				StateOf::<T, I>::try_from(state).map_err(|_| SharedStateError::InputTooLong)?;
+			let entry = (<frame_system::Pallet<T>>::block_number(), state);
+			States::<T, I>::mutate(key, |history| {
+				// Drops the oldest state when the history is full
+				let _ = history.force_insert_keep_right(history.len(), entry);
+			});
+			Self::deposit_event(Event::<T, I>::StateUpdated { key });
 			Ok(())
 		}
+
+		fn write_weight(len: u32) -> Weight {
+			T::WeightInfo::write_state(len.min(T::MaxStateLength::get()))
+		}
+	}
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// The states kept for `key` decoded as `V`, along with the block they were written in,
+		/// oldest first. States that are not a `V` are skipped.
+		pub fn history_of<V: Decode>(key: StateKey) -> Vec<(BlockNumberFor<T>, V)> {
+			States::<T, I>::get(key)
+				.into_iter()
+				.filter_map(|(written_at, state)| {
+					Some((written_at, V::decode(&mut &state[..]).ok()?))
+				})
+				.collect()
+		}
 	}
 
+	/// The states written under each key.
 	#[pallet::storage]
-	#[pallet::getter(fn get_state)]
-	pub type State<T: Config<I>, I: 'static = ()> =
-		StorageValue<_, BoundedVec<u8, T::MaxStateLength>, ValueQuery>;
+	#[pallet::getter(fn get_history)]
+	pub type States<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, StateKey, HistoryOf<T, I>, ValueQuery>;
 }
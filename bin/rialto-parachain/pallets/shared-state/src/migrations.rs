@@ -0,0 +1,47 @@
+//! Storage migrations for the shared state pallet.
+
+use super::*;
+use frame_support::{
+	pallet_prelude::*,
+	storage_alias,
+	traits::{OnRuntimeUpgrade, StorageVersion},
+};
+use sp_std::marker::PhantomData;
+
+const LOG_TARGET: &str = "runtime::shared-state";
+
+pub mod v1 {
+	use super::*;
+
+	/// The single state kept before states were written by key.
+	#[storage_alias]
+	pub(crate) type State<T: Config<I>, I: 'static> =
+		StorageValue<Pallet<T, I>, BoundedVec<u8, <T as Config<I>>::MaxStateLength>, ValueQuery>;
+
+	/// Remove the single state kept before states were written by key.
+	///
+	/// The key the state belongs to is not known, and the solo chains write their states again
+	/// as they go, so it is dropped rather than moved.
+	pub struct RemoveUnkeyedState<T, I = ()>(PhantomData<(T, I)>);
+
+	impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for RemoveUnkeyedState<T, I> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T, I>::on_chain_storage_version();
+			if on_chain_version >= 1 {
+				log::info!(
+					target: LOG_TARGET,
+					"Skipping migration to v1, on-chain storage version is {:?}",
+					on_chain_version
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let had_state = State::<T, I>::exists();
+			State::<T, I>::kill();
+			log::info!(target: LOG_TARGET, "Removed the unkeyed state: {}", had_state);
+
+			StorageVersion::new(1).put::<Pallet<T, I>>();
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+	}
+}
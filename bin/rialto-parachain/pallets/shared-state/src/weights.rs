@@ -0,0 +1,50 @@
+//! Weights for `pallet_shared_state`
+//!
+//! Estimated for the RialtoParachain configuration (states of up to 2KiB, 8 of them kept per
+//! key) until the benchmarks are run on reference hardware. Regenerate them by running
+//! `./benchmark.sh` from the `src` dir on a node built with `--features=runtime-benchmarks`.
+
+// #![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+pub trait WeightInfo {
+	/// the `l` parameter stands for the length of the written state
+	fn write_state(l: u32) -> Weight;
+}
+
+/// Weight functions for `pallet_shared_state`.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: MillauSharedState States (r:1 w:1)
+	// Proof: MillauSharedState States (max_values: None, max_size: Some(16474), added: 18949, mode: MaxEncodedLen)
+	// System Events (r:0 w:1)
+	/// The range of component `l` is `[1, 2048]`.
+	fn write_state(l: u32) -> Weight {
+		Weight::from_parts(38_000_000, 18_949)
+			.saturating_add(Weight::from_parts(1_200, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: MillauSharedState States (r:1 w:1)
+	// Proof: MillauSharedState States (max_values: None, max_size: Some(16474), added: 18949, mode: MaxEncodedLen)
+	// System Events (r:0 w:1)
+	/// The range of component `l` is `[1, 2048]`.
+	fn write_state(l: u32) -> Weight {
+		Weight::from_parts(38_000_000, 18_949)
+			.saturating_add(Weight::from_parts(1_200, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
@@ -156,6 +156,10 @@ pub type UncheckedExtrinsic =
 	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, RuntimeCall, SignedExtra>;
+/// Migrations to apply on runtime upgrade.
+pub type Migrations = (
+	pallet_shared_state::migrations::v1::RemoveUnkeyedState<Runtime, pallet_shared_state::Instance1>,
+);
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -163,6 +167,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
 impl_opaque_keys! {
@@ -410,7 +415,7 @@ pub type XcmOriginToTransactDispatchOrigin = (
 
 parameter_types! {
 	/// The amount of weight an XCM operation takes. We don't care much about those values as we're on testnet.
-	pub const UnitWeightCost: Weight = Weight::from_parts(1_000_000, 1024);
+	pub const UnitWeightCost: Weight = bp_rialto_parachain::XCM_INSTRUCTION_WEIGHT;
 	// One UNIT buys 1 second of weight.
 	pub const WeightPrice: (MultiLocation, u128) = (MultiLocation::parent(), UNIT);
 	pub const MaxInstructions: u32 = 100;
@@ -582,22 +587,165 @@ match_types! {
 	pub type ChildSoloDLEChain: impl Contains<MultiLocation> = {
 		MultiLocation { parents: 1,
 		// Expect any bridge. This might be fine for now, but may need thought later
-		interior: X1(GlobalConsensus(_)) }
+		interior: X1(GlobalConsensus(_)) } |
+		// Pallets of bridged chains, the x-chain pallet decides which ones to trust
+		MultiLocation { parents: 1, interior: X2(GlobalConsensus(_), PalletInstance(_)) }
 	};
 }
+parameter_types! {
+	pub const MillauLogicProviderPalletName: &'static str = bp_millau::LOGIC_PROVIDER_PALLET_NAME;
+	/// The location Millau sends the results of its logic provider from.
+	pub MillauLogicProviderLocation: MultiLocation = MultiLocation::new(
+		1,
+		X2(
+			GlobalConsensus(MillauNetwork::get()),
+			PalletInstance(bp_millau::LOGIC_PROVIDER_PALLET_INDEX),
+		),
+	);
+	/// Results of a source may arrive up to 64 nonces out of order.
+	pub const XChainReplayWindow: u32 = 64;
+}
+
 impl pallet_x_chain::Config<pallet_x_chain::Instance1> for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type SharedStateAccess = MillauSharedState;
 	type CrossChainOrigin = EnsureXcm<ChildSoloDLEChain>;
+	type AllowListOrigin = EnsureRoot<AccountId>;
+	type BridgedChain = bp_millau::Millau;
+	type BridgedHeaderChain = BridgeMillauGrandpa;
+	type ResultsStorage = bp_millau::OutboundResultsKeyProvider;
+	type ResultsPalletName = MillauLogicProviderPalletName;
+	type ResultsSource = MillauLogicProviderLocation;
+	type ReplayWindow = XChainReplayWindow;
+	type OnResultReceived = ();
+	type RequestOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+	type RequestSender = ToMillauRequestSender;
+	type WeightInfo = pallet_x_chain::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = XChainBenchmarkHelper;
+}
+
+/// Exports the XCM messages sent to Millau over the Millau bridge.
+pub type ToMillauRouter =
+	xcm_builder::UnpaidLocalExporter<millau_messages::ToMillauBlobExporter, UniversalLocation>;
+
+/// Sends the requests of the x-chain pallet to the metadata requests pallet of Millau, over the
+/// Millau bridge.
+pub struct ToMillauRequestSender;
+
+impl pallet_x_chain::SendRequest<bp_millau::BlockNumber, bp_millau::Balance>
+	for ToMillauRequestSender
+{
+	fn send_request(
+		requester: InteriorMultiLocation,
+		source: Vec<u8>,
+		lifetime: bp_millau::BlockNumber,
+		reward: bp_millau::Balance,
+	) -> Result<XcmHash, SendError> {
+		use codec::Encode;
+
+		let call = bp_millau::Call::MetadataRequests(
+			bp_millau::MetadataRequestsCall::request_from_location { source, lifetime, reward },
+		);
+		// The call is dispatched from the location of the requester, so that Millau escrows the
+		// reward from its sovereign account and sends the result back to this chain.
+		let xcm: Xcm<()> = vec![
+			Instruction::DescendOrigin(requester),
+			Instruction::Transact {
+				origin_kind: OriginKind::Xcm,
+				require_weight_at_most: bp_millau::MAX_METADATA_REQUEST_WEIGHT,
+				call: call.encode().into(),
+			},
+		]
+		.into();
+
+		let dest = MultiLocation::new(1, X1(GlobalConsensus(MillauNetwork::get())));
+		send_xcm::<ToMillauRouter>(dest, xcm).map(|(message_hash, _)| message_hash)
+	}
+}
+
+/// Proves results against Millau headers imported for the benchmarks of the x-chain pallet.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct XChainBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_x_chain::BenchmarkHelper<RuntimeOrigin, bp_millau::Hash> for XChainBenchmarkHelper {
+	fn cross_chain_origin() -> RuntimeOrigin {
+		pallet_xcm::Origin::Xcm(MillauLogicProviderLocation::get()).into()
+	}
+
+	fn request_origin() -> RuntimeOrigin {
+		use bridge_runtime_common::messages_xcm_extension::XcmBlobHauler;
+
+		pallet_bridge_messages::OutboundLanes::<Runtime, WithMillauMessagesInstance>::insert(
+			millau_messages::ToMillauXcmBlobHauler::xcm_lane(),
+			bp_messages::OutboundLaneData::opened(),
+		);
+		RuntimeOrigin::signed(AccountId::from([1; 32]))
+	}
+
+	fn prepare_result_proof(
+		result: &bin_types::OutboundResult,
+		proof_size: u32,
+	) -> (bp_millau::Hash, bp_runtime::UnverifiedStorageProof) {
+		use bp_runtime::{
+			HasherOf, StorageMapKeyProvider, UnverifiedStorageProof, UnverifiedStorageProofParams,
+		};
+		use codec::Encode;
+		use sp_runtime::traits::Header;
+		use sp_trie::{LayoutV0, MemoryDB, TrieDBMutBuilder, TrieMut};
+
+		let key = bp_millau::OutboundResultsKeyProvider::final_key(
+			bp_millau::LOGIC_PROVIDER_PALLET_NAME,
+			&result.payload.metadata_id,
+		)
+		.0;
+		// The result is decoded from the start of the value, the rest only inflates the proof
+		let value = bp_runtime::grow_storage_value(
+			result.encode(),
+			&UnverifiedStorageProofParams::from_db_size(proof_size),
+		);
+
+		let mut root = Default::default();
+		let mut mdb = MemoryDB::<HasherOf<bp_millau::Millau>>::default();
+		{
+			let mut trie =
+				TrieDBMutBuilder::<LayoutV0<HasherOf<bp_millau::Millau>>>::new(&mut mdb, &mut root)
+					.build();
+			trie.insert(&key, &value).expect("TrieMut::insert should not fail in benchmarks");
+		}
+		let storage_proof = UnverifiedStorageProof::try_from_db::<HasherOf<bp_millau::Millau>, _>(
+			&mdb,
+			root,
+			vec![key],
+		)
+		.expect("UnverifiedStorageProof::try_from_db() should not fail in benchmarks");
+
+		let header = bp_millau::Header::new(
+			Default::default(),
+			Default::default(),
+			root,
+			Default::default(),
+			Default::default(),
+		);
+		let header_hash = header.hash();
+		pallet_bridge_grandpa::initialize_for_benchmarks::<Runtime, MillauGrandpaInstance>(header);
+		(header_hash, storage_proof)
+	}
 }
 
 parameter_types! {
 	/// Set the max state length to be 2KiB.
 	pub const MaxStateLength: u32 = 2048;
+	/// Keep the last 8 states written for each key.
+	pub const MaxHistoryLength: u32 = 8;
 }
 
 impl pallet_shared_state::Config<pallet_shared_state::Instance1> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
 	type MaxStateLength = MaxStateLength;
+	type MaxHistoryLength = MaxHistoryLength;
+	type WeightInfo = pallet_shared_state::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -627,8 +775,8 @@ construct_runtime!(
 		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
 		BridgeMillauMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>},
 
-		MillauSharedState: pallet_shared_state::<Instance1>::{Pallet, Storage},
-		XChain: pallet_x_chain::<Instance1>::{Pallet, Call, Storage, Event<T>} = 200
+		MillauSharedState: pallet_shared_state::<Instance1>::{Pallet, Storage, Event<T>},
+		XChain: pallet_x_chain::<Instance1>::{Pallet, Call, Storage, Event<T>, Config<T>} = 200
 	}
 );
 
@@ -755,6 +903,15 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_shared_state_runtime_api::SharedStateApi<Block, bin_types::CrossChainResult>
+		for Runtime
+	{
+		fn latest_state(key: bin_traits::StateKey) -> Option<bin_types::CrossChainResult> {
+			use bin_traits::SharedState;
+			MillauSharedState::read_typed(key)
+		}
+	}
+
 	impl bp_millau::MillauFinalityApi<Block> for Runtime {
 		fn best_finalized() -> Option<HeaderId<bp_millau::Hash, bp_millau::BlockNumber>> {
 			BridgeMillauGrandpa::best_finalized()
@@ -788,11 +945,25 @@ impl_runtime_apis! {
 
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
-		fn benchmark_metadata(_extra: bool) -> (
+		fn benchmark_metadata(extra: bool) -> (
 			Vec<frame_benchmarking::BenchmarkList>,
 			Vec<frame_support::traits::StorageInfo>,
 		) {
-			todo!("TODO: fix or remove")
+			use frame_benchmarking::{Benchmarking, BenchmarkList, list_benchmark};
+			use frame_support::traits::StorageInfoTrait;
+			use frame_system_benchmarking::Pallet as SystemBench;
+
+			let mut list = Vec::<BenchmarkList>::new();
+
+			list_benchmark!(list, extra, frame_system, SystemBench::<Runtime>);
+			list_benchmark!(list, extra, pallet_balances, Balances);
+			list_benchmark!(list, extra, pallet_timestamp, Timestamp);
+			list_benchmark!(list, extra, pallet_shared_state, MillauSharedState);
+			list_benchmark!(list, extra, pallet_x_chain, XChain);
+
+			let storage_info = AllPalletsWithSystem::storage_info();
+
+			(list, storage_info)
 		}
 
 		fn dispatch_benchmark(
@@ -822,6 +993,8 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_shared_state, MillauSharedState);
+			add_benchmark!(params, batches, pallet_x_chain, XChain);
 
 			Ok(batches)
 		}
@@ -964,6 +1137,311 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn indirect_x_chain_calls_match_runtime_calls() {
+		let result = bin_types::CrossChainResult::new(
+			42,
+			[1; 32].into(),
+			bin_types::RoundInfo { settled_at: 100, participants: 3, agreeing: 2 },
+		);
+		let call = RuntimeCall::XChain(pallet_x_chain::Call::submit_result { result });
+		let indirect_call = bp_rialto_parachain::Call::XChain(
+			bp_rialto_parachain::XChainCall::submit_result { result },
+		);
+		assert_eq!(call.encode(), indirect_call.encode());
+	}
+
+	#[test]
+	fn x_chain_only_accepts_fresh_results_from_allowed_sources() {
+		use frame_support::{assert_noop, assert_ok};
+		type Error = pallet_x_chain::Error<Runtime, pallet_x_chain::Instance1>;
+
+		new_test_ext().execute_with(|| {
+			let source = MillauLogicProviderLocation::get();
+			let other_pallet = MultiLocation::new(
+				1,
+				X2(GlobalConsensus(MillauNetwork::get()), PalletInstance(42)),
+			);
+			let mut result = bin_types::CrossChainResult::new(
+				42,
+				[1; 32].into(),
+				bin_types::RoundInfo { settled_at: 100, participants: 3, agreeing: 2 },
+			);
+			let origin = |location| RuntimeOrigin::from(pallet_xcm::Origin::Xcm(location));
+
+			assert_noop!(XChain::submit_result(origin(source), result), Error::SourceNotAllowed);
+			assert_ok!(XChain::allow_source(RuntimeOrigin::root(), Box::new(source)));
+			assert_ok!(XChain::submit_result(origin(source), result));
+			assert_eq!(XChain::received_nonces(source).into_inner(), vec![0]);
+
+			// Replays and results from other pallets of the same chain are rejected
+			assert_noop!(XChain::submit_result(origin(source), result), Error::AlreadyReceived);
+			result.nonce = 1;
+			assert_noop!(
+				XChain::submit_result(origin(other_pallet), result),
+				Error::SourceNotAllowed
+			);
+			assert_ok!(XChain::submit_result(origin(source), result));
+
+			// The nonces are remembered while the source is disallowed
+			assert_ok!(XChain::disallow_source(RuntimeOrigin::root(), Box::new(source)));
+			assert_noop!(XChain::submit_result(origin(source), result), Error::SourceNotAllowed);
+			assert_ok!(XChain::allow_source(RuntimeOrigin::root(), Box::new(source)));
+			assert_noop!(XChain::submit_result(origin(source), result), Error::AlreadyReceived);
+
+			// Nonces that fell out of the replay window are rejected
+			for nonce in 3..=XChainReplayWindow::get() as u64 + 2 {
+				result.nonce = nonce;
+				assert_ok!(XChain::submit_result(origin(source), result));
+			}
+			result.nonce = 2;
+			assert_noop!(XChain::submit_result(origin(source), result), Error::StaleNonce);
+		});
+	}
+
+	#[test]
+	fn submit_result_fits_in_the_weight_millau_pays_for() {
+		use frame_support::dispatch::GetDispatchInfo;
+
+		let result = bin_types::CrossChainResult::new(
+			42,
+			[1; 32].into(),
+			bin_types::RoundInfo { settled_at: 100, participants: 3, agreeing: 2 },
+		);
+		// Includes the write to the shared state and the `max_weight` of the handlers
+		let dispatch_weight =
+			RuntimeCall::XChain(pallet_x_chain::Call::submit_result { result })
+				.get_dispatch_info()
+				.weight;
+		assert!(
+			dispatch_weight.all_lte(bp_rialto_parachain::SUBMIT_RESULT_DISPATCH_WEIGHT),
+			"submit_result weighs {dispatch_weight}, more than the {} Millau pays for",
+			bp_rialto_parachain::SUBMIT_RESULT_DISPATCH_WEIGHT,
+		);
+	}
+
+	#[test]
+	fn shared_state_keeps_the_latest_states_of_each_key() {
+		use bin_traits::SharedState;
+
+		type States = MillauSharedState;
+		new_test_ext().execute_with(|| {
+			for block in 1..=MaxHistoryLength::get() + 2 {
+				System::set_block_number(block);
+				assert!(States::write_typed(1, &block).is_ok());
+			}
+			assert!(States::write_typed(2, &42u32).is_ok());
+
+			let history = States::history_of::<u32>(1);
+			assert_eq!(history.len() as u32, MaxHistoryLength::get());
+			assert_eq!(history.first(), Some(&(3, 3)));
+			assert_eq!(States::read_typed::<u32>(1), Some(MaxHistoryLength::get() + 2));
+			assert_eq!(States::read_typed::<u32>(2), Some(42));
+			assert_eq!(States::read_typed::<u32>(3), None);
+		});
+	}
+
+	// The state root of a Millau state holding `entry` in the ledger of its logic provider, along
+	// with a proof of the entry
+	fn prove_outbound_result(
+		entry: &bin_types::OutboundResult,
+	) -> (bp_millau::Hash, bp_runtime::UnverifiedStorageProof) {
+		use bp_runtime::{HasherOf, StorageMapKeyProvider, UnverifiedStorageProof};
+
+		let key = bp_millau::OutboundResultsKeyProvider::final_key(
+			bp_millau::LOGIC_PROVIDER_PALLET_NAME,
+			&entry.payload.metadata_id,
+		);
+		UnverifiedStorageProof::try_from_entries::<HasherOf<bp_millau::Millau>>(
+			bp_millau::Millau::STATE_VERSION,
+			&[(key.0, Some(entry.encode()))],
+		)
+		.unwrap()
+	}
+
+	// Import a finalized Millau header with `state_root`, returning its hash
+	fn import_millau_header(number: u64, state_root: bp_millau::Hash) -> bp_millau::Hash {
+		let header_hash = bp_millau::Hash::repeat_byte(number as u8);
+		pallet_bridge_grandpa::ImportedHeaders::<Runtime, MillauGrandpaInstance>::insert(
+			header_hash,
+			bp_header_chain::StoredHeaderData { number, state_root },
+		);
+		header_hash
+	}
+
+	#[test]
+	fn x_chain_accepts_results_proven_against_millau_headers() {
+		use bin_traits::SharedState;
+		use bin_types::{CrossChainResult, DeliveryStatus, OutboundResult, RoundInfo};
+		use frame_support::{assert_noop, assert_ok};
+		type Error = pallet_x_chain::Error<Runtime, pallet_x_chain::Instance1>;
+
+		new_test_ext().execute_with(|| {
+			let relayer = RuntimeOrigin::signed(AccountId::from([1; 32]));
+			let source = MillauLogicProviderLocation::get();
+			let mut payload = CrossChainResult::new(
+				42,
+				sp_core::H256::repeat_byte(7),
+				RoundInfo { settled_at: 100, participants: 3, agreeing: 2 },
+			);
+			payload.nonce = 5;
+			let sent =
+				OutboundResult { payload, status: DeliveryStatus::Sent([1; 32]), attempts: 1 };
+			let pending = OutboundResult {
+				payload: CrossChainResult { metadata_id: 43, ..payload },
+				status: DeliveryStatus::Pending,
+				attempts: 1,
+			};
+
+			// The header has not been imported yet
+			let (state_root, proof) = prove_outbound_result(&sent);
+			assert_noop!(
+				XChain::submit_result_proof(
+					relayer.clone(),
+					bp_millau::Hash::repeat_byte(1),
+					42,
+					proof.clone()
+				),
+				Error::InvalidResultProof(bp_header_chain::HeaderChainError::UnknownHeader),
+			);
+			let header_hash = import_millau_header(1, state_root);
+
+			// Proven results go through the same checks as the submitted ones
+			assert_noop!(
+				XChain::submit_result_proof(relayer.clone(), header_hash, 42, proof.clone()),
+				Error::SourceNotAllowed,
+			);
+			assert_ok!(XChain::allow_source(RuntimeOrigin::root(), Box::new(source)));
+			let (pending_root, pending_proof) = prove_outbound_result(&pending);
+			let pending_header_hash = import_millau_header(2, pending_root);
+			assert_noop!(
+				XChain::submit_result_proof(relayer.clone(), pending_header_hash, 43, pending_proof),
+				Error::ResultNotSent,
+			);
+			assert_ok!(XChain::submit_result_proof(
+				relayer.clone(),
+				header_hash,
+				42,
+				proof.clone()
+			));
+			assert_eq!(MillauSharedState::read_typed::<CrossChainResult>(42), Some(payload));
+			assert_eq!(XChain::received_nonces(source).into_inner(), vec![5]);
+
+			// Neither the proof nor the message carrying the result can be replayed
+			assert_noop!(
+				XChain::submit_result_proof(relayer, header_hash, 42, proof),
+				Error::AlreadyReceived,
+			);
+			let origin = RuntimeOrigin::from(pallet_xcm::Origin::Xcm(source));
+			assert_noop!(XChain::submit_result(origin, payload), Error::AlreadyReceived);
+		});
+	}
+
+	#[test]
+	fn x_chain_accepts_results_proven_out_of_order() {
+		use bin_traits::SharedState;
+		use bin_types::{CrossChainResult, DeliveryStatus, OutboundResult, RoundInfo};
+		use frame_support::{assert_noop, assert_ok};
+		type Error = pallet_x_chain::Error<Runtime, pallet_x_chain::Instance1>;
+
+		new_test_ext().execute_with(|| {
+			let relayer = RuntimeOrigin::signed(AccountId::from([1; 32]));
+			let source = MillauLogicProviderLocation::get();
+			let origin = RuntimeOrigin::from(pallet_xcm::Origin::Xcm(source));
+			assert_ok!(XChain::allow_source(RuntimeOrigin::root(), Box::new(source)));
+			let sent = |metadata_id, nonce| {
+				let mut payload = CrossChainResult::new(
+					metadata_id,
+					sp_core::H256::repeat_byte(metadata_id as u8),
+					RoundInfo { settled_at: 100, participants: 3, agreeing: 2 },
+				);
+				payload.nonce = nonce;
+				OutboundResult { payload, status: DeliveryStatus::Sent([1; 32]), attempts: 1 }
+			};
+			let (first, second) = (sent(42, 5), sent(43, 6));
+
+			// The second result is proven before the first one, which is still accepted
+			let (second_root, second_proof) = prove_outbound_result(&second);
+			let second_header_hash = import_millau_header(1, second_root);
+			assert_ok!(XChain::submit_result_proof(
+				relayer.clone(),
+				second_header_hash,
+				43,
+				second_proof
+			));
+			let (first_root, first_proof) = prove_outbound_result(&first);
+			let first_header_hash = import_millau_header(2, first_root);
+			assert_ok!(XChain::submit_result_proof(
+				relayer.clone(),
+				first_header_hash,
+				42,
+				first_proof.clone()
+			));
+			assert_eq!(MillauSharedState::read_typed::<CrossChainResult>(42), Some(first.payload));
+			assert_eq!(MillauSharedState::read_typed::<CrossChainResult>(43), Some(second.payload));
+			assert_eq!(XChain::received_nonces(source).into_inner(), vec![5, 6]);
+
+			// Each of them is received once, whichever way it arrives
+			assert_noop!(
+				XChain::submit_result_proof(relayer, first_header_hash, 42, first_proof),
+				Error::AlreadyReceived,
+			);
+			assert_noop!(
+				XChain::submit_result(origin.clone(), first.payload),
+				Error::AlreadyReceived
+			);
+			assert_noop!(XChain::submit_result(origin, second.payload), Error::AlreadyReceived);
+		});
+	}
+
+	#[test]
+	fn x_chain_requests_are_sent_to_millau() {
+		use frame_support::{assert_noop, assert_ok};
+
+		new_test_ext().execute_with(|| {
+			let requester = AccountId::from([1; 32]);
+			let lane_id = crate::millau_messages::ToMillauXcmBlobHauler::xcm_lane();
+			let request = |origin| {
+				XChain::request_processing(
+					origin,
+					b"http://localhost:8000/metadatas".to_vec(),
+					100,
+					1_000,
+				)
+			};
+
+			assert_noop!(request(RuntimeOrigin::root()), sp_runtime::DispatchError::BadOrigin);
+			// Nothing is sent before the bridge lane is opened
+			assert_noop!(
+				request(RuntimeOrigin::signed(requester.clone())),
+				pallet_x_chain::Error::<Runtime, pallet_x_chain::Instance1>::RequestNotSent,
+			);
+
+			OutboundLanes::<Runtime, WithMillauMessagesInstance>::insert(
+				lane_id,
+				OutboundLaneData::opened(),
+			);
+			assert_ok!(request(RuntimeOrigin::signed(requester.clone())));
+			assert_eq!(
+				OutboundLanes::<Runtime, WithMillauMessagesInstance>::get(lane_id)
+					.unwrap()
+					.latest_generated_nonce,
+				1,
+			);
+			let location = MultiLocation::new(
+				0,
+				X1(AccountId32 { network: Some(RelayNetwork::get()), id: requester.into() }),
+			);
+			assert!(matches!(
+				System::events().last().map(|record| &record.event),
+				Some(RuntimeEvent::XChain(pallet_x_chain::Event::ProcessingRequested {
+					requester: sent_by,
+					..
+				})) if *sent_by == location
+			));
+		});
+	}
+
 	#[test]
 	fn ensure_signed_extension_definition_is_correct() {
 		let payload: SignedExtra = (
@@ -0,0 +1,89 @@
+//! Benchmarking setup for pallet-x-chain
+
+use super::*;
+use bin_traits::SharedState;
+use bin_types::{CrossChainResult, DeliveryStatus, OutboundResult, RoundInfo};
+use frame_benchmarking::{instance_benchmarks, whitelisted_caller};
+use frame_support::{
+	traits::{EnsureOrigin, Get},
+	BoundedVec,
+};
+use frame_system::RawOrigin;
+use sp_core::H256;
+use sp_std::boxed::Box;
+use xcm::latest::prelude::*;
+
+/// A location to allow or disallow.
+fn source() -> MultiLocation {
+	MultiLocation::new(1, X1(Parachain(2000)))
+}
+
+/// The nonces of a source whose replay window is full, so that receiving a result drops one.
+fn full_window<T: Config<I>, I: 'static>() -> BoundedVec<u64, T::ReplayWindow> {
+	BoundedVec::truncate_from((1..=T::ReplayWindow::get() as u64).collect())
+}
+
+instance_benchmarks! {
+	submit_result {
+		let origin = T::BenchmarkHelper::cross_chain_origin();
+		let source = T::CrossChainOrigin::ensure_origin(origin.clone())
+			.map_err(|_| "the helper's origin is not a cross chain origin")?;
+		AllowedSources::<T, I>::insert(source, ());
+		ReceivedNonces::<T, I>::insert(source, full_window::<T, I>());
+		let mut result = CrossChainResult::new(
+			1,
+			H256::repeat_byte(1),
+			RoundInfo { settled_at: 1, participants: 3, agreeing: 3 },
+		);
+		result.nonce = T::ReplayWindow::get() as u64 + 1;
+	}: _<T::RuntimeOrigin>(origin, result)
+	verify {
+		assert_eq!(T::SharedStateAccess::read_typed::<CrossChainResult>(1), Some(result));
+		assert_eq!(ReceivedNonces::<T, I>::get(source).last(), Some(&result.nonce));
+	}
+
+	// Prove a result with a storage proof of about `p` bytes.
+	submit_result_proof {
+		let p in 64 .. 16 * 1024;
+		let caller: T::AccountId = whitelisted_caller();
+		let source = T::ResultsSource::get();
+		AllowedSources::<T, I>::insert(source, ());
+		ReceivedNonces::<T, I>::insert(source, full_window::<T, I>());
+		let mut payload = CrossChainResult::new(
+			1,
+			H256::repeat_byte(1),
+			RoundInfo { settled_at: 1, participants: 3, agreeing: 3 },
+		);
+		payload.nonce = T::ReplayWindow::get() as u64 + 1;
+		let sent =
+			OutboundResult { payload, status: DeliveryStatus::Sent([1; 32]), attempts: 1 };
+		let (at, storage_proof) = T::BenchmarkHelper::prepare_result_proof(&sent, p);
+	}: _(RawOrigin::Signed(caller), at, 1, storage_proof)
+	verify {
+		assert_eq!(T::SharedStateAccess::read_typed::<CrossChainResult>(1), Some(payload));
+		assert_eq!(ReceivedNonces::<T, I>::get(source).last(), Some(&payload.nonce));
+	}
+
+	allow_source {
+		let origin = T::AllowListOrigin::try_successful_origin()
+			.map_err(|_| "no origin can manage the allow-list")?;
+	}: _<T::RuntimeOrigin>(origin, Box::new(source()))
+	verify {
+		assert!(AllowedSources::<T, I>::contains_key(source()));
+	}
+
+	disallow_source {
+		let origin = T::AllowListOrigin::try_successful_origin()
+			.map_err(|_| "no origin can manage the allow-list")?;
+		AllowedSources::<T, I>::insert(source(), ());
+	}: _<T::RuntimeOrigin>(origin, Box::new(source()))
+	verify {
+		assert!(!AllowedSources::<T, I>::contains_key(source()));
+	}
+
+	// The call fails unless the request was sent.
+	request_processing {
+		let origin = T::BenchmarkHelper::request_origin();
+		let source = b"http://localhost:8000/metadatas".to_vec();
+	}: _<T::RuntimeOrigin>(origin, source, 100u32.into(), 1_000u32.into())
+}
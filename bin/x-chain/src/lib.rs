@@ -1,18 +1,29 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub mod types;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
 
 /// Edit this file to define custom logic or remove it if it is not needed.
 /// Learn more about FRAME and the core library of Substrate FRAME pallets:
 /// <https://docs.substrate.io/v3/runtime/frame>
 pub use pallet::*;
+pub use weights::*;
 
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 	use bin_traits::{SharedState, SharedStateError};
-	use frame_support::{dispatch::Vec, pallet_prelude::*};
+	use bin_types::{CrossChainResult, OutboundResult};
+	use bp_header_chain::{HeaderChain, HeaderChainError};
+	use bp_runtime::{
+		BalanceOf, BlockNumberOf, Chain, HashOf, Size, StorageMapKeyProvider,
+		UnverifiedStorageProof,
+	};
+	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
+	use sp_core::H256;
+	use sp_std::{boxed::Box, vec::Vec};
 	use xcm::latest::prelude::*;
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -20,26 +31,198 @@ pub mod pallet {
 	pub trait Config<I: 'static = ()>: frame_system::Config {
 		type RuntimeEvent: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type SharedStateAccess: SharedState<I>;
-		/// Origins we allow to respond with a query.
+		/// Origins we allow to respond with a query. Only the locations in `AllowedSources` may
+		/// actually submit results.
 		type CrossChainOrigin: EnsureOrigin<
 			<Self as frame_system::Config>::RuntimeOrigin,
 			Success = MultiLocation,
 		>;
+		/// Origin managing `AllowedSources`, e.g. root or governance.
+		type AllowListOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+		/// The chain whose results can be proven through `submit_result_proof`.
+		type BridgedChain: Chain;
+		/// The finalized headers of `BridgedChain`, e.g. a `pallet-bridge-grandpa` instance.
+		type BridgedHeaderChain: HeaderChain<Self::BridgedChain>;
+		/// The map `BridgedChain` keeps the results it sent in, by metadata id.
+		type ResultsStorage: StorageMapKeyProvider<Key = u64, Value = OutboundResult>;
+		/// Name of the pallet holding `ResultsStorage` at `BridgedChain`.
+		type ResultsPalletName: Get<&'static str>;
+		/// The source that results proven through `submit_result_proof` are accepted from, i.e.
+		/// the location the pallet holding `ResultsStorage` sends its results from. The proven
+		/// results share its nonces with the ones it sends through `submit_result`.
+		type ResultsSource: Get<MultiLocation>;
+		/// How many of the highest nonces received from a source are remembered, at least one.
+		/// Results may arrive in any order as long as their nonce is above the oldest one
+		/// remembered, e.g. when a later result is proven before an earlier one is delivered.
+		#[pallet::constant]
+		type ReplayWindow: Get<u32>;
+		/// Notified of every result received through `submit_result` or `submit_result_proof`,
+		/// e.g. a tuple of pallets.
+		type OnResultReceived: OnResultReceived;
+		/// Origins allowed to request the processing of metadata at `BridgedChain`, e.g.
+		/// `EnsureXcmOrigin`. The location they resolve to must be interior to this chain, the
+		/// result is sent back to it.
+		type RequestOrigin: EnsureOrigin<
+			<Self as frame_system::Config>::RuntimeOrigin,
+			Success = MultiLocation,
+		>;
+		/// Sends the requests made through `request_processing` to `BridgedChain`.
+		type RequestSender: SendRequest<
+			BlockNumberOf<Self::BridgedChain>,
+			BalanceOf<Self::BridgedChain>,
+		>;
+		type WeightInfo: WeightInfo;
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::RuntimeOrigin, HashOf<Self::BridgedChain>>;
+	}
+
+	/// Prepares what the benchmarks of this pallet need from the runtime.
+	#[cfg(feature = "runtime-benchmarks")]
+	pub trait BenchmarkHelper<Origin, BridgedHash> {
+		/// An origin accepted by `CrossChainOrigin`.
+		fn cross_chain_origin() -> Origin;
+
+		/// Import a finalized bridged header whose state holds `result` in `ResultsStorage`.
+		/// Returns its hash, along with a storage proof of the result of about `proof_size` bytes.
+		fn prepare_result_proof(
+			result: &OutboundResult,
+			proof_size: u32,
+		) -> (BridgedHash, UnverifiedStorageProof);
+
+		/// An origin accepted by `RequestOrigin`, whose requests `RequestSender` is able to send,
+		/// e.g. with the bridge lane opened.
+		fn request_origin() -> Origin;
+	}
+
+	/// Sends metadata requests to the logic providers of the bridged chain.
+	pub trait SendRequest<BlockNumber, Balance> {
+		/// Ask the bridged chain to process the metadata found at `source` on behalf of
+		/// `requester`, within `lifetime` of its blocks, paying `reward` from the sovereign account
+		/// of `requester` there. Returns the hash of the sent message.
+		fn send_request(
+			requester: InteriorMultiLocation,
+			source: Vec<u8>,
+			lifetime: BlockNumber,
+			reward: Balance,
+		) -> Result<XcmHash, SendError>;
+	}
+
+	impl<BlockNumber, Balance> SendRequest<BlockNumber, Balance> for () {
+		fn send_request(
+			_requester: InteriorMultiLocation,
+			_source: Vec<u8>,
+			_lifetime: BlockNumber,
+			_reward: Balance,
+		) -> Result<XcmHash, SendError> {
+			Err(SendError::NotApplicable)
+		}
+	}
+
+	/// Hook for parachain pallets running their business logic on the results received from the
+	/// bridged chain.
+	pub trait OnResultReceived {
+		/// The most `on_result_received` may consume, charged upfront by `submit_result` and
+		/// `submit_result_proof`.
+		fn max_weight() -> Weight;
+
+		/// Called once `result` has been written to the shared state. Returns the weight
+		/// consumed.
+		fn on_result_received(result: &CrossChainResult) -> Weight;
+	}
+
+	#[impl_trait_for_tuples::impl_for_tuples(30)]
+	impl OnResultReceived for Tuple {
+		fn max_weight() -> Weight {
+			let mut weight = Weight::zero();
+			for_tuples!( #( weight = weight.saturating_add(Tuple::max_weight()); )* );
+			weight
+		}
+
+		fn on_result_received(result: &CrossChainResult) -> Weight {
+			let mut weight = Weight::zero();
+			for_tuples!( #( weight = weight.saturating_add(Tuple::on_result_received(result)); )* );
+			weight
+		}
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	pub struct Pallet<T, I = ()>(_);
 
+	/// Locations allowed to submit results through `submit_result`.
+	#[pallet::storage]
+	pub type AllowedSources<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MultiLocation, ()>;
+
+	/// The highest `ReplayWindow` nonces received from every source, in ascending order. Kept when
+	/// a source is disallowed, so that its old results can't be replayed once it is allowed again.
+	#[pallet::storage]
+	#[pallet::getter(fn received_nonces)]
+	pub type ReceivedNonces<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		MultiLocation,
+		BoundedVec<u64, T::ReplayWindow>,
+		ValueQuery,
+	>;
+
+	#[pallet::genesis_config]
+	#[derive(DefaultNoBound)]
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+		/// Locations allowed to submit results from the start.
+		pub allowed_sources: Vec<MultiLocation>,
+		/// Dummy marker.
+		pub phantom: sp_std::marker::PhantomData<(T, I)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config<I>, I: 'static> BuildGenesisConfig for GenesisConfig<T, I> {
+		fn build(&self) {
+			for source in &self.allowed_sources {
+				AllowedSources::<T, I>::insert(source, ());
+			}
+		}
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub (super) fn deposit_event)]
 	pub enum Event<T: Config<I>, I: 'static = ()> {
-		ExecutedTransactWith(Vec<u8>),
+		/// A result was received from `source` and written to the shared state.
+		ResultReceived { source: MultiLocation, result: CrossChainResult },
+		/// The result of `metadata_id` was proven against the bridged header `at`, and received
+		/// from `ResultsSource`.
+		ResultProven { metadata_id: u64, result_hash: H256, at: HashOf<T::BridgedChain> },
+		/// `source` may now submit results.
+		SourceAllowed { source: MultiLocation },
+		/// Results from `source` are no longer accepted.
+		SourceDisallowed { source: MultiLocation },
+		/// `requester` asked the bridged chain to process metadata, in the message `message_hash`.
+		ProcessingRequested { requester: MultiLocation, message_hash: XcmHash },
 	}
 
 	#[pallet::error]
 	pub enum Error<T, I = ()> {
 		InputTooLong,
+		/// The result was encoded in a version this pallet does not understand.
+		UnsupportedVersion,
+		/// The bridged chain has not settled a result for this metadata at the given header.
+		ResultNotSettled,
+		/// The bridged chain has not sent the result of this metadata at the given header yet, so
+		/// it has no nonce.
+		ResultNotSent,
+		/// The header is unknown or the storage proof does not match its state root.
+		InvalidResultProof(HeaderChainError),
+		/// The origin of the result is not in `AllowedSources`.
+		SourceNotAllowed,
+		/// The nonce is below the highest `ReplayWindow` nonces received from the source, so the
+		/// result can no longer be told apart from a replay.
+		StaleNonce,
+		/// Requests can only be made by locations of this chain.
+		BadRequester,
+		/// The request could not be sent to the bridged chain.
+		RequestNotSent,
+		/// The source already had a result with the same nonce accepted, so this one is a replay.
+		AlreadyReceived,
 	}
 
 	impl<T, I> From<SharedStateError> for Error<T, I> {
@@ -52,13 +235,170 @@ pub mod pallet {
 
 	#[pallet::call]
 	impl<T: Config<I>, I: 'static> Pallet<T, I> {
-		// Todo: benchmarking for this 🤔
-		#[pallet::weight(0)]
-		pub fn placeholder(origin: OriginFor<T>, proof: Vec<u8>) -> DispatchResult {
-			let _loc = T::CrossChainOrigin::ensure_origin(origin)?;
-			T::SharedStateAccess::write(proof.clone()).map_err(|e| -> Error<T, I> { e.into() })?;
-			Self::deposit_event(Event::<T, I>::ExecutedTransactWith(proof));
+		/// Store a result sent from the bridged chain in the shared state, and hand it to
+		/// `OnResultReceived`. The result has to come from one of the `AllowedSources`, with a
+		/// nonce it did not use yet, within the `ReplayWindow` of the source.
+		#[pallet::weight(
+			Pallet::<T, I>::submit_result_weight().saturating_add(T::OnResultReceived::max_weight())
+		)]
+		pub fn submit_result(
+			origin: OriginFor<T>,
+			result: CrossChainResult,
+		) -> DispatchResultWithPostInfo {
+			let source = T::CrossChainOrigin::ensure_origin(origin)?;
+			let handlers_weight = Self::receive_result(source, result)?;
+			Ok(Some(Self::submit_result_weight().saturating_add(handlers_weight)).into())
+		}
+
+		/// Receive the result the bridged chain sent for `metadata_id`, as proven by
+		/// `storage_proof` against its finalized header `at`, the same way as if `ResultsSource`
+		/// submitted it. Can be called by anyone, e.g. a relayer, since the proof is what is
+		/// trusted rather than the origin.
+		#[pallet::weight(
+			Pallet::<T, I>::submit_result_proof_weight(storage_proof.size())
+				.saturating_add(T::OnResultReceived::max_weight())
+		)]
+		pub fn submit_result_proof(
+			origin: OriginFor<T>,
+			at: HashOf<T::BridgedChain>,
+			metadata_id: u64,
+			storage_proof: UnverifiedStorageProof,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let proof_size = storage_proof.size();
+
+			let mut storage = T::BridgedHeaderChain::verify_storage_proof(at, storage_proof)
+				.map_err(Error::<T, I>::InvalidResultProof)?;
+			let key = T::ResultsStorage::final_key(T::ResultsPalletName::get(), &metadata_id);
+			let sent = storage
+				.get_and_decode_optional::<OutboundResult>(&key)
+				.and_then(|sent| {
+					storage.ensure_no_unused_keys()?;
+					Ok(sent)
+				})
+				.map_err(|e| Error::<T, I>::InvalidResultProof(HeaderChainError::StorageProof(e)))?
+				.ok_or(Error::<T, I>::ResultNotSettled)?;
+			ensure!(sent.was_sent(), Error::<T, I>::ResultNotSent);
+
+			let handlers_weight = Self::receive_result(T::ResultsSource::get(), sent.payload)?;
+			let result_hash = sent.payload.result_hash;
+			Self::deposit_event(Event::<T, I>::ResultProven { metadata_id, result_hash, at });
+			Ok(Some(Self::submit_result_proof_weight(proof_size).saturating_add(handlers_weight))
+				.into())
+		}
+
+		/// Accept results from `source`.
+		#[pallet::weight(T::WeightInfo::allow_source())]
+		pub fn allow_source(origin: OriginFor<T>, source: Box<MultiLocation>) -> DispatchResult {
+			T::AllowListOrigin::ensure_origin(origin)?;
+			AllowedSources::<T, I>::insert(*source, ());
+			Self::deposit_event(Event::<T, I>::SourceAllowed { source: *source });
 			Ok(())
 		}
+
+		/// Stop accepting results from `source`.
+		#[pallet::weight(T::WeightInfo::disallow_source())]
+		pub fn disallow_source(
+			origin: OriginFor<T>,
+			source: Box<MultiLocation>,
+		) -> DispatchResult {
+			T::AllowListOrigin::ensure_origin(origin)?;
+			ensure!(AllowedSources::<T, I>::contains_key(*source), Error::<T, I>::SourceNotAllowed);
+			AllowedSources::<T, I>::remove(*source);
+			Self::deposit_event(Event::<T, I>::SourceDisallowed { source: *source });
+			Ok(())
+		}
+
+		/// Ask the logic providers of the bridged chain to process the metadata found at
+		/// `source`. The request stays open for `lifetime` bridged blocks and pays out `reward`,
+		/// which the sovereign account of the requester at the bridged chain has to hold. The
+		/// result is sent back once settled, through `submit_result`.
+		#[pallet::weight(T::WeightInfo::request_processing())]
+		pub fn request_processing(
+			origin: OriginFor<T>,
+			source: Vec<u8>,
+			lifetime: BlockNumberOf<T::BridgedChain>,
+			reward: BalanceOf<T::BridgedChain>,
+		) -> DispatchResult {
+			let requester = T::RequestOrigin::ensure_origin(origin)?;
+			Self::request(requester, source, lifetime, reward)
+		}
+	}
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Request the processing of the metadata found at `source` on behalf of `requester`, an
+		/// interior location of this chain, e.g. one of its pallets. See `request_processing`.
+		pub fn request(
+			requester: MultiLocation,
+			source: Vec<u8>,
+			lifetime: BlockNumberOf<T::BridgedChain>,
+			reward: BalanceOf<T::BridgedChain>,
+		) -> DispatchResult {
+			ensure!(requester.parents == 0, Error::<T, I>::BadRequester);
+			let message_hash =
+				T::RequestSender::send_request(requester.interior, source, lifetime, reward)
+					.map_err(|e| {
+						log::debug!(target: "runtime::x-chain", "Failed to send request: {:?}", e);
+						Error::<T, I>::RequestNotSent
+					})?;
+			Self::deposit_event(Event::<T, I>::ProcessingRequested { requester, message_hash });
+			Ok(())
+		}
+
+		/// Store `result` from `source` in the shared state and hand it to `OnResultReceived`. The
+		/// source has to be one of the `AllowedSources`, and the result has to be in a supported
+		/// version, with a nonce not received from the source yet. Returns the weight consumed by
+		/// the handlers.
+		fn receive_result(
+			source: MultiLocation,
+			result: CrossChainResult,
+		) -> Result<Weight, DispatchError> {
+			ensure!(AllowedSources::<T, I>::contains_key(source), Error::<T, I>::SourceNotAllowed);
+			ensure!(result.is_supported(), Error::<T, I>::UnsupportedVersion);
+			let received = Self::receive_nonce(source, result.nonce)?;
+
+			T::SharedStateAccess::write_typed(result.metadata_id, &result)
+				.map_err(|e| -> Error<T, I> { e.into() })?;
+			ReceivedNonces::<T, I>::insert(source, received);
+			Self::deposit_event(Event::<T, I>::ResultReceived { source, result });
+
+			Ok(T::OnResultReceived::on_result_received(&result)
+				.min(T::OnResultReceived::max_weight()))
+		}
+
+		/// The nonces received from `source` once `nonce` is, dropping the oldest one when the
+		/// window is full. Fails if `nonce` was received already or is older than the window.
+		fn receive_nonce(
+			source: MultiLocation,
+			nonce: u64,
+		) -> Result<BoundedVec<u64, T::ReplayWindow>, Error<T, I>> {
+			let mut received = ReceivedNonces::<T, I>::get(source);
+			let mut position =
+				received.binary_search(&nonce).err().ok_or(Error::<T, I>::AlreadyReceived)?;
+			if received.is_full() {
+				ensure!(position > 0, Error::<T, I>::StaleNonce);
+				received.remove(0);
+				position -= 1;
+			}
+			received.try_insert(position, nonce).map_err(|_| Error::<T, I>::StaleNonce)?;
+			Ok(received)
+		}
+
+		/// The weight of writing a result to the shared state, under a key whose history is full.
+		fn write_result_weight() -> Weight {
+			T::SharedStateAccess::write_weight(CrossChainResult::max_encoded_len() as u32)
+		}
+
+		/// The weight of `submit_result` without the `OnResultReceived` handlers.
+		fn submit_result_weight() -> Weight {
+			T::WeightInfo::submit_result().saturating_add(Self::write_result_weight())
+		}
+
+		/// The weight of `submit_result_proof` with a proof of `proof_size` bytes, without the
+		/// `OnResultReceived` handlers.
+		fn submit_result_proof_weight(proof_size: u32) -> Weight {
+			T::WeightInfo::submit_result_proof(proof_size)
+				.saturating_add(Self::write_result_weight())
+		}
 	}
 }
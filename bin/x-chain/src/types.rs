@@ -1,9 +0,0 @@
-use codec::{Decode, Encode};
-use frame_support::pallet_prelude::*;
-use scale_info::TypeInfo;
-
-#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, MaxEncodedLen, TypeInfo)]
-pub struct State {
-	pub int_one: u8,
-	pub int_two: u8,
-}
@@ -0,0 +1,133 @@
+//! Weights for `pallet_x_chain`
+//!
+//! Estimated for the RialtoParachain configuration until the benchmarks are run on reference
+//! hardware. Regenerate them by running `./benchmark.sh` from the `src` dir on a node built with
+//! `--features=runtime-benchmarks`.
+
+// #![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+pub trait WeightInfo {
+	/// Without the write to the shared state, which is charged through
+	/// `SharedState::write_weight`.
+	fn submit_result() -> Weight;
+	/// the `p` parameter stands for the size of the storage proof, in bytes. Without the write to
+	/// the shared state either.
+	fn submit_result_proof(p: u32) -> Weight;
+	fn allow_source() -> Weight;
+	fn disallow_source() -> Weight;
+	fn request_processing() -> Weight;
+}
+
+/// Weight functions for `pallet_x_chain`.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: XChain AllowedSources (r:1 w:0)
+	// Proof: XChain AllowedSources (max_values: None, max_size: Some(621), added: 3096, mode: MaxEncodedLen)
+	// Storage: XChain ReceivedNonces (r:1 w:1)
+	// Proof: XChain ReceivedNonces (max_values: None, max_size: Some(1135), added: 3610, mode: MaxEncodedLen)
+	// System Events (r:0 w:1)
+	fn submit_result() -> Weight {
+		Weight::from_parts(26_000_000, 4_600)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: BridgeMillauGrandpa ImportedHeaders (r:1 w:0)
+	// Proof: BridgeMillauGrandpa ImportedHeaders (max_values: Some(14400), max_size: Some(68), added: 2048, mode: MaxEncodedLen)
+	// Storage: XChain AllowedSources (r:1 w:0)
+	// Proof: XChain AllowedSources (max_values: None, max_size: Some(621), added: 3096, mode: MaxEncodedLen)
+	// Storage: XChain ReceivedNonces (r:1 w:1)
+	// Proof: XChain ReceivedNonces (max_values: None, max_size: Some(1135), added: 3610, mode: MaxEncodedLen)
+	// System Events (r:0 w:1)
+	/// The range of component `p` is `[64, 16384]`.
+	fn submit_result_proof(p: u32) -> Weight {
+		Weight::from_parts(62_000_000, 4_600)
+			.saturating_add(Weight::from_parts(2_600, 0).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: XChain AllowedSources (r:0 w:1)
+	// Proof: XChain AllowedSources (max_values: None, max_size: Some(621), added: 3096, mode: MaxEncodedLen)
+	fn allow_source() -> Weight {
+		Weight::from_parts(12_000_000, 0).saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: XChain AllowedSources (r:1 w:1)
+	// Proof: XChain AllowedSources (max_values: None, max_size: Some(621), added: 3096, mode: MaxEncodedLen)
+	fn disallow_source() -> Weight {
+		Weight::from_parts(15_000_000, 4_086)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: BridgeMillauMessages PalletOperatingMode (r:1 w:0)
+	// Proof: BridgeMillauMessages PalletOperatingMode (max_values: Some(1), max_size: Some(2), added: 497, mode: MaxEncodedLen)
+	// Storage: BridgeMillauMessages OutboundLanes (r:1 w:1)
+	// Proof: BridgeMillauMessages OutboundLanes (max_values: Some(1), max_size: Some(44), added: 539, mode: MaxEncodedLen)
+	// Storage: BridgeMillauMessages OutboundMessages (r:0 w:1)
+	// Proof: BridgeMillauMessages OutboundMessages (max_values: None, max_size: Some(2621472), added: 2623947, mode: MaxEncodedLen)
+	// System Events (r:0 w:1)
+	fn request_processing() -> Weight {
+		Weight::from_parts(45_000_000, 1_529)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: XChain AllowedSources (r:1 w:0)
+	// Proof: XChain AllowedSources (max_values: None, max_size: Some(621), added: 3096, mode: MaxEncodedLen)
+	// Storage: XChain ReceivedNonces (r:1 w:1)
+	// Proof: XChain ReceivedNonces (max_values: None, max_size: Some(1135), added: 3610, mode: MaxEncodedLen)
+	// System Events (r:0 w:1)
+	fn submit_result() -> Weight {
+		Weight::from_parts(26_000_000, 4_600)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: BridgeMillauGrandpa ImportedHeaders (r:1 w:0)
+	// Proof: BridgeMillauGrandpa ImportedHeaders (max_values: Some(14400), max_size: Some(68), added: 2048, mode: MaxEncodedLen)
+	// Storage: XChain AllowedSources (r:1 w:0)
+	// Proof: XChain AllowedSources (max_values: None, max_size: Some(621), added: 3096, mode: MaxEncodedLen)
+	// Storage: XChain ReceivedNonces (r:1 w:1)
+	// Proof: XChain ReceivedNonces (max_values: None, max_size: Some(1135), added: 3610, mode: MaxEncodedLen)
+	// System Events (r:0 w:1)
+	/// The range of component `p` is `[64, 16384]`.
+	fn submit_result_proof(p: u32) -> Weight {
+		Weight::from_parts(62_000_000, 4_600)
+			.saturating_add(Weight::from_parts(2_600, 0).saturating_mul(p.into()))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: XChain AllowedSources (r:0 w:1)
+	// Proof: XChain AllowedSources (max_values: None, max_size: Some(621), added: 3096, mode: MaxEncodedLen)
+	fn allow_source() -> Weight {
+		Weight::from_parts(12_000_000, 0).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: XChain AllowedSources (r:1 w:1)
+	// Proof: XChain AllowedSources (max_values: None, max_size: Some(621), added: 3096, mode: MaxEncodedLen)
+	fn disallow_source() -> Weight {
+		Weight::from_parts(15_000_000, 4_086)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	// Storage: BridgeMillauMessages PalletOperatingMode (r:1 w:0)
+	// Proof: BridgeMillauMessages PalletOperatingMode (max_values: Some(1), max_size: Some(2), added: 497, mode: MaxEncodedLen)
+	// Storage: BridgeMillauMessages OutboundLanes (r:1 w:1)
+	// Proof: BridgeMillauMessages OutboundLanes (max_values: Some(1), max_size: Some(44), added: 539, mode: MaxEncodedLen)
+	// Storage: BridgeMillauMessages OutboundMessages (r:0 w:1)
+	// Proof: BridgeMillauMessages OutboundMessages (max_values: None, max_size: Some(2621472), added: 2623947, mode: MaxEncodedLen)
+	// System Events (r:0 w:1)
+	fn request_processing() -> Weight {
+		Weight::from_parts(45_000_000, 1_529)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+}
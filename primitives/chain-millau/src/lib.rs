@@ -27,9 +27,11 @@ use bp_messages::{
 	ChainWithMessages, InboundMessageDetails, LaneId, MessageNonce, MessagePayload,
 	OutboundMessageDetails,
 };
-use bp_runtime::{decl_bridge_runtime_apis, Chain, ChainId};
+use bp_runtime::{decl_bridge_runtime_apis, Chain, ChainId, StorageMapKeyProvider};
+use codec::{Decode, Encode};
 use frame_support::{
 	dispatch::DispatchClass,
+	Blake2_128Concat,
 	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, IdentityFee, Weight},
 	RuntimeDebug,
 };
@@ -66,6 +68,12 @@ pub const TX_EXTRA_BYTES: u32 = 103;
 pub const MAXIMUM_BLOCK_WEIGHT: Weight =
 	Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND.saturating_div(2), u64::MAX);
 
+/// Weight of a single XCM instruction executed by Millau.
+pub const XCM_INSTRUCTION_WEIGHT: Weight = Weight::from_parts(1_000_000_000, 64 * 1024);
+
+/// The most a metadata request sent by another chain may consume when it is dispatched at Millau.
+pub const MAX_METADATA_REQUEST_WEIGHT: Weight = Weight::from_parts(200_000_000, 16 * 1024);
+
 /// Represents the portion of a block that will be used by Normal extrinsics.
 pub const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
 
@@ -265,5 +273,53 @@ pub const WITH_MILLAU_GRANDPA_PALLET_NAME: &str = "BridgeMillauGrandpa";
 pub const WITH_MILLAU_MESSAGES_PALLET_NAME: &str = "BridgeMillauMessages";
 /// Name of the transaction payment pallet at the Millau runtime.
 pub const TRANSACTION_PAYMENT_PALLET_NAME: &str = "TransactionPayment";
+/// Name of the logic provider pallet at the Millau runtime.
+pub const LOGIC_PROVIDER_PALLET_NAME: &str = "LogicProvider";
+/// Index of the logic provider pallet at the Millau runtime. Results are sent to the bridged
+/// chains from the `PalletInstance` of this index.
+pub const LOGIC_PROVIDER_PALLET_INDEX: u8 = 100;
+/// Index of the metadata requests pallet at the Millau runtime.
+pub const METADATA_REQUESTS_PALLET_INDEX: u8 = 104;
+
+/// `Millau` runtime `Call` enum.
+///
+/// The enum represents the subset of possible `Call`s that other chains send to `Millau`, so that
+/// they can be encoded without depending on the entire runtime. All entries must be kept in sync
+/// with the `Millau` `construct_runtime`, so that we maintain SCALE-compatibility.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub enum Call {
+	/// Metadata requests pallet.
+	#[codec(index = 104)]
+	MetadataRequests(MetadataRequestsCall),
+}
+
+/// Calls of the `MetadataRequests` pallet of `Millau`.
+#[allow(non_camel_case_types)]
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub enum MetadataRequestsCall {
+	/// `pallet_metadata_requests::Call::request_from_location`.
+	#[codec(index = 2)]
+	request_from_location {
+		/// Where the metadata should be fetched from.
+		source: Vec<u8>,
+		/// For how many Millau blocks the request stays open.
+		lifetime: BlockNumber,
+		/// The reward escrowed from the sovereign account of the requester.
+		reward: Balance,
+	},
+}
+
+/// Can be used to access the runtime storage key of the result sent for a metadata id.
+///
+/// The results are kept by the logic provider pallet in the `OutboundResults` ledger, along with
+/// the nonce they were sent under.
+pub struct OutboundResultsKeyProvider;
+impl StorageMapKeyProvider for OutboundResultsKeyProvider {
+	const MAP_NAME: &'static str = "OutboundResults";
+
+	type Hasher = Blake2_128Concat;
+	type Key = u64;
+	type Value = bin_types::OutboundResult;
+}
 
 decl_bridge_runtime_apis!(millau);
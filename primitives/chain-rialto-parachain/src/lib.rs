@@ -19,17 +19,20 @@
 #![warn(missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use bin_types::CrossChainResult;
 use bp_messages::{
 	ChainWithMessages, InboundMessageDetails, LaneId, MessageNonce, MessagePayload,
 	OutboundMessageDetails,
 };
 use bp_runtime::{decl_bridge_runtime_apis, Chain, ChainId, Parachain};
+use codec::{Decode, Encode};
 use frame_support::{
 	dispatch::DispatchClass,
 	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, IdentityFee, Weight},
 	RuntimeDebug, StateVersion,
 };
 use frame_system::limits;
+use scale_info::TypeInfo;
 use sp_core::Hasher as HasherT;
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentifyAccount, Verify},
@@ -61,9 +64,49 @@ pub const TX_EXTRA_BYTES: u32 = 104;
 pub const MAXIMUM_BLOCK_WEIGHT: Weight =
 	Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND.saturating_mul(2), 5 * 1024 * 1024);
 
+/// Weight of a single XCM instruction executed by RialtoParachain.
+pub const XCM_INSTRUCTION_WEIGHT: Weight = Weight::from_parts(1_000_000, 1024);
+
+/// The most the pallets notified of the results received by the x-chain pallet may consume, on
+/// top of the weight of `submit_result` itself.
+pub const MAX_RESULT_HANDLERS_WEIGHT: Weight = Weight::from_parts(100_000_000, 16 * 1024);
+
+/// The most `submit_result` of the x-chain pallet may weigh at RialtoParachain, including the
+/// write of the result to the shared state but without the notified pallets.
+pub const SUBMIT_RESULT_WEIGHT: Weight = Weight::from_parts(100_000_000, 24 * 1024);
+
+/// The weight RialtoParachain charges for dispatching `submit_result`, i.e. what senders have to
+/// allow for in the `Transact` carrying the call.
+pub const SUBMIT_RESULT_DISPATCH_WEIGHT: Weight =
+	SUBMIT_RESULT_WEIGHT.saturating_add(MAX_RESULT_HANDLERS_WEIGHT);
+
 /// Represents the portion of a block that will be used by Normal extrinsics.
 pub const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
 
+/// `RialtoParachain` runtime `Call` enum.
+///
+/// The enum represents the subset of possible `Call`s that other chains send to `RialtoParachain`,
+/// so that they can be encoded without depending on the entire runtime. All entries must be kept in
+/// sync with the `RialtoParachain` `construct_runtime`, so that we maintain SCALE-compatibility.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub enum Call {
+	/// Cross-chain results pallet.
+	#[codec(index = 200)]
+	XChain(XChainCall),
+}
+
+/// Calls of the `XChain` pallet of `RialtoParachain`.
+#[allow(non_camel_case_types)]
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub enum XChainCall {
+	/// `pallet_x_chain::Call::submit_result`.
+	#[codec(index = 0)]
+	submit_result {
+		/// The result to store in the shared state.
+		result: CrossChainResult,
+	},
+}
+
 /// Maximal number of unrewarded relayer entries in Rialto confirmation transaction.
 pub const MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX: MessageNonce = 1024;
 